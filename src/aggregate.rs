@@ -0,0 +1,113 @@
+// src/aggregate.rs
+//! Key-based roll-up of reconstructed records
+//!
+//! Merges all records sharing a key into one, e.g. the line items of an
+//! invoice into a single row with a combined description.
+
+use crate::engine::{clean_and_normalize_field, ReconstructedRow};
+use csv::StringRecord;
+use std::collections::HashMap;
+
+/// Merge records that share the value of column `key`
+///
+/// Produces one record per distinct key (compared after whitespace
+/// normalization), in order of first occurrence. Columns in `concat_columns`
+/// hold every non-empty value of the group joined by `separator`; all other
+/// columns are taken from the first record. Header rows pass through, and
+/// keys are only merged within the same section.
+pub fn group_rows(
+    rows: Vec<ReconstructedRow>,
+    key: usize,
+    concat_columns: &[usize],
+    separator: &str,
+) -> Vec<ReconstructedRow> {
+    let mut grouped: Vec<ReconstructedRow> = Vec::new();
+    let mut concatenated: Vec<Vec<Vec<String>>> = Vec::new();
+    let mut positions: HashMap<(usize, String), usize> = HashMap::new();
+
+    for row in rows {
+        if row.header {
+            grouped.push(row);
+            concatenated.push(Vec::new());
+            continue;
+        }
+
+        let group_key = (row.section, clean_and_normalize_field(row.record.get(key).unwrap_or("")));
+        let values: Vec<String> = concat_columns
+            .iter()
+            .map(|&column| row.record.get(column).unwrap_or("").to_string())
+            .collect();
+
+        match positions.get(&group_key) {
+            Some(&index) => {
+                let first = &mut grouped[index];
+                first.fixed |= row.fixed;
+                first.source_lines.extend(row.source_lines);
+                for (parts, value) in concatenated[index].iter_mut().zip(values) {
+                    parts.push(value);
+                }
+            }
+            None => {
+                positions.insert(group_key, grouped.len());
+                grouped.push(row);
+                concatenated.push(values.into_iter().map(|value| vec![value]).collect());
+            }
+        }
+    }
+
+    for (row, parts) in grouped.iter_mut().zip(concatenated) {
+        if row.header || parts.iter().all(|values| values.len() < 2) {
+            continue;
+        }
+        let mut fields: Vec<String> = row.record.iter().map(str::to_string).collect();
+        for (&column, values) in concat_columns.iter().zip(parts) {
+            if let Some(field) = fields.get_mut(column) {
+                let non_empty: Vec<&str> = values.iter().map(String::as_str).filter(|v| !v.trim().is_empty()).collect();
+                *field = non_empty.join(separator);
+            }
+        }
+        row.record = StringRecord::from(fields);
+    }
+
+    grouped
+}
+
+// ============================================
+// Unit Tests
+// ============================================
+
+#[cfg(test)]
+mod tests {
+    use crate::config::ReconstructConfig;
+    use crate::engine::{reconstruct_rows_from_reader, Stats};
+
+    #[test]
+    fn test_group_by_invoice_concatenates_descriptions() {
+        let content = "Invoice,Description,Total\n\
+                       INV-1,Water,100\n\
+                       INV-2,Juice,50\n\
+                       INV-1,Bottles\nand caps,100\n\
+                       INV-1,Delivery,100\n";
+        let config = ReconstructConfig {
+            group_by: Some(0),
+            concat_columns: vec![1],
+            concat_separator: " | ".to_string(),
+            ..ReconstructConfig::default()
+        };
+
+        let mut stats = Stats::default();
+        let rows = reconstruct_rows_from_reader(content.as_bytes(), &config, &mut stats).unwrap();
+
+        let records: Vec<Vec<&str>> = rows.iter().map(|row| row.record.iter().collect()).collect();
+        assert_eq!(
+            records,
+            vec![
+                vec!["Invoice", "Description", "Total"],
+                vec!["INV-1", "Water | Bottles\nand caps | Delivery", "100"],
+                vec!["INV-2", "Juice", "50"],
+            ]
+        );
+        assert!(rows[1].fixed);
+        assert_eq!(rows[1].source_lines, vec![2, 4, 5, 6]);
+    }
+}
@@ -0,0 +1,203 @@
+// src/audit.rs
+//! Transformation audit log
+//!
+//! Records every field whose written value differs from its reconstructed
+//! value, so reviewers can see exactly what normalization changed.
+
+use crate::config::ReconstructConfig;
+use crate::engine::ReconstructedRow;
+use crate::error::FixerrError;
+use crate::infer::{infer_column_types, ColumnType, DEFAULT_INFERENCE_SAMPLE};
+use crate::output::prepare_field;
+use csv::WriterBuilder;
+
+/// Column names of the audit log
+pub const AUDIT_LOG_HEADER: [&str; 6] = [
+    "out_row",
+    "src_lines",
+    "column",
+    "original_value",
+    "normalized_value",
+    "confidence",
+];
+
+/// How likely a change is to be correct
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChangeConfidence {
+    /// Only whitespace within a single line changed
+    High,
+    /// Line breaks in a text value were flattened
+    Medium,
+    /// Line breaks were flattened in a numeric value or column, which
+    /// usually means a fragment was stitched into the wrong place
+    Low,
+}
+
+impl ChangeConfidence {
+    /// Name used in the audit log
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ChangeConfidence::High => "high",
+            ChangeConfidence::Medium => "medium",
+            ChangeConfidence::Low => "low",
+        }
+    }
+}
+
+/// A single altered field
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AuditEntry {
+    /// 1-based position of the record in the reconstructed output (header = 1)
+    pub out_row: usize,
+    /// Source line span of the record, e.g. `4` or `4-6`
+    pub src_lines: String,
+    /// 0-based column index
+    pub column: usize,
+    /// Value after reconstruction, before normalization
+    pub original_value: String,
+    /// Value as written to the output
+    pub normalized_value: String,
+    /// How likely the change is to be correct
+    pub confidence: ChangeConfidence,
+}
+
+/// Collect an audit entry for every field changed by normalization
+///
+/// Unchanged fields are omitted to keep the log small. Column types used for
+/// the confidence rating are inferred from the intact (unrepaired) data rows.
+pub fn collect_audit_entries(rows: &[ReconstructedRow], config: &ReconstructConfig) -> Vec<AuditEntry> {
+    let intact: Vec<_> = rows
+        .iter()
+        .filter(|row| !row.header && !row.fixed)
+        .map(|row| row.record.clone())
+        .collect();
+    let column_types = infer_column_types(&intact, DEFAULT_INFERENCE_SAMPLE);
+    let mut entries = Vec::new();
+
+    for (index, row) in rows.iter().enumerate() {
+        for (column, original) in row.record.iter().enumerate() {
+            let normalized = prepare_field(original, config);
+            if normalized == original {
+                continue;
+            }
+            entries.push(AuditEntry {
+                out_row: index + 1,
+                src_lines: format_line_span(&row.source_lines),
+                column,
+                original_value: original.to_string(),
+                confidence: rate_change(original, &normalized, column_types.get(column).copied()),
+                normalized_value: normalized,
+            });
+        }
+    }
+
+    entries
+}
+
+/// Write the audit log as CSV, returning the number of entries written
+pub fn write_audit_log(
+    path: &str,
+    rows: &[ReconstructedRow],
+    config: &ReconstructConfig,
+) -> Result<usize, FixerrError> {
+    let entries = collect_audit_entries(rows, config);
+    let mut writer = WriterBuilder::new().from_path(path)?;
+
+    writer.write_record(AUDIT_LOG_HEADER)?;
+    for entry in &entries {
+        writer.write_record([
+            entry.out_row.to_string().as_str(),
+            entry.src_lines.as_str(),
+            entry.column.to_string().as_str(),
+            entry.original_value.as_str(),
+            entry.normalized_value.as_str(),
+            entry.confidence.as_str(),
+        ])?;
+    }
+
+    writer.flush()?;
+    Ok(entries.len())
+}
+
+// ============================================
+// Private Helper Functions
+// ============================================
+
+fn rate_change(original: &str, normalized: &str, column_type: Option<ColumnType>) -> ChangeConfidence {
+    if !original.contains(['\n', '\r']) {
+        return ChangeConfidence::High;
+    }
+    let numeric = |t: ColumnType| matches!(t, ColumnType::Integer | ColumnType::Decimal);
+    if numeric(ColumnType::of_value(normalized)) || column_type.map_or(false, numeric) {
+        ChangeConfidence::Low
+    } else {
+        ChangeConfidence::Medium
+    }
+}
+
+fn format_line_span(lines: &[u64]) -> String {
+    match (lines.first(), lines.last()) {
+        (Some(first), Some(last)) if first != last => format!("{first}-{last}"),
+        (Some(first), _) => first.to_string(),
+        _ => String::new(),
+    }
+}
+
+// ============================================
+// Unit Tests
+// ============================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::{reconstruct_rows_with_config, Stats};
+    use std::fs;
+
+    #[test]
+    fn test_audit_log_records_only_changed_fields() {
+        let filename = "test_audit_input.csv";
+        let content = "ID,Details,Amount\n1,Clean,10\n2,Split\nacross,20";
+        fs::write(filename, content).unwrap();
+
+        let config = ReconstructConfig::default();
+        let mut stats = Stats::default();
+        let rows = reconstruct_rows_with_config(filename, &config, &mut stats).unwrap();
+        let _ = fs::remove_file(filename);
+
+        let entries = collect_audit_entries(&rows, &config);
+        assert_eq!(
+            entries,
+            vec![AuditEntry {
+                out_row: 3,
+                src_lines: "3-4".to_string(),
+                column: 1,
+                original_value: "Split\nacross".to_string(),
+                normalized_value: "Split across".to_string(),
+                confidence: ChangeConfidence::Medium,
+            }]
+        );
+
+        let log_path = "test_audit_log.csv";
+        let count = write_audit_log(log_path, &rows, &config).unwrap();
+        let log = fs::read_to_string(log_path).unwrap();
+        let _ = fs::remove_file(log_path);
+
+        assert_eq!(count, 1);
+        assert_eq!(
+            log,
+            "out_row,src_lines,column,original_value,normalized_value,confidence\n3,3-4,1,\"Split\nacross\",Split across,medium\n"
+        );
+    }
+
+    #[test]
+    fn test_confidence_whitespace_high_numeric_stitch_low() {
+        let content = "ID,Name,Amount,Note\n1,  Tbilisi Waters ,10,a\n2,Gori,1722\n.63,b\n3,Kutaisi,30,c\n";
+        let config = ReconstructConfig::default();
+        let mut stats = Stats::default();
+        let rows = crate::engine::reconstruct_rows_from_reader(content.as_bytes(), &config, &mut stats).unwrap();
+
+        let entries = collect_audit_entries(&rows, &config);
+        let ratings: Vec<(usize, ChangeConfidence)> = entries.iter().map(|e| (e.out_row, e.confidence)).collect();
+        assert_eq!(ratings, vec![(2, ChangeConfidence::High), (3, ChangeConfidence::Low)]);
+    }
+}
@@ -0,0 +1,346 @@
+// src/batch.rs
+//! Directory batch processing
+//!
+//! Repairs every CSV file of a directory in one go, for teams that receive
+//! many exports at once. By default a failing file is recorded and skipped
+//! so the rest of the batch still completes; [`ErrorPolicy::Abort`] stops at
+//! the first one instead.
+
+use crate::config::ReconstructConfig;
+use crate::detect::detect_delimiter;
+use crate::engine::{
+    open_input, reconstruct_records_with_config, write_output_csv, ColumnCountStrategy, Delimiter, HeaderMode, NormalizeMode, Stats,
+};
+use crate::error::FixerrError;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+/// What a batch does when a file fails
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorPolicy {
+    /// Stop at the first failing file, e.g. for a CI gate
+    Abort,
+    /// Record the failure and repair the remaining files (default)
+    #[default]
+    Continue,
+}
+
+impl FromStr for ErrorPolicy {
+    type Err = String;
+
+    /// Parse `abort` or `continue`, ignoring case and surrounding whitespace
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "abort" => Ok(ErrorPolicy::Abort),
+            "continue" => Ok(ErrorPolicy::Continue),
+            _ => Err(format!("unknown error policy '{}' (expected abort or continue)", s.trim())),
+        }
+    }
+}
+
+/// Outcome of one successfully repaired file
+#[derive(Debug)]
+pub struct FileReport {
+    /// The input file
+    pub input: PathBuf,
+    /// The written output file
+    pub output: PathBuf,
+    /// Records written (header included)
+    pub records: usize,
+    /// Statistics of this file alone
+    pub stats: Stats,
+}
+
+/// Outcome of [`process_directory`]
+#[derive(Debug, Default)]
+pub struct DirectoryReport {
+    /// Files repaired, in file name order
+    pub files: Vec<FileReport>,
+    /// Files that failed, in file name order, with the reason
+    pub failures: Vec<(PathBuf, FixerrError)>,
+    /// Statistics summed over all repaired files
+    pub totals: Stats,
+}
+
+impl DirectoryReport {
+    /// Records written over all repaired files
+    pub fn total_records(&self) -> usize {
+        self.files.iter().map(|file| file.records).sum()
+    }
+}
+
+/// Repair every `*.csv` file in `input_dir`, writing each to `output_dir`
+///
+/// Output files keep their input file names. `output_dir` is created if
+/// missing and must differ from `input_dir`. Other files and subdirectories
+/// are skipped. With `Delimiter::Auto` the delimiter is detected per file
+/// and also used for its output; with `NoHeaders` each file's column count is
+/// found by majority vote. A file that cannot be read, repaired or written
+/// is listed in `failures` and the batch continues.
+pub fn process_directory(
+    input_dir: &str,
+    output_dir: &str,
+    header_mode: HeaderMode,
+    delimiter: Delimiter,
+) -> Result<DirectoryReport, FixerrError> {
+    process_directory_with_policy(input_dir, output_dir, header_mode, delimiter, ErrorPolicy::Continue)
+}
+
+/// [`process_directory`] with a choice of what happens when a file fails
+///
+/// With [`ErrorPolicy::Abort`] the batch stops at the first failing file:
+/// the report lists the files repaired before it and that one failure, and
+/// later files are left untouched.
+pub fn process_directory_with_policy(
+    input_dir: &str,
+    output_dir: &str,
+    header_mode: HeaderMode,
+    delimiter: Delimiter,
+    on_error: ErrorPolicy,
+) -> Result<DirectoryReport, FixerrError> {
+    let mut results = Vec::new();
+    for input in list_inputs(input_dir, output_dir)? {
+        let result = process_entry(input, output_dir, header_mode, delimiter);
+        let failed = result.2.is_err();
+        results.push(result);
+        if failed && on_error == ErrorPolicy::Abort {
+            break;
+        }
+    }
+    Ok(build_report(results))
+}
+
+/// [`process_directory`] with several files repaired at once
+///
+/// Each file is still reconstructed sequentially, since stitching depends on
+/// the rows before it; only separate files run concurrently, on rayon's
+/// global thread pool. The report is the same as the serial one.
+#[cfg(feature = "parallel")]
+pub fn process_directory_parallel(
+    input_dir: &str,
+    output_dir: &str,
+    header_mode: HeaderMode,
+    delimiter: Delimiter,
+) -> Result<DirectoryReport, FixerrError> {
+    process_directory_parallel_with_policy(input_dir, output_dir, header_mode, delimiter, ErrorPolicy::Continue)
+}
+
+/// [`process_directory_parallel`] with a choice of what happens when a file fails
+///
+/// With [`ErrorPolicy::Abort`] no further files are started after the first
+/// failure, but files already running are finished and reported, so which
+/// files complete depends on scheduling.
+#[cfg(feature = "parallel")]
+pub fn process_directory_parallel_with_policy(
+    input_dir: &str,
+    output_dir: &str,
+    header_mode: HeaderMode,
+    delimiter: Delimiter,
+    on_error: ErrorPolicy,
+) -> Result<DirectoryReport, FixerrError> {
+    use rayon::prelude::*;
+    use std::sync::atomic::{AtomicBool, Ordering};
+
+    let aborted = AtomicBool::new(false);
+    let results: Vec<Option<EntryResult>> = list_inputs(input_dir, output_dir)?
+        .into_par_iter()
+        .map(|input| {
+            if aborted.load(Ordering::Relaxed) {
+                return None;
+            }
+            let result = process_entry(input, output_dir, header_mode, delimiter);
+            if result.2.is_err() && on_error == ErrorPolicy::Abort {
+                aborted.store(true, Ordering::Relaxed);
+            }
+            Some(result)
+        })
+        .collect();
+    Ok(build_report(results.into_iter().flatten().collect()))
+}
+
+// ============================================
+// Private Helper Functions
+// ============================================
+
+/// Outcome of one input file: its output path and records and stats, or the error
+type EntryResult = (PathBuf, PathBuf, Result<(usize, Stats), FixerrError>);
+
+/// The `*.csv` files of `input_dir` in file name order, after checking the
+/// output directory
+fn list_inputs(input_dir: &str, output_dir: &str) -> Result<Vec<PathBuf>, FixerrError> {
+    fs::create_dir_all(output_dir)?;
+    if fs::canonicalize(input_dir)? == fs::canonicalize(output_dir)? {
+        return Err(FixerrError::InvalidConfig(
+            "output directory must differ from the input directory".to_string(),
+        ));
+    }
+
+    let mut inputs: Vec<PathBuf> = Vec::new();
+    for entry in fs::read_dir(input_dir)? {
+        let path = entry?.path();
+        let is_csv = path.extension().and_then(|e| e.to_str()).map_or(false, |e| e.eq_ignore_ascii_case("csv"));
+        if is_csv && path.is_file() {
+            inputs.push(path);
+        }
+    }
+    inputs.sort();
+    Ok(inputs)
+}
+
+/// Repair `input` into `output_dir` under its own file name
+fn process_entry(input: PathBuf, output_dir: &str, header_mode: HeaderMode, delimiter: Delimiter) -> EntryResult {
+    let output = Path::new(output_dir).join(input.file_name().unwrap_or_default());
+    let result = process_file(&input, &output, header_mode, delimiter);
+    (input, output, result)
+}
+
+/// Collect per-file results, in order, into a report with summed totals
+fn build_report(results: Vec<EntryResult>) -> DirectoryReport {
+    let mut report = DirectoryReport::default();
+    for (input, output, result) in results {
+        match result {
+            Ok((records, stats)) => {
+                report.totals.absorb(stats.clone());
+                report.files.push(FileReport { input, output, records, stats });
+            }
+            Err(e) => report.failures.push((input, e)),
+        }
+    }
+    report
+}
+
+/// Repair one file, returning the number of records written and its stats
+fn process_file(
+    input: &Path,
+    output: &Path,
+    header_mode: HeaderMode,
+    delimiter: Delimiter,
+) -> Result<(usize, Stats), FixerrError> {
+    let input_path = input.to_string_lossy();
+    let delimiter = match delimiter {
+        Delimiter::Auto => detect_delimiter(open_input(&input_path, &ReconstructConfig::default())?)?,
+        delimiter => delimiter,
+    };
+
+    let mut config = ReconstructConfig::new(header_mode, delimiter);
+    if matches!(header_mode, HeaderMode::NoHeaders) {
+        config.column_count_strategy = ColumnCountStrategy::MajorityVote;
+    }
+
+    let mut stats = Stats::default();
+    let records = reconstruct_records_with_config(&input_path, &config, &mut stats)?;
+    write_output_csv(&output.to_string_lossy(), &records, delimiter, NormalizeMode::Collapse)?;
+    Ok((records.len(), stats))
+}
+
+// ============================================
+// Unit Tests
+// ============================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_process_directory_repairs_csv_files_and_records_failures() {
+        let input_dir = "test_batch_input";
+        let output_dir = "test_batch_output";
+        let _ = fs::remove_dir_all(input_dir);
+        let _ = fs::remove_dir_all(output_dir);
+        fs::create_dir_all(input_dir).unwrap();
+        fs::write(format!("{input_dir}/a.csv"), "ID,Name,Amount\n1,Tbilisi\nWaters,10\n2,Gori,20\n").unwrap();
+        fs::write(format!("{input_dir}/b.CSV"), "ID,Name,Amount\n3,Mestia,30\n").unwrap();
+        fs::write(format!("{input_dir}/broken.csv"), b"ID,Name,Amount\n1,\xff\xfe,10\n").unwrap();
+        fs::write(format!("{input_dir}/notes.txt"), "not,a,csv\n").unwrap();
+
+        let report = process_directory(input_dir, output_dir, HeaderMode::HasHeaders, Delimiter::Comma).unwrap();
+        let repaired = fs::read_to_string(format!("{output_dir}/a.csv")).unwrap();
+        let skipped = Path::new(&format!("{output_dir}/notes.txt")).exists();
+        fs::remove_dir_all(input_dir).unwrap();
+        fs::remove_dir_all(output_dir).unwrap();
+
+        assert_eq!(repaired, "ID,Name,Amount\n1,Tbilisi Waters,10\n2,Gori,20\n");
+        assert!(!skipped);
+        let names: Vec<_> = report.files.iter().map(|file| file.input.file_name().unwrap().to_owned()).collect();
+        assert_eq!(names, ["a.csv", "b.CSV"]);
+        assert_eq!(report.failures.len(), 1);
+        assert!(report.failures[0].0.ends_with("broken.csv"));
+        assert_eq!(report.total_records(), 5);
+        assert_eq!(report.totals.fixed_rows, 1);
+    }
+
+    #[test]
+    fn test_error_policy_abort_stops_at_first_failure() {
+        let input_dir = "test_policy_input";
+        let _ = fs::remove_dir_all(input_dir);
+        fs::create_dir_all(input_dir).unwrap();
+        fs::write(format!("{input_dir}/a.csv"), "ID,Name\n1,Tbilisi\n").unwrap();
+        fs::write(format!("{input_dir}/b.csv"), b"ID,Name\n1,\xff\xfe\n").unwrap();
+        fs::write(format!("{input_dir}/c.csv"), "ID,Name\n2,Gori\n").unwrap();
+
+        let run = |output_dir: &str, on_error: ErrorPolicy| {
+            let report = process_directory_with_policy(
+                input_dir,
+                output_dir,
+                HeaderMode::HasHeaders,
+                Delimiter::Comma,
+                on_error,
+            )
+            .unwrap();
+            let wrote_last = Path::new(output_dir).join("c.csv").exists();
+            fs::remove_dir_all(output_dir).unwrap();
+            (report, wrote_last)
+        };
+        let (continued, continued_last) = run("test_policy_continue", ErrorPolicy::Continue);
+        let (aborted, aborted_last) = run("test_policy_abort", ErrorPolicy::Abort);
+        fs::remove_dir_all(input_dir).unwrap();
+
+        let names = |report: &DirectoryReport| -> Vec<PathBuf> {
+            report.files.iter().map(|file| file.input.file_name().unwrap().into()).collect()
+        };
+        assert_eq!(names(&continued), [PathBuf::from("a.csv"), PathBuf::from("c.csv")]);
+        assert!(continued_last);
+        assert_eq!(names(&aborted), [PathBuf::from("a.csv")]);
+        assert!(!aborted_last);
+        assert_eq!((continued.failures.len(), aborted.failures.len()), (1, 1));
+        assert!(aborted.failures[0].0.ends_with("b.csv"));
+        assert_eq!("Abort".parse(), Ok(ErrorPolicy::Abort));
+        assert!("stop".parse::<ErrorPolicy>().is_err());
+    }
+
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn test_parallel_directory_matches_serial() {
+        let input_dir = "test_parallel_input";
+        let _ = fs::remove_dir_all(input_dir);
+        fs::create_dir_all(input_dir).unwrap();
+        for i in 0..8 {
+            let content = format!("ID,Name,Amount\n{i},Tbilisi\nWaters,10\n{i},Gori,20\n{i},x,y,z\n");
+            fs::write(format!("{input_dir}/file_{i}.csv"), content).unwrap();
+        }
+        fs::write(format!("{input_dir}/broken.csv"), b"ID,Name,Amount\n1,\xff\xfe,10\n").unwrap();
+
+        let serial = process_directory(input_dir, "test_parallel_serial", HeaderMode::HasHeaders, Delimiter::Comma);
+        let parallel =
+            process_directory_parallel(input_dir, "test_parallel_output", HeaderMode::HasHeaders, Delimiter::Comma);
+        let repaired = fs::read_to_string("test_parallel_output/file_3.csv").unwrap();
+        for dir in [input_dir, "test_parallel_serial", "test_parallel_output"] {
+            fs::remove_dir_all(dir).unwrap();
+        }
+        let (serial, parallel) = (serial.unwrap(), parallel.unwrap());
+
+        assert_eq!(repaired, "ID,Name,Amount\n3,Tbilisi Waters,10\n3,Gori,20\n");
+        let names = |report: &DirectoryReport| -> Vec<PathBuf> {
+            report.files.iter().map(|file| file.input.clone()).collect()
+        };
+        assert_eq!(names(&parallel), names(&serial));
+        assert_eq!(parallel.failures.len(), 1);
+        assert_eq!(parallel.total_records(), 24);
+        assert_eq!(
+            (parallel.totals.total_rows, parallel.totals.fixed_rows, parallel.totals.removed_rows),
+            (serial.totals.total_rows, serial.totals.fixed_rows, serial.totals.removed_rows)
+        );
+        assert_eq!(parallel.totals.fixed_rows, 8);
+    }
+}
@@ -0,0 +1,436 @@
+// src/cli.rs
+//! Non-interactive command-line mode
+//!
+//! Runs a single repair, or a batch over a directory, from flags and exits
+//! with a status code, so Fixerr can be scheduled (e.g. from cron) without a
+//! terminal.
+
+use crate::MIN_COLUMN_VOTE_CONFIDENCE;
+use fixerr::{
+    detect_delimiter, open_input, process_directory_with_policy, reconstruct_pipeline, reconstruct_records_with_config,
+    vote_column_count, write_output_csv, ColumnCountStrategy, Delimiter, ErrorPolicy, HeaderMode, NormalizeMode,
+    OverflowStrategy, ReconstructConfig, Stats,
+};
+use std::error::Error;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Usage text printed by `--help` and after argument errors
+pub const USAGE: &str = "\
+Usage: fixerr --input <FILE> --output <FILE> [OPTIONS]
+       fixerr --input-dir <DIR> --output-dir <DIR> [OPTIONS]
+       fixerr --repl
+       fixerr                      (interactive menu)
+
+Options:
+  -i, --input <FILE>       CSV file to repair
+  -o, --output <FILE>      Where to write the repaired CSV
+      --input-dir <DIR>    Repair every *.csv file of a directory
+      --output-dir <DIR>   Where to write the files repaired from --input-dir
+      --on-error <MODE>    With --input-dir: abort at the first failing file
+                           or continue with the rest (default: continue);
+                           any failure gives a non-zero exit status
+  -d, --delimiter <DELIM>  comma, semicolon, tab, pipe, auto, a character
+                           or a byte such as 0x1F (default: comma)
+      --no-headers         The first row is data, not a header
+  -c, --columns <N>        Column count of a headerless file (default:
+                           detected by majority vote)
+      --columns-from <FILE>
+                           Take the column count from the first row of a
+                           reference file with the right structure
+      --split-overlong     Split a row exactly N times too wide into N
+                           records instead of discarding it
+      --stage <FILE>       Repair in passes, one per stage file, each
+                           reading the previous pass's output; a stage
+                           file adjusts the options above (repeatable)
+  -h, --help               Show this help";
+
+/// What the command line asks for
+#[derive(Debug, Clone, PartialEq)]
+pub enum Command {
+    /// Print the usage text
+    Help,
+    /// Repair one file
+    Run(RunArgs),
+    /// Repair every CSV file of a directory
+    Batch(BatchArgs),
+}
+
+/// Options of a non-interactive repair
+#[derive(Debug, Clone, PartialEq)]
+pub struct RunArgs {
+    pub input: String,
+    pub output: String,
+    pub delimiter: Delimiter,
+    pub header_mode: HeaderMode,
+    pub columns: Option<usize>,
+    pub columns_from: Option<PathBuf>,
+    pub overflow_strategy: OverflowStrategy,
+    pub stages: Vec<PathBuf>,
+}
+
+/// Options of a non-interactive directory repair
+#[derive(Debug, Clone, PartialEq)]
+pub struct BatchArgs {
+    pub input_dir: String,
+    pub output_dir: String,
+    pub delimiter: Delimiter,
+    pub header_mode: HeaderMode,
+    pub on_error: ErrorPolicy,
+}
+
+/// Parse the arguments after the program name
+pub fn parse<I: IntoIterator<Item = String>>(args: I) -> Result<Command, String> {
+    let mut input = None;
+    let mut output = None;
+    let mut delimiter = Delimiter::Comma;
+    let mut header_mode = HeaderMode::HasHeaders;
+    let mut columns = None;
+    let mut columns_from = None;
+    let mut overflow_strategy = OverflowStrategy::Discard;
+    let mut stages = Vec::new();
+    let mut input_dir = None;
+    let mut output_dir = None;
+    let mut on_error = None;
+
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        // Accept both `--flag value` and `--flag=value`
+        let (flag, inline) = match arg.split_once('=') {
+            Some((flag, value)) if flag.starts_with("--") => (flag.to_string(), Some(value.to_string())),
+            _ => (arg, None),
+        };
+        let mut value = |name: &str| inline.clone().or_else(|| args.next()).ok_or(format!("{name} needs a value"));
+
+        match flag.as_str() {
+            "-h" | "--help" => return Ok(Command::Help),
+            "-i" | "--input" => input = Some(value("--input")?),
+            "-o" | "--output" => output = Some(value("--output")?),
+            "--input-dir" => input_dir = Some(value("--input-dir")?),
+            "--output-dir" => output_dir = Some(value("--output-dir")?),
+            "--on-error" => on_error = Some(value("--on-error")?.parse::<ErrorPolicy>()?),
+            "-d" | "--delimiter" => delimiter = value("--delimiter")?.parse()?,
+            "--no-headers" => header_mode = HeaderMode::NoHeaders,
+            "--columns-from" => columns_from = Some(PathBuf::from(value("--columns-from")?)),
+            "--split-overlong" => overflow_strategy = OverflowStrategy::SplitAtExpected,
+            "--stage" => stages.push(PathBuf::from(value("--stage")?)),
+            "-c" | "--columns" => {
+                let count = value("--columns")?;
+                columns = match count.parse::<usize>() {
+                    Ok(n) if n > 0 => Some(n),
+                    _ => return Err(format!("invalid column count '{count}'")),
+                };
+            }
+            other => return Err(format!("unknown argument '{other}'")),
+        }
+    }
+
+    if input_dir.is_some() || output_dir.is_some() {
+        if input.is_some() || output.is_some() {
+            return Err("--input/--output and --input-dir/--output-dir are exclusive".to_string());
+        }
+        let single_file_only = columns.is_some() || columns_from.is_some() || !stages.is_empty();
+        if single_file_only || overflow_strategy != OverflowStrategy::Discard {
+            return Err("--columns, --columns-from, --split-overlong and --stage only apply to one file".to_string());
+        }
+        let input_dir = input_dir.ok_or("--input-dir is required with --output-dir")?;
+        let output_dir = output_dir.ok_or("--output-dir is required with --input-dir")?;
+        let on_error = on_error.unwrap_or_default();
+        return Ok(Command::Batch(BatchArgs { input_dir, output_dir, delimiter, header_mode, on_error }));
+    }
+    if on_error.is_some() {
+        return Err("--on-error only applies with --input-dir".to_string());
+    }
+
+    let input = input.ok_or("--input is required")?;
+    let output = output.ok_or("--output is required")?;
+    if columns.is_some() && header_mode.as_bool() {
+        return Err("--columns only applies with --no-headers".to_string());
+    }
+    if columns.is_some() && columns_from.is_some() {
+        return Err("--columns and --columns-from are exclusive".to_string());
+    }
+    Ok(Command::Run(RunArgs {
+        input,
+        output,
+        delimiter,
+        header_mode,
+        columns,
+        columns_from,
+        overflow_strategy,
+        stages,
+    }))
+}
+
+/// Repair one file as described by `args`, reporting on stdout and stderr
+pub fn run(args: &RunArgs) -> Result<(), Box<dyn Error>> {
+    let delimiter = match args.delimiter {
+        Delimiter::Auto => detect_delimiter(open_input(&args.input, &ReconstructConfig::default())?)?,
+        delimiter => delimiter,
+    };
+
+    let mut config = ReconstructConfig::new(args.header_mode, delimiter);
+    config.overflow_strategy = args.overflow_strategy;
+    if let Some(reference) = &args.columns_from {
+        config.column_count_strategy = ColumnCountStrategy::FromReferenceFile(reference.clone());
+    } else if !args.header_mode.as_bool() {
+        config.expected_columns = Some(match args.columns {
+            Some(columns) => columns,
+            None => {
+                let vote = vote_column_count(open_input(&args.input, &config)?, HeaderMode::NoHeaders, delimiter)?;
+                if vote.confidence < MIN_COLUMN_VOTE_CONFIDENCE {
+                    return Err(format!(
+                        "column count is unclear ({} columns in {:.0}% of rows); pass --columns",
+                        vote.columns,
+                        vote.confidence * 100.0
+                    )
+                    .into());
+                }
+                vote.columns
+            }
+        });
+    }
+
+    let mut stats = Stats::default();
+    let (records, delimiter, normalize_mode) = if args.stages.is_empty() {
+        (reconstruct_records_with_config(&args.input, &config, &mut stats)?, delimiter, NormalizeMode::Collapse)
+    } else {
+        let stages = args.stages.iter().map(|path| load_stage(path, &config)).collect::<Result<Vec<_>, _>>()?;
+        let rows = reconstruct_pipeline(&args.input, &stages, &mut stats)?;
+        let last = &stages[stages.len() - 1];
+        (rows.into_iter().map(|row| row.record).collect(), last.delimiter, last.normalize_mode)
+    };
+    write_output_csv(&args.output, &records, delimiter, normalize_mode)?;
+
+    for warning in &stats.warnings {
+        eprintln!("warning: {warning}");
+    }
+    println!(
+        "{}: {} records written to {} ({} fixed, {} discarded)",
+        args.input,
+        records.len(),
+        args.output,
+        stats.fixed_rows,
+        stats.removed_rows
+    );
+    Ok(())
+}
+
+/// Repair a directory as described by `args`, failing if any file failed
+pub fn run_batch(args: &BatchArgs) -> Result<(), Box<dyn Error>> {
+    let report =
+        process_directory_with_policy(&args.input_dir, &args.output_dir, args.header_mode, args.delimiter, args.on_error)?;
+
+    for file in &report.files {
+        println!(
+            "{}: {} records ({} fixed, {} discarded)",
+            file.input.display(),
+            file.records,
+            file.stats.fixed_rows,
+            file.stats.removed_rows
+        );
+    }
+    for (input, error) in &report.failures {
+        eprintln!("{}: {error}", input.display());
+    }
+
+    match (report.failures.len(), args.on_error) {
+        (0, _) => Ok(()),
+        (_, ErrorPolicy::Abort) => Err("stopped at the first failing file".into()),
+        (failed, ErrorPolicy::Continue) => {
+            Err(format!("{failed} of {} files failed", report.files.len() + failed).into())
+        }
+    }
+}
+
+/// Read a stage file, applying its settings on top of `base`
+///
+/// Each setting is one `key = value` line; blank lines and lines starting
+/// with `#` are skipped. The keys are `delimiter`, `headers` (true/false),
+/// `columns`, `split-overlong` (true/false) and `normalize` (collapse,
+/// preserve-newlines or trim-only). Only the final stage's `normalize` and
+/// `delimiter` shape the written file.
+fn load_stage(path: &Path, base: &ReconstructConfig) -> Result<ReconstructConfig, String> {
+    let content = fs::read_to_string(path).map_err(|e| format!("cannot read stage {}: {e}", path.display()))?;
+    let invalid = |line: &str| format!("invalid setting in stage {}: '{line}'", path.display());
+    let flag = |value: &str, line: &str| match value {
+        "true" => Ok(true),
+        "false" => Ok(false),
+        _ => Err(invalid(line)),
+    };
+
+    let mut config = base.clone();
+    for line in content.lines().map(str::trim).filter(|line| !line.is_empty() && !line.starts_with('#')) {
+        let (key, value) = line.split_once('=').map(|(k, v)| (k.trim(), v.trim())).ok_or_else(|| invalid(line))?;
+        match key {
+            "delimiter" => {
+                config.delimiter = match value.parse()? {
+                    Delimiter::Auto => return Err(format!("stage {} needs an explicit delimiter", path.display())),
+                    delimiter => delimiter,
+                }
+            }
+            "headers" => {
+                config.header_mode = if flag(value, line)? { HeaderMode::HasHeaders } else { HeaderMode::NoHeaders }
+            }
+            "columns" => config.expected_columns = Some(value.parse().map_err(|_| invalid(line))?),
+            "split-overlong" => {
+                config.overflow_strategy =
+                    if flag(value, line)? { OverflowStrategy::SplitAtExpected } else { OverflowStrategy::Discard }
+            }
+            "normalize" => {
+                config.normalize_mode = match value {
+                    "collapse" => NormalizeMode::Collapse,
+                    "preserve-newlines" => NormalizeMode::PreserveNewlines,
+                    "trim-only" => NormalizeMode::TrimOnly,
+                    _ => return Err(invalid(line)),
+                }
+            }
+            _ => return Err(invalid(line)),
+        }
+    }
+    Ok(config)
+}
+
+// ============================================
+// Unit Tests
+// ============================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(list: &[&str]) -> Result<Command, String> {
+        parse(list.iter().map(|arg| arg.to_string()))
+    }
+
+    #[test]
+    fn test_parse_flags() {
+        assert_eq!(
+            args(&[
+                "--input",
+                "in.csv",
+                "-o",
+                "out.csv",
+                "--delimiter=semicolon",
+                "--no-headers",
+                "--columns",
+                "4",
+                "--split-overlong",
+            ]),
+            Ok(Command::Run(RunArgs {
+                input: "in.csv".to_string(),
+                output: "out.csv".to_string(),
+                delimiter: Delimiter::Semicolon,
+                header_mode: HeaderMode::NoHeaders,
+                columns: Some(4),
+                columns_from: None,
+                overflow_strategy: OverflowStrategy::SplitAtExpected,
+                stages: Vec::new(),
+            }))
+        );
+        assert_eq!(args(&["-i", "in.csv", "--help"]), Ok(Command::Help));
+        assert!(args(&["--input", "in.csv"]).is_err());
+        assert!(args(&["-i", "in.csv", "-o", "out.csv", "--columns", "4"]).is_err());
+        assert!(args(&["-i", "in.csv", "-o", "out.csv", "--bogus"]).is_err());
+        assert!(args(&["-i", "in.csv", "-o"]).is_err());
+    }
+
+    #[test]
+    fn test_parse_columns_from() {
+        match args(&["-i", "in.csv", "-o", "out.csv", "--columns-from=layout.csv"]) {
+            Ok(Command::Run(run)) => assert_eq!(run.columns_from, Some(PathBuf::from("layout.csv"))),
+            other => panic!("unexpected parse: {other:?}"),
+        }
+        assert!(args(&["-i", "in.csv", "-o", "out.csv", "--no-headers", "-c", "3", "--columns-from", "x"]).is_err());
+        assert!(args(&["--input-dir", "in", "--output-dir", "out", "--columns-from", "layout.csv"]).is_err());
+        assert!(args(&["-i", "in.csv", "-o", "out.csv", "--columns-from"]).is_err());
+    }
+
+    #[test]
+    fn test_parse_stages() {
+        match args(&["-i", "in.csv", "-o", "out.csv", "--stage", "stitch.conf", "--stage=tidy.conf"]) {
+            Ok(Command::Run(run)) => assert_eq!(run.stages, [PathBuf::from("stitch.conf"), PathBuf::from("tidy.conf")]),
+            other => panic!("unexpected parse: {other:?}"),
+        }
+        assert!(args(&["--input-dir", "in", "--output-dir", "out", "--stage", "stitch.conf"]).is_err());
+    }
+
+    #[test]
+    fn test_run_applies_stage_files_in_order() {
+        let (input, output) = ("test_cli_stages_in.csv", "test_cli_stages_out.csv");
+        let (stitch, tidy, bad) = ("test_cli_stage_stitch.conf", "test_cli_stage_tidy.conf", "test_cli_stage_bad.conf");
+        fs::write(input, "ID;Name;Amount\n1;Tbilisi\nWaters;10\n2;Gori;20\n").unwrap();
+        fs::write(stitch, "# fragments are split on semicolons\ndelimiter = semicolon\n").unwrap();
+        fs::write(tidy, "delimiter = comma\nnormalize = trim-only\n").unwrap();
+        fs::write(bad, "delimiter = auto\n").unwrap();
+        let run_args = |stages: &[&str]| RunArgs {
+            input: input.to_string(),
+            output: output.to_string(),
+            delimiter: Delimiter::Comma,
+            header_mode: HeaderMode::HasHeaders,
+            columns: None,
+            columns_from: None,
+            overflow_strategy: OverflowStrategy::Discard,
+            stages: stages.iter().map(PathBuf::from).collect(),
+        };
+
+        run(&run_args(&[stitch, tidy])).unwrap();
+        let written = fs::read_to_string(output).unwrap();
+        let rejected = run(&run_args(&[stitch, bad]));
+        for path in [input, output, stitch, tidy, bad] {
+            let _ = fs::remove_file(path);
+        }
+
+        assert_eq!(written, "ID,Name,Amount\n1,Tbilisi Waters,10\n2,Gori,20\n");
+        assert!(rejected.unwrap_err().to_string().contains("explicit delimiter"));
+    }
+
+    #[test]
+    fn test_parse_batch_flags() {
+        assert_eq!(
+            args(&["--input-dir", "in", "--output-dir=out", "--on-error", "abort"]),
+            Ok(Command::Batch(BatchArgs {
+                input_dir: "in".to_string(),
+                output_dir: "out".to_string(),
+                delimiter: Delimiter::Comma,
+                header_mode: HeaderMode::HasHeaders,
+                on_error: ErrorPolicy::Abort,
+            }))
+        );
+        match args(&["--input-dir", "in", "--output-dir", "out"]) {
+            Ok(Command::Batch(batch)) => assert_eq!(batch.on_error, ErrorPolicy::Continue),
+            other => panic!("unexpected parse: {other:?}"),
+        }
+        assert!(args(&["-i", "in.csv", "-o", "out.csv", "--on-error", "continue"]).is_err());
+        assert!(args(&["--input-dir", "in", "--output-dir", "out", "--on-error", "retry"]).is_err());
+        assert!(args(&["--input-dir", "in", "-o", "out.csv"]).is_err());
+        assert!(args(&["--input-dir", "in"]).is_err());
+    }
+
+    #[test]
+    fn test_batch_fails_when_any_file_fails() {
+        let input_dir = "test_cli_batch_input";
+        let output_dir = "test_cli_batch_output";
+        let _ = std::fs::remove_dir_all(input_dir);
+        std::fs::create_dir_all(input_dir).unwrap();
+        std::fs::write(format!("{input_dir}/a.csv"), "ID,Name\n1,Tbilisi\n").unwrap();
+        let batch = |on_error| BatchArgs {
+            input_dir: input_dir.to_string(),
+            output_dir: output_dir.to_string(),
+            delimiter: Delimiter::Comma,
+            header_mode: HeaderMode::HasHeaders,
+            on_error,
+        };
+
+        let clean = run_batch(&batch(ErrorPolicy::Continue));
+        std::fs::write(format!("{input_dir}/b.csv"), b"ID,Name\n1,\xff\xfe\n").unwrap();
+        let continued = run_batch(&batch(ErrorPolicy::Continue));
+        let aborted = run_batch(&batch(ErrorPolicy::Abort));
+        std::fs::remove_dir_all(input_dir).unwrap();
+        std::fs::remove_dir_all(output_dir).unwrap();
+
+        assert!(clean.is_ok());
+        assert_eq!(continued.unwrap_err().to_string(), "1 of 2 files failed");
+        assert_eq!(aborted.unwrap_err().to_string(), "stopped at the first failing file");
+    }
+}
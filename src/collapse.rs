@@ -0,0 +1,75 @@
+// src/collapse.rs
+//! Collapsing of delimiter runs
+//!
+//! Space- or tab-padded reports line their columns up with a varying number
+//! of delimiters. Read as-is every extra delimiter adds an empty column, so
+//! each run is reduced to a single separator first.
+
+/// Replace every run of `delimiter` outside quotes with a single one
+///
+/// Runs at the start or end of a line are removed entirely, so padding
+/// before the first or after the last column adds no empty fields either.
+pub(crate) fn collapse_delimiter_runs(data: &[u8], delimiter: u8, quote: u8) -> Vec<u8> {
+    let mut output = Vec::with_capacity(data.len());
+    let mut in_quotes = false;
+    let mut line_start = true;
+    let mut pending = false;
+
+    for &b in data {
+        if in_quotes {
+            in_quotes = b != quote;
+            output.push(b);
+        } else if b == delimiter {
+            pending = !line_start;
+        } else if b == b'\n' || b == b'\r' {
+            pending = false;
+            line_start = true;
+            output.push(b);
+        } else {
+            if pending {
+                output.push(delimiter);
+                pending = false;
+            }
+            in_quotes = b == quote;
+            line_start = false;
+            output.push(b);
+        }
+    }
+    output
+}
+
+// ============================================
+// Unit Tests
+// ============================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{reconstruct_rows_from_reader, Delimiter, ReconstructConfig, Stats};
+
+    #[test]
+    fn test_space_runs_collapse_to_one_separator() {
+        let input = "ID   Name        Amount\n  1  \"Tbilisi  Waters\"   10\n2 Gori      20  \r\n3    Mestia\n  Valley   30\n";
+        assert_eq!(
+            collapse_delimiter_runs(input.as_bytes(), b' ', b'"'),
+            b"ID Name Amount\n1 \"Tbilisi  Waters\" 10\n2 Gori 20\r\n3 Mestia\nValley 30\n".to_vec()
+        );
+
+        let config = ReconstructConfig {
+            delimiter: Delimiter::Custom(b' '),
+            collapse_consecutive_delimiters: true,
+            ..ReconstructConfig::default()
+        };
+        let rows = reconstruct_rows_from_reader(input.as_bytes(), &config, &mut Stats::default()).unwrap();
+        let records: Vec<Vec<&str>> = rows.iter().map(|row| row.record.iter().collect()).collect();
+        assert_eq!(
+            records,
+            vec![
+                vec!["ID", "Name", "Amount"],
+                vec!["1", "Tbilisi  Waters", "10"],
+                vec!["2", "Gori", "20"],
+                vec!["3", "Mestia\nValley", "30"],
+            ]
+        );
+    }
+}
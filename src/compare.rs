@@ -0,0 +1,175 @@
+// src/compare.rs
+//! A/B comparison of two configurations
+//!
+//! Runs the same input through two configurations in memory and reports how
+//! the counters and the produced records differ, so the effect of a config
+//! change can be judged without diffing two output files.
+
+use crate::config::ReconstructConfig;
+use crate::engine::{reconstruct_rows_with_config, ReconstructedRow, Stats};
+use crate::error::FixerrError;
+use csv::StringRecord;
+use std::collections::HashMap;
+use std::fmt;
+
+/// Counters of one run in a [`RunComparison`]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RunCounts {
+    /// Physical rows read
+    pub total_rows: usize,
+    /// Logical records repaired
+    pub fixed_rows: usize,
+    /// Logical records discarded
+    pub removed_rows: usize,
+    /// Data records produced (header excluded)
+    pub records: usize,
+    /// Warnings raised
+    pub warnings: usize,
+}
+
+impl RunCounts {
+    fn from_run(stats: &Stats, rows: &[ReconstructedRow]) -> Self {
+        RunCounts {
+            total_rows: stats.total_rows,
+            fixed_rows: stats.fixed_rows,
+            removed_rows: stats.removed_rows,
+            records: rows.iter().filter(|row| !row.header).count(),
+            warnings: stats.warnings.len(),
+        }
+    }
+}
+
+/// Differences between two runs over the same input
+///
+/// Records are compared by their full field contents; a record produced
+/// twice by one run and once by the other appears once in the difference.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RunComparison {
+    /// Counters of the run with the first configuration
+    pub a: RunCounts,
+    /// Counters of the run with the second configuration
+    pub b: RunCounts,
+    /// Data records only the first configuration produced, in file order
+    pub only_in_a: Vec<StringRecord>,
+    /// Data records only the second configuration produced, in file order
+    pub only_in_b: Vec<StringRecord>,
+}
+
+impl RunComparison {
+    /// Change in repaired records from `a` to `b`
+    pub fn fixed_delta(&self) -> i64 {
+        self.b.fixed_rows as i64 - self.a.fixed_rows as i64
+    }
+
+    /// Change in discarded records from `a` to `b`
+    pub fn removed_delta(&self) -> i64 {
+        self.b.removed_rows as i64 - self.a.removed_rows as i64
+    }
+
+    /// Change in produced data records from `a` to `b`
+    pub fn records_delta(&self) -> i64 {
+        self.b.records as i64 - self.a.records as i64
+    }
+
+    /// True if both runs produced the same records
+    pub fn is_identical(&self) -> bool {
+        self.only_in_a.is_empty() && self.only_in_b.is_empty()
+    }
+}
+
+impl fmt::Display for RunComparison {
+    /// Render the counters side by side with their deltas
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let delta = |a: usize, b: usize| format!("{:+}", b as i64 - a as i64);
+        let rows = [
+            ("Total lines read", self.a.total_rows, self.b.total_rows),
+            ("Fixed/Merged rows", self.a.fixed_rows, self.b.fixed_rows),
+            ("Discarded rows", self.a.removed_rows, self.b.removed_rows),
+            ("Total valid records", self.a.records, self.b.records),
+            ("Warnings", self.a.warnings, self.b.warnings),
+        ];
+
+        writeln!(f, "{:<20} {:>10} {:>10} {:>8}", "", "A", "B", "Delta")?;
+        for (label, a, b) in rows {
+            writeln!(f, "{label:<20} {a:>10} {b:>10} {:>8}", delta(a, b))?;
+        }
+        writeln!(f, "{:<20} {:>10} {:>10}", "Records only in run", self.only_in_a.len(), self.only_in_b.len())
+    }
+}
+
+/// Reconstruct `file_path` with both configurations and compare the results
+///
+/// Nothing is written to disk; output options of both configurations are
+/// ignored.
+pub fn compare_runs(
+    config_a: &ReconstructConfig,
+    config_b: &ReconstructConfig,
+    file_path: &str,
+) -> Result<RunComparison, FixerrError> {
+    let mut stats_a = Stats::default();
+    let rows_a = reconstruct_rows_with_config(file_path, config_a, &mut stats_a)?;
+    let mut stats_b = Stats::default();
+    let rows_b = reconstruct_rows_with_config(file_path, config_b, &mut stats_b)?;
+
+    Ok(RunComparison {
+        a: RunCounts::from_run(&stats_a, &rows_a),
+        b: RunCounts::from_run(&stats_b, &rows_b),
+        only_in_a: records_missing_from(&rows_a, &rows_b),
+        only_in_b: records_missing_from(&rows_b, &rows_a),
+    })
+}
+
+// ============================================
+// Private Helper Functions
+// ============================================
+
+/// Data records of `rows` not matched by a record of `other`, as a multiset difference
+fn records_missing_from(rows: &[ReconstructedRow], other: &[ReconstructedRow]) -> Vec<StringRecord> {
+    let fields = |row: &ReconstructedRow| -> Vec<String> { row.record.iter().map(str::to_string).collect() };
+    let mut available: HashMap<Vec<String>, usize> = HashMap::new();
+    for row in other.iter().filter(|row| !row.header) {
+        *available.entry(fields(row)).or_insert(0) += 1;
+    }
+
+    let mut missing = Vec::new();
+    for row in rows.iter().filter(|row| !row.header) {
+        match available.get_mut(&fields(row)) {
+            Some(count) if *count > 0 => *count -= 1,
+            _ => missing.push(row.record.clone()),
+        }
+    }
+    missing
+}
+
+// ============================================
+// Unit Tests
+// ============================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::{Delimiter, HeaderMode, OverflowStrategy};
+    use std::fs;
+
+    #[test]
+    fn test_compare_strict_and_lenient_overflow() {
+        let path = "test_compare_input.csv";
+        fs::write(path, "ID,Name,Amount\n1,Tbilisi, Waters,10\n2,Gori,20\n3,Mestia\n,30\n").unwrap();
+
+        let strict = ReconstructConfig::new(HeaderMode::HasHeaders, Delimiter::Comma);
+        let mut lenient = strict.clone();
+        lenient.overflow_strategy = OverflowStrategy::SmartMerge;
+        lenient.free_text_columns = vec![1];
+
+        let comparison = compare_runs(&strict, &lenient, path).unwrap();
+        fs::remove_file(path).unwrap();
+
+        assert_eq!(comparison.removed_delta(), -1);
+        assert_eq!(comparison.records_delta(), 1);
+        assert_eq!(comparison.a.fixed_rows, 1);
+        assert!(comparison.only_in_a.is_empty());
+        assert_eq!(comparison.only_in_b, vec![StringRecord::from(vec!["1", "Tbilisi, Waters", "10"])]);
+        assert!(!comparison.is_identical());
+        assert!(comparison.to_string().contains("Discarded rows"));
+    }
+}
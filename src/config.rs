@@ -0,0 +1,396 @@
+// src/config.rs
+//! Reconstruction configuration
+//!
+//! Groups every option that influences how records are reconstructed and
+//! written, so new behaviours can be added without changing the signatures
+//! of the public API functions.
+
+use crate::encoding::{InputEncoding, UnmappableAction};
+use crate::engine::{
+    ColumnCountStrategy, Delimiter, HeaderMode, LineEnding, LineTerminator, NormalizeMode, OverflowStrategy, QuoteChar,
+    QuoteStyle,
+};
+use crate::infer::ColumnType;
+use crate::normalizer::{ColumnNormalizer, NormalizerRegistry};
+use crate::progress::ProgressHook;
+use crate::redact::RedactMode;
+use crate::retry::RetryPolicy;
+use crate::sort::{SortComparison, SortOrder};
+use crate::validate::{default_type_hint_suffixes, RecordValidator};
+use encoding_rs::{Encoding, UTF_8};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use regex::Regex;
+
+/// How multi-section output is laid out
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SectionOutput {
+    /// One file per section: `<output>_section_1.csv`, ... (default)
+    #[default]
+    SeparateFiles,
+    /// A single file with a leading `section` column holding the section index
+    SectionColumn,
+}
+
+/// What happens to rows that violate a header type hint
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TypeHintPolicy {
+    /// Keep the row and record a warning (default)
+    #[default]
+    Warn,
+    /// Drop the row and count it as removed
+    Reject,
+}
+
+/// What happens to stitched rows with a field over `max_newlines_per_field`
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RunawayFieldPolicy {
+    /// Drop the row and count it as removed (default)
+    #[default]
+    Reject,
+    /// Keep the row and record a warning
+    Warn,
+}
+
+/// Options controlling reconstruction and output
+#[derive(Debug, Clone)]
+pub struct ReconstructConfig {
+    /// Whether the first row is a header
+    pub header_mode: HeaderMode,
+    /// Field separator of the input file
+    pub delimiter: Delimiter,
+    /// Character enclosing quoted fields in the input
+    pub quote: QuoteChar,
+    /// Character enclosing quoted fields in written files, independent of
+    /// `quote`; fields containing it are quoted and it is doubled inside them
+    pub output_quote: QuoteChar,
+    /// Which fields of written files are quoted (default `Necessary`); with
+    /// `Never`, fields containing the delimiter make the output unparseable
+    pub quote_style: QuoteStyle,
+    /// Skip input lines starting with this byte, e.g. `#` metadata above the
+    /// header (default: none). A fragment of a split record starting with it
+    /// is skipped too
+    pub comment: Option<u8>,
+    /// Expected column count for `NoHeaders` mode; required there unless
+    /// `column_count_strategy` provides one
+    pub expected_columns: Option<usize>,
+    /// How the expected column count is determined
+    pub column_count_strategy: ColumnCountStrategy,
+    /// Column names that, if they make up the whole first row of a `NoHeaders`
+    /// file, trigger a [`Warning::PossibleHeaderRow`](crate::Warning)
+    pub known_header_names: Vec<String>,
+    /// Header every `HasHeaders` input must have; any difference aborts with
+    /// [`FixerrError::HeaderMismatch`](crate::FixerrError)
+    pub expect_headers: Option<Vec<String>>,
+    /// Accept `expect_headers` in any order, as long as the same names appear
+    pub allow_reorder: bool,
+    /// Pattern that values of column 1 match; a row one field short whose
+    /// first value matches is taken to have lost its leading field, and an
+    /// empty one is inserted
+    pub leading_column_pattern: Option<Regex>,
+    /// Valid values of enumerated columns; an over-wide row whose surplus
+    /// fields rejoin into one of them is repaired before `overflow_strategy`
+    pub enum_columns: HashMap<usize, Vec<String>>,
+    /// In comma-delimited input, repair over-wide rows in which unquoted
+    /// decimal commas (`12,50`) split numbers into two fields; applied before
+    /// `overflow_strategy` and only when the split is unambiguous
+    pub repair_decimal_comma: bool,
+    /// Write numbers repaired by `repair_decimal_comma` with a decimal point
+    /// and without thousands separators (`1.234,50` becomes `1234.50`)
+    pub decimal_comma_to_point: bool,
+    /// What to do with physical rows wider than the expected column count
+    pub overflow_strategy: OverflowStrategy,
+    /// Columns holding free text that may contain unescaped delimiters; the
+    /// first is where `OverflowStrategy::SmartMerge` puts surplus fields
+    pub free_text_columns: Vec<usize>,
+    /// Stitch fragments from the end of the file backwards, for formats whose
+    /// records end cleanly but may be preceded by orphaned fragments. Buffers
+    /// the whole input; `multi_section` and `overflow_strategy` are ignored
+    pub reverse: bool,
+    /// Fields by which a stitched row may overshoot the expected column count
+    /// and still be repaired: empty fields at its line breaks (from a line
+    /// ending in a delimiter, or continuing with one) are dropped, last first,
+    /// until it fits. 0 (default) discards an overshooting row at once
+    pub stitch_overshoot_tolerance: usize,
+    /// Most embedded newlines a stitched field may hold; beyond this the
+    /// stitch most likely swallowed real records
+    pub max_newlines_per_field: Option<usize>,
+    /// Handling of stitched rows that exceed `max_newlines_per_field`
+    pub runaway_field_policy: RunawayFieldPolicy,
+    /// When the input ends inside a quoted field, close it implicitly and keep
+    /// the final record (padded to full width) instead of discarding it
+    pub repair_unterminated_quote: bool,
+    /// Pad a record left incomplete at the end of the input with empty fields
+    /// and keep it as a repaired record instead of discarding it, for files
+    /// whose last record lost its trailing empty fields
+    pub pad_trailing_row: bool,
+    /// Treat a header-like row after a blank line as the start of a new
+    /// section with its own header and column count (`HasHeaders` mode)
+    pub multi_section: bool,
+    /// Output layout used when `multi_section` is enabled
+    pub section_output: SectionOutput,
+    /// In `NoHeaders` mode, synthesize a header row from inferred column types
+    /// (e.g. `int_col_0`, `text_col_1`) and emit it as the first output row
+    pub generate_typed_headers: bool,
+    /// Replace blank header names with `col_<index>` (0-based), e.g. the
+    /// middle name of `ID,"",Amount` becomes `col_1`
+    pub fill_empty_header_names: bool,
+    /// Column holding a sequential record number to verify after reconstruction
+    pub sequence_column: Option<usize>,
+    /// Expected increment between consecutive sequence values (default 1)
+    pub sequence_step: i64,
+    /// Merge records sharing the value of this column into one record
+    pub group_by: Option<usize>,
+    /// Columns whose values are concatenated when records are merged by
+    /// `group_by`; other columns keep the first record's value
+    pub concat_columns: Vec<usize>,
+    /// Separator placed between concatenated values (default `"; "`)
+    pub concat_separator: String,
+    /// Hook run on every data record as the last step of reconstruction,
+    /// deciding whether it is kept, dropped or replaced; headers are skipped
+    pub validate_fn: Option<RecordValidator>,
+    /// Sort records by this column after reconstruction; headers stay on top
+    pub sort_by: Option<(usize, SortOrder)>,
+    /// How `sort_by` compares key values
+    pub sort_comparison: SortComparison,
+    /// Columns whose numbers are rewritten to the canonical `1234.56` form,
+    /// accepting both `1.234,56` and `1,234.56` styles, even mixed per row
+    pub numeric_columns: Vec<usize>,
+    /// Named normalizers applied to individual columns after reconstruction
+    pub column_normalizers: Vec<ColumnNormalizer>,
+    /// Normalizers that `column_normalizers` can refer to (built-ins included)
+    pub normalizers: NormalizerRegistry,
+    /// Validate values against types implied by header suffixes such as
+    /// `id_int` or `amount_decimal` (`HasHeaders` mode)
+    pub header_type_hints: bool,
+    /// Header suffixes and the column types they imply
+    pub type_hint_suffixes: Vec<(String, ColumnType)>,
+    /// Handling of rows that violate a header type hint
+    pub type_hint_policy: TypeHintPolicy,
+    /// Split the output into numbered part files of at most this many data rows
+    pub max_rows_per_file: Option<usize>,
+    /// Write repaired records and untouched records to two separate files
+    pub split_changed: bool,
+    /// Translate line breaks inside field values (stitch points included) to
+    /// this terminator; `None` keeps them as read. Flattening output modes
+    /// replace them with spaces regardless
+    pub internal_newline: Option<LineTerminator>,
+    /// Output delimiter placed before each column, one entry per column (the
+    /// first entry is unused), e.g. commas with a tab before a final notes
+    /// column. Records of any other width are an error
+    pub column_delimiters: Option<Vec<u8>>,
+    /// Write embedded tabs, newlines and backslashes as `\t`, `\n`, `\\`
+    /// instead of flattening them to spaces
+    pub escape_special_chars: bool,
+    /// Whitespace clean-up applied to each field on output, unless
+    /// `escape_special_chars` is set; `PreserveNewlines` together with
+    /// `escape_special_chars` is an error
+    pub normalize_mode: NormalizeMode,
+    /// Write header rows exactly as read, skipping the field clean-up applied
+    /// to data rows, for consumers that match on exact header strings
+    pub verbatim_header: bool,
+    /// Columns whose data values are redacted on output, after field
+    /// clean-up; the header is left untouched
+    pub redact_columns: HashMap<usize, RedactMode>,
+    /// Salt mixed into `RedactMode::Hash`; keep it secret and reuse it for
+    /// files that must stay joinable
+    pub redact_salt: String,
+    /// Write a CSV log of every field whose value was changed on output
+    pub audit_log_path: Option<String>,
+    /// Write a JSON description of which physical lines formed each record
+    pub stitch_graph_path: Option<String>,
+    /// Write a uniform random sample of `n` data rows (plus header) to a path
+    pub sample_output: Option<(String, usize)>,
+    /// Seed for `sample_output`; `None` seeds from the clock
+    pub sample_seed: Option<u64>,
+    /// Write only the sample, skipping the full output (a dry run); an input
+    /// [`reconstruct_file`](crate::reconstruct_file) streams is sampled as it is read
+    pub sample_only: bool,
+    /// Character encoding of the input, decoded to UTF-8 before parsing
+    /// (default UTF-8); set `output_encoding` to write the same charset back
+    pub input_encoding: InputEncoding,
+    /// Character encoding of the written output (default UTF-8)
+    pub output_encoding: &'static Encoding,
+    /// Handling of characters that don't exist in `output_encoding`
+    pub unmappable_action: UnmappableAction,
+    /// File receiving the discarded rows byte-for-byte as they appeared in
+    /// the input (not re-serialized); not collected in `reverse` mode
+    pub rejects_path: Option<String>,
+    /// Re-read every written file with a strict (non-flexible) parser and fail
+    /// with [`FixerrError::OutputNotStrictlyValid`](crate::FixerrError) unless
+    /// all records have the same width; not applied with `column_delimiters`
+    pub strict_verify_output: bool,
+    /// Input size in bytes above which [`reconstruct_file`](crate::reconstruct_file)
+    /// streams the input instead of reconstructing it in memory; `None`
+    /// (default) always works in memory
+    pub auto_mode_threshold: Option<u64>,
+    /// Gunzip the input before reading it; also enabled by a `.gz` input path
+    pub decompress: bool,
+    /// Gzip-compress written files; also enabled by a `.gz` output path
+    pub compress: bool,
+    /// Number of physical rows forming the header in `HasHeaders` mode
+    /// (default 1). The cells of each column are joined into one name
+    pub header_rows: usize,
+    /// Separator between the cells joined by `header_rows` (default `" "`)
+    pub header_row_separator: String,
+    /// Inserted between the two halves of a field split across physical rows
+    /// (default `"\n"`, the break that split it). Output normalization runs
+    /// afterwards: `Collapse` turns a `"\n"` or `" "` join into one space, so
+    /// use `""` to rejoin hyphenated words. `max_newlines_per_field` and
+    /// `internal_newline` only see stitch points joined with `"\n"`
+    pub stitch_separator: String,
+    /// Treat each run of the delimiter as a single separator, for reports
+    /// padded with repeated spaces or tabs
+    ///
+    /// Runs at the start and end of a line are dropped. This rules out
+    /// legitimately empty fields: an empty value between two delimiters
+    /// disappears and shifts the columns after it. The input is buffered
+    pub collapse_consecutive_delimiters: bool,
+    /// Parse every physical row with whichever of `row_delimiter_candidates`
+    /// gives it the expected width, for feeds that mix delimiters row by row
+    ///
+    /// A last resort: the whole input is buffered and every row is parsed
+    /// once per candidate. Rows no candidate fits are left to the usual
+    /// handling, so fragments are still stitched and over-wide rows are
+    /// discarded. Needs the header or `expected_columns` for the width
+    pub per_row_delimiter_detection: bool,
+    /// Delimiters tried by `per_row_delimiter_detection`, after `delimiter`
+    pub row_delimiter_candidates: Vec<Delimiter>,
+    /// Line terminator written after each record (default `Lf`); `Auto`
+    /// mirrors the input's dominant line ending
+    pub line_ending: LineEnding,
+    /// End written files with a line terminator after the last record
+    ///
+    /// Defaults to `true`, matching the csv writer. Set to `false` when the
+    /// output is compared byte for byte with an input that has no trailing
+    /// newline.
+    pub final_newline: bool,
+    /// Remember which fields of intact rows were quoted in the input and
+    /// quote exactly those (plus any that need it) on output
+    ///
+    /// Repaired rows are quoted only where needed. Honoured by
+    /// `write_output_rows_with_config`, which checks the file when
+    /// `strict_verify_output` is set and returns an error when this is
+    /// combined with `split_changed`, `max_rows_per_file`,
+    /// `column_delimiters` or `multi_section`
+    pub preserve_quoting: bool,
+    /// Keep the physical records of discarded rows in `stats.discarded_records`
+    pub collect_discarded: bool,
+    /// State file remembering the column count and row widths of earlier
+    /// runs per input, for recurring feeds
+    ///
+    /// Honoured by the path-based functions. In `NoHeaders` mode the stored
+    /// column count is used when none is configured, and warnings are
+    /// raised when a run's column count or width distribution departs from
+    /// the stored runs. Costs a second read of the input
+    pub history_file: Option<PathBuf>,
+    /// Name an input is stored under in `history_file`; defaults to its file
+    /// name, so set it for feeds whose file names carry a date
+    pub history_key: Option<String>,
+    /// Called every [`PROGRESS_INTERVAL_ROWS`](crate::PROGRESS_INTERVAL_ROWS)
+    /// physical rows with the position in the input
+    pub progress: Option<ProgressHook>,
+    /// Retry policy for transient errors when opening, reading and writing
+    /// files (no retries by default)
+    pub retry: RetryPolicy,
+}
+
+impl Default for ReconstructConfig {
+    fn default() -> Self {
+        Self {
+            header_mode: HeaderMode::default(),
+            delimiter: Delimiter::default(),
+            quote: QuoteChar::default(),
+            output_quote: QuoteChar::default(),
+            quote_style: QuoteStyle::default(),
+            comment: None,
+            expected_columns: None,
+            column_count_strategy: ColumnCountStrategy::default(),
+            known_header_names: Vec::new(),
+            expect_headers: None,
+            allow_reorder: false,
+            leading_column_pattern: None,
+            enum_columns: HashMap::new(),
+            repair_decimal_comma: false,
+            decimal_comma_to_point: false,
+            overflow_strategy: OverflowStrategy::default(),
+            free_text_columns: Vec::new(),
+            reverse: false,
+            stitch_overshoot_tolerance: 0,
+            max_newlines_per_field: None,
+            runaway_field_policy: RunawayFieldPolicy::default(),
+            repair_unterminated_quote: false,
+            pad_trailing_row: false,
+            multi_section: false,
+            section_output: SectionOutput::default(),
+            generate_typed_headers: false,
+            fill_empty_header_names: false,
+            sequence_column: None,
+            sequence_step: 1,
+            group_by: None,
+            concat_columns: Vec::new(),
+            concat_separator: "; ".to_string(),
+            validate_fn: None,
+            sort_by: None,
+            sort_comparison: SortComparison::default(),
+            numeric_columns: Vec::new(),
+            column_normalizers: Vec::new(),
+            normalizers: NormalizerRegistry::default(),
+            header_type_hints: false,
+            type_hint_suffixes: default_type_hint_suffixes(),
+            type_hint_policy: TypeHintPolicy::default(),
+            max_rows_per_file: None,
+            split_changed: false,
+            internal_newline: None,
+            column_delimiters: None,
+            escape_special_chars: false,
+            normalize_mode: NormalizeMode::default(),
+            verbatim_header: false,
+            redact_columns: HashMap::new(),
+            redact_salt: String::new(),
+            audit_log_path: None,
+            stitch_graph_path: None,
+            sample_output: None,
+            sample_seed: None,
+            sample_only: false,
+            input_encoding: InputEncoding::default(),
+            output_encoding: UTF_8,
+            unmappable_action: UnmappableAction::default(),
+            rejects_path: None,
+            strict_verify_output: false,
+            auto_mode_threshold: None,
+            decompress: false,
+            compress: false,
+            header_rows: 1,
+            header_row_separator: " ".to_string(),
+            stitch_separator: "\n".to_string(),
+            collapse_consecutive_delimiters: false,
+            per_row_delimiter_detection: false,
+            row_delimiter_candidates: vec![Delimiter::Comma, Delimiter::Semicolon, Delimiter::Tab, Delimiter::Pipe],
+            line_ending: LineEnding::default(),
+            final_newline: true,
+            preserve_quoting: false,
+            collect_discarded: false,
+            history_file: None,
+            history_key: None,
+            progress: None,
+            retry: RetryPolicy::default(),
+        }
+    }
+}
+
+impl ReconstructConfig {
+    /// Create a configuration with the given header mode and delimiter
+    pub fn new(header_mode: HeaderMode, delimiter: Delimiter) -> Self {
+        Self {
+            header_mode,
+            delimiter,
+            ..Self::default()
+        }
+    }
+
+    /// Whether the first reconstructed row is a header rather than data
+    pub fn output_has_header(&self) -> bool {
+        self.header_mode.as_bool() || self.generate_typed_headers
+    }
+}
@@ -0,0 +1,85 @@
+// src/dataframe.rs
+//! Polars DataFrame output (requires the `polars` feature)
+//!
+//! Builds a DataFrame directly from reconstructed records so data-science
+//! workflows don't need to write and re-read an intermediate file.
+
+use crate::config::ReconstructConfig;
+use crate::engine::{reconstruct_records_with_config, Stats};
+use crate::error::FixerrError;
+use crate::output::prepare_record;
+use csv::WriterBuilder;
+use polars::prelude::*;
+use std::io::Cursor;
+
+/// Reconstruct a file into a Polars DataFrame
+///
+/// Fields are normalized exactly as they would be on CSV output. Header names
+/// become column names; headerless files get Polars' generated names (or the
+/// typed placeholders when `generate_typed_headers` is set). Column dtypes
+/// are inferred by Polars.
+pub fn reconstruct_to_dataframe(
+    file_path: &str,
+    config: &ReconstructConfig,
+) -> Result<DataFrame, FixerrError> {
+    reconstruct_to_dataframe_with_schema(file_path, config, None)
+}
+
+/// Reconstruct a file into a Polars DataFrame with an explicit schema
+///
+/// When `schema` is `None`, dtypes are inferred as in [`reconstruct_to_dataframe`].
+pub fn reconstruct_to_dataframe_with_schema(
+    file_path: &str,
+    config: &ReconstructConfig,
+    schema: Option<Schema>,
+) -> Result<DataFrame, FixerrError> {
+    let mut stats = Stats::default();
+    let records = reconstruct_records_with_config(file_path, config, &mut stats)?;
+
+    // Serialize the normalized records and let Polars' CSV reader infer dtypes
+    let mut buffer = WriterBuilder::new()
+        .delimiter(config.delimiter.as_byte())
+        .from_writer(Vec::new());
+    for (index, record) in records.iter().enumerate() {
+        buffer.write_record(prepare_record(record, index == 0 && config.output_has_header(), config))?;
+    }
+    let bytes = buffer.into_inner().map_err(|e| e.into_error())?;
+
+    let frame = CsvReadOptions::default()
+        .with_has_header(config.output_has_header())
+        .with_schema(schema.map(Arc::new))
+        .with_parse_options(CsvParseOptions::default().with_separator(config.delimiter.as_byte()))
+        .into_reader_with_file_handle(Cursor::new(bytes))
+        .finish()?;
+
+    Ok(frame)
+}
+
+// ============================================
+// Unit Tests
+// ============================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn test_dataframe_shape_and_columns() {
+        let filename = "test_dataframe.csv";
+        let content = "ID,Organization,Amount\n1,Tbilisi\nWaters,1722.63\n2,Gori Beverages,3427.50";
+        fs::write(filename, content).unwrap();
+
+        let frame = reconstruct_to_dataframe(filename, &ReconstructConfig::default()).unwrap();
+        let _ = fs::remove_file(filename);
+
+        assert_eq!(frame.shape(), (2, 3));
+        let names: Vec<&str> = frame.get_column_names().iter().map(|n| n.as_str()).collect();
+        assert_eq!(names, vec!["ID", "Organization", "Amount"]);
+        assert_eq!(frame.column("ID").unwrap().dtype(), &DataType::Int64);
+        assert_eq!(
+            frame.column("Organization").unwrap().str().unwrap().get(0),
+            Some("Tbilisi Waters")
+        );
+    }
+}
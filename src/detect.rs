@@ -0,0 +1,390 @@
+// src/detect.rs
+//! Detection of input dialect settings from a sample
+//!
+//! Guessing the quote character, the delimiter or the column count wrong
+//! splits records in the wrong places, so all three can be read off the data
+//! instead.
+
+use crate::config::ReconstructConfig;
+use crate::engine::{build_quoted_reader, Delimiter, HeaderMode};
+use crate::error::FixerrError;
+use csv::{StringRecord, WriterBuilder};
+use std::io::Read;
+
+/// Bytes read from the start of the input to detect the quote character
+/// and the delimiter
+pub const QUOTE_SAMPLE_BYTES: usize = 64 * 1024;
+
+/// Non-empty lines of the sample examined by [`detect_delimiter`]
+pub const DELIMITER_SAMPLE_LINES: usize = 100;
+
+/// Data rows examined by [`vote_column_count`]
+pub const COLUMN_VOTE_SAMPLE_ROWS: usize = 100;
+
+/// Delimiters considered by [`detect_delimiter`], in tie-breaking order
+const DELIMITER_CANDIDATES: [Delimiter; 4] =
+    [Delimiter::Comma, Delimiter::Semicolon, Delimiter::Tab, Delimiter::Pipe];
+
+/// Quote characters considered by [`detect_quote`]
+const QUOTE_CANDIDATES: [u8; 2] = [b'"', b'\''];
+
+/// Determine which quote character `sample` uses
+///
+/// A candidate scores for every field it encloses from the field's first byte
+/// up to a closing quote right before a delimiter or line end, and scores
+/// double when the enclosed text contains the delimiter (the reason quoting
+/// exists). Quotes inside values, such as apostrophes in names, don't count.
+/// Returns `None` when no candidate encloses a field.
+pub fn detect_quote(sample: &[u8], delimiter: Delimiter) -> Option<u8> {
+    let delimiter = delimiter.as_byte();
+    QUOTE_CANDIDATES
+        .iter()
+        .map(|&quote| (quote, quote_score(sample, delimiter, quote)))
+        .filter(|&(_, score)| score > 0)
+        .max_by_key(|&(_, score)| score)
+        .map(|(quote, _)| quote)
+}
+
+/// Determine the delimiter of the input read from `reader`
+///
+/// Counts each candidate outside double-quoted regions on the first
+/// [`DELIMITER_SAMPLE_LINES`] lines and picks the one whose most common
+/// per-line count occurs on the most lines; split records make some lines
+/// short, but the intact ones agree. Ties go to the earlier candidate, so a
+/// comma wins any tie it is part of. Input without any candidate is taken to
+/// be comma-separated.
+pub fn detect_delimiter<R: Read>(reader: R) -> Result<Delimiter, FixerrError> {
+    let mut sample = Vec::new();
+    reader.take(QUOTE_SAMPLE_BYTES as u64).read_to_end(&mut sample)?;
+    Ok(rank_delimiters(&sample).0)
+}
+
+/// The detected delimiter plus every candidate that tied with it
+pub(crate) fn rank_delimiters(sample: &[u8]) -> (Delimiter, Vec<Delimiter>) {
+    let mut counts = vec![Vec::new(); DELIMITER_CANDIDATES.len()];
+    let mut in_quotes = false;
+    let lines = sample.split(|&b| b == b'\n').filter(|line| !line.is_empty());
+    for line in lines.take(DELIMITER_SAMPLE_LINES) {
+        let mut line_counts = [0usize; DELIMITER_CANDIDATES.len()];
+        for &b in line {
+            if b == b'"' {
+                // A doubled quote toggles twice, which leaves the state as it was
+                in_quotes = !in_quotes;
+            } else if !in_quotes {
+                if let Some(i) = DELIMITER_CANDIDATES.iter().position(|d| d.as_byte() == b) {
+                    line_counts[i] += 1;
+                }
+            }
+        }
+        for (i, &count) in line_counts.iter().enumerate() {
+            counts[i].push(count);
+        }
+    }
+
+    let scores: Vec<(usize, usize)> = counts.iter().map(|counts| consistency(counts)).collect();
+    let best = scores.iter().copied().max().unwrap_or((0, 0));
+    if best == (0, 0) {
+        return (Delimiter::Comma, Vec::new());
+    }
+    let tied: Vec<Delimiter> = DELIMITER_CANDIDATES
+        .iter()
+        .zip(&scores)
+        .filter(|&(_, &score)| score == best)
+        .map(|(&delimiter, _)| delimiter)
+        .collect();
+    (tied[0], tied)
+}
+
+/// Column count chosen by [`vote_column_count`]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ColumnCountVote {
+    /// The most common width among the voting rows
+    pub columns: usize,
+    /// Fraction of voting rows with that width, in `(0, 1]`; a low value
+    /// means the input is too fragmented for the vote to be trusted
+    pub confidence: f64,
+}
+
+/// Infer the column count from the first [`COLUMN_VOTE_SAMPLE_ROWS`] data rows
+///
+/// Single-field rows don't vote: they are typically the tail of a value
+/// broken by a newline rather than a complete record. Ties go to the wider
+/// count, since fragments are narrower than the record they belong to. In
+/// `HasHeaders` mode the header is skipped, so a broken header doesn't skew
+/// the result. Fails with [`FixerrError::EmptyInput`] when no row votes.
+pub fn vote_column_count<R: Read>(
+    reader: R,
+    header_mode: HeaderMode,
+    delimiter: Delimiter,
+) -> Result<ColumnCountVote, FixerrError> {
+    let mut sample = Vec::new();
+    reader.take(QUOTE_SAMPLE_BYTES as u64).read_to_end(&mut sample)?;
+    let truncated = sample.len() == QUOTE_SAMPLE_BYTES;
+    vote_in_sample(&sample, header_mode, delimiter, b'"', None, truncated)?.ok_or(FixerrError::EmptyInput)
+}
+
+/// [`vote_column_count`] over a sample; the last row of a `truncated` sample
+/// may be cut short and is left out
+pub(crate) fn vote_in_sample(
+    sample: &[u8],
+    header_mode: HeaderMode,
+    delimiter: Delimiter,
+    quote: u8,
+    comment: Option<u8>,
+    truncated: bool,
+) -> Result<Option<ColumnCountVote>, FixerrError> {
+    let mut reader = build_quoted_reader(sample, header_mode, delimiter, quote, comment);
+    let mut widths = Vec::new();
+    for record in reader.records().take(COLUMN_VOTE_SAMPLE_ROWS + 1) {
+        widths.push(record?.len());
+    }
+    if truncated || widths.len() > COLUMN_VOTE_SAMPLE_ROWS {
+        widths.pop();
+    }
+
+    let voters: Vec<usize> = widths.into_iter().filter(|&width| width > 1).collect();
+    let mut tally: Vec<(usize, usize)> = Vec::new();
+    for &width in &voters {
+        match tally.iter_mut().find(|(value, _)| *value == width) {
+            Some((_, votes)) => *votes += 1,
+            None => tally.push((width, 1)),
+        }
+    }
+    Ok(tally
+        .into_iter()
+        .max_by_key(|&(width, votes)| (votes, width))
+        .map(|(columns, votes)| ColumnCountVote { columns, confidence: votes as f64 / voters.len() as f64 }))
+}
+
+/// Rewrite every row of `data` that fits the expected width with another
+/// candidate delimiter so it uses the configured delimiter
+///
+/// The width comes from `expected_columns` or, with headers, from the first
+/// row parsed with whichever candidate splits it into the most fields. Rows
+/// are split on line breaks outside quotes. Rows that fit under the
+/// configured delimiter, or under no candidate, are copied unchanged.
+pub(crate) fn unify_row_delimiters(data: &[u8], config: &ReconstructConfig, quote: u8) -> Result<Vec<u8>, FixerrError> {
+    let mut candidates = vec![config.delimiter];
+    candidates.extend(config.row_delimiter_candidates.iter().filter(|&&d| d != config.delimiter));
+
+    let mut expected = config.expected_columns.filter(|_| !config.header_mode.as_bool());
+    let mut output = Vec::with_capacity(data.len());
+    for row in quoted_rows(data, quote) {
+        let content = row.strip_suffix(b"\n").unwrap_or(row);
+        let content = content.strip_suffix(b"\r").unwrap_or(content);
+        if content.is_empty() {
+            output.extend_from_slice(row);
+            continue;
+        }
+
+        let parsed: Vec<StringRecord> = candidates
+            .iter()
+            .map(|&delimiter| parse_row(content, delimiter, quote))
+            .collect::<Result<_, _>>()?;
+        let width = *expected.get_or_insert_with(|| parsed.iter().map(StringRecord::len).max().unwrap_or(0));
+
+        match parsed.iter().position(|record| record.len() == width) {
+            Some(index) if index > 0 => {
+                let mut writer = WriterBuilder::new()
+                    .delimiter(config.delimiter.as_byte())
+                    .quote(quote)
+                    .from_writer(&mut output);
+                writer.write_record(&parsed[index])?;
+                writer.flush()?;
+            }
+            _ => output.extend_from_slice(row),
+        }
+    }
+    Ok(output)
+}
+
+// ============================================
+// Private Helper Functions
+// ============================================
+
+/// Physical rows of `data`, each with its line break; a break inside quotes
+/// continues the row
+fn quoted_rows(data: &[u8], quote: u8) -> Vec<&[u8]> {
+    let mut rows = Vec::new();
+    let mut start = 0;
+    let mut in_quotes = false;
+    for (i, &b) in data.iter().enumerate() {
+        if b == quote {
+            in_quotes = !in_quotes;
+        } else if b == b'\n' && !in_quotes {
+            rows.push(&data[start..=i]);
+            start = i + 1;
+        }
+    }
+    if start < data.len() {
+        rows.push(&data[start..]);
+    }
+    rows
+}
+
+fn parse_row(row: &[u8], delimiter: Delimiter, quote: u8) -> Result<StringRecord, FixerrError> {
+    let mut reader = build_quoted_reader(row, HeaderMode::NoHeaders, delimiter, quote, None);
+    let mut record = StringRecord::new();
+    reader.read_record(&mut record)?;
+    Ok(record)
+}
+
+/// Number of lines sharing the most common non-zero count, and that count
+fn consistency(counts: &[usize]) -> (usize, usize) {
+    let mut frequency: Vec<(usize, usize)> = Vec::new();
+    for &count in counts.iter().filter(|&&count| count > 0) {
+        match frequency.iter_mut().find(|(value, _)| *value == count) {
+            Some((_, lines)) => *lines += 1,
+            None => frequency.push((count, 1)),
+        }
+    }
+    frequency.into_iter().map(|(count, lines)| (lines, count)).max().unwrap_or((0, 0))
+}
+
+fn quote_score(sample: &[u8], delimiter: u8, quote: u8) -> usize {
+    let mut score = 0;
+    for line in sample.split(|&b| b == b'\n') {
+        let line = line.strip_suffix(b"\r").unwrap_or(line);
+        let mut pos = 0;
+        while pos < line.len() {
+            if line[pos] == quote {
+                match closing_quote(line, pos + 1, delimiter, quote) {
+                    Some(end) => {
+                        score += if line[pos + 1..end].contains(&delimiter) { 2 } else { 1 };
+                        pos = end + 1;
+                    }
+                    // Opened but not closed on this line: multi-line or not a quote
+                    None => break,
+                }
+            }
+            // Skip to the start of the next field
+            match line[pos.min(line.len())..].iter().position(|&b| b == delimiter) {
+                Some(offset) => pos += offset + 1,
+                None => break,
+            }
+        }
+    }
+    score
+}
+
+/// Index of the quote closing a field opened before `start`, if any
+fn closing_quote(line: &[u8], start: usize, delimiter: u8, quote: u8) -> Option<usize> {
+    let mut pos = start;
+    while pos < line.len() {
+        if line[pos] == quote {
+            if line.get(pos + 1) == Some(&quote) {
+                // Doubled quote is an escaped literal
+                pos += 2;
+                continue;
+            }
+            return match line.get(pos + 1) {
+                None => Some(pos),
+                Some(&b) if b == delimiter => Some(pos),
+                Some(_) => None,
+            };
+        }
+        pos += 1;
+    }
+    None
+}
+
+// ============================================
+// Unit Tests
+// ============================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{reconstruct_rows_from_reader, QuoteChar, ReconstructConfig, Stats, Warning};
+
+    #[test]
+    fn test_detect_quote_character() {
+        let double = b"ID,Name,Amount\n1,\"Waters, Tbilisi\",10\n2,O'Neil,20\n";
+        let single = b"ID,Name,Amount\n1,'Waters, Tbilisi',10\n2,'Gori',20\n";
+        let unquoted = b"ID,Name,Amount\n1,Tbilisi,10\n2,Gori,20\n";
+
+        assert_eq!(detect_quote(double, Delimiter::Comma), Some(b'"'));
+        assert_eq!(detect_quote(single, Delimiter::Comma), Some(b'\''));
+        assert_eq!(detect_quote(unquoted, Delimiter::Comma), None);
+
+        let config = ReconstructConfig {
+            quote: QuoteChar::Auto,
+            ..ReconstructConfig::default()
+        };
+        let rows = reconstruct_rows_from_reader(&single[..], &config, &mut Stats::default()).unwrap();
+        assert_eq!(rows[1].record.get(1), Some("Waters, Tbilisi"));
+        let rows = reconstruct_rows_from_reader(&unquoted[..], &config, &mut Stats::default()).unwrap();
+        assert_eq!(rows.len(), 3);
+    }
+
+    #[test]
+    fn test_detect_delimiter() {
+        let semicolons = "ID;Name;Notes\n1;Tbilisi Waters;\"a, b, c, d\"\n2;Gori\nBeverages;x\n3;Mestia;y\n";
+        assert_eq!(detect_delimiter(semicolons.as_bytes()).unwrap(), Delimiter::Semicolon);
+        assert_eq!(detect_delimiter("ID\tName\n1\tGori\n".as_bytes()).unwrap(), Delimiter::Tab);
+        assert_eq!(detect_delimiter("ID\n1\n".as_bytes()).unwrap(), Delimiter::Comma);
+
+        // Commas and pipes are equally consistent: comma wins, with a warning
+        let tie = "ID,Name|Amount\n1,Gori|10\n2,Mestia|20\n";
+        assert_eq!(rank_delimiters(tie.as_bytes()), (Delimiter::Comma, vec![Delimiter::Comma, Delimiter::Pipe]));
+
+        let config = ReconstructConfig {
+            delimiter: Delimiter::Auto,
+            ..ReconstructConfig::default()
+        };
+        let mut stats = Stats::default();
+        let rows = reconstruct_rows_from_reader(semicolons.as_bytes(), &config, &mut stats).unwrap();
+        assert_eq!(rows.len(), 4);
+        assert_eq!(&rows[2].record[1], "Gori\nBeverages");
+        assert!(stats.warnings.is_empty());
+
+        let mut stats = Stats::default();
+        reconstruct_rows_from_reader(tie.as_bytes(), &config, &mut stats).unwrap();
+        assert!(matches!(stats.warnings[0], Warning::AmbiguousDelimiter { chosen: Delimiter::Comma, .. }));
+    }
+
+    #[test]
+    fn test_vote_column_count() {
+        // Broken header and a fragmented record: the complete rows outvote both
+        let input = "ID,Name\n1,Tbilisi Waters,10\n2,Gori\nBeverages,20\n3,Mestia,30\n4,Sarme,40\n";
+        let vote = vote_column_count(input.as_bytes(), HeaderMode::HasHeaders, Delimiter::Comma).unwrap();
+        assert_eq!(vote.columns, 3);
+        assert_eq!(vote.confidence, 0.6);
+        assert!(vote_column_count(&b""[..], HeaderMode::NoHeaders, Delimiter::Comma).is_err());
+
+        let headerless = &input[input.find('\n').unwrap() + 1..];
+        let config = ReconstructConfig {
+            header_mode: HeaderMode::NoHeaders,
+            column_count_strategy: crate::ColumnCountStrategy::MajorityVote,
+            ..ReconstructConfig::default()
+        };
+        let rows = reconstruct_rows_from_reader(headerless.as_bytes(), &config, &mut Stats::default()).unwrap();
+        assert_eq!(rows.len(), 4);
+        assert_eq!(&rows[1].record[1], "Gori\nBeverages");
+    }
+
+    #[test]
+    fn test_per_row_delimiter_detection() {
+        let input = "ID,Name,Notes\n1,Tbilisi Waters,\"a; b\"\n2\tGori, Ltd\tx\n3;Mestia;\"tab\there\"\n4,Sarme\nBeverages,y\n5\tonly two\n";
+        let config = ReconstructConfig {
+            per_row_delimiter_detection: true,
+            ..ReconstructConfig::default()
+        };
+        let mut stats = Stats::default();
+        let rows = reconstruct_rows_from_reader(input.as_bytes(), &config, &mut stats).unwrap();
+        let records: Vec<Vec<&str>> = rows.iter().map(|row| row.record.iter().collect()).collect();
+
+        assert_eq!(
+            records,
+            vec![
+                vec!["ID", "Name", "Notes"],
+                vec!["1", "Tbilisi Waters", "a; b"],
+                vec!["2", "Gori, Ltd", "x"],
+                vec!["3", "Mestia", "tab\there"],
+                vec!["4", "Sarme\nBeverages", "y"],
+            ]
+        );
+        // "5<TAB>only two" fits no candidate and is discarded
+        assert_eq!(stats.removed_rows, 1);
+    }
+}
@@ -0,0 +1,327 @@
+// src/encoding.rs
+//! Character encoding support
+//!
+//! The engine works on UTF-8. [`DecodingReader`] converts legacy input
+//! charsets to UTF-8 before the csv reader sees them, and
+//! [`TranscodingWriter`] converts the UTF-8 output of the csv writer to a
+//! legacy target charset on the way to disk.
+
+use encoding_rs::{CoderResult, Decoder, EncoderResult, Encoding, UTF_8, WINDOWS_1251};
+use std::io::{self, Read, Write};
+
+/// Bytes inspected by [`InputEncoding::Auto`] before choosing an encoding
+pub const ENCODING_SNIFF_BYTES: usize = 64 * 1024;
+
+/// Encoding [`InputEncoding::Auto`] assumes for input that is not UTF-8
+pub const AUTO_FALLBACK_ENCODING: &Encoding = WINDOWS_1251;
+
+/// Size of the raw chunks [`DecodingReader`] reads from its source
+const DECODE_CHUNK_BYTES: usize = 8 * 1024;
+
+/// Character encoding of the input
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InputEncoding {
+    /// UTF-8, passed through unchanged (default)
+    #[default]
+    Utf8,
+    /// A fixed encoding such as `WINDOWS_1251`; a byte-order mark at the
+    /// start overrides it
+    Fixed(&'static Encoding),
+    /// A byte-order mark if present; otherwise UTF-8 if the first
+    /// [`ENCODING_SNIFF_BYTES`] are valid UTF-8, else
+    /// [`AUTO_FALLBACK_ENCODING`]
+    Auto,
+}
+
+/// A `Read` adapter that decodes its source to UTF-8
+///
+/// Malformed byte sequences become U+FFFD and byte-order marks are removed.
+/// With [`InputEncoding::Utf8`], or when `Auto` finds UTF-8 without a
+/// byte-order mark, bytes pass through untouched.
+///
+/// Font-hack encodings such as AcadNusx, which store Georgian as Latin
+/// code points, are valid text in their declared charset and cannot be
+/// recognized here.
+pub struct DecodingReader<R: Read> {
+    inner: R,
+    encoding: InputEncoding,
+    /// `None` while undecided (`Auto`) or for UTF-8 pass-through
+    decoder: Option<Decoder>,
+    decided: bool,
+    /// Decoded bytes not yet handed out, starting at `position`
+    decoded: Vec<u8>,
+    position: usize,
+    finished: bool,
+}
+
+impl<R: Read> DecodingReader<R> {
+    /// Wrap `inner`, decoding it from `encoding`
+    pub fn new(inner: R, encoding: InputEncoding) -> Self {
+        Self {
+            inner,
+            encoding,
+            decoder: None,
+            decided: false,
+            decoded: Vec::new(),
+            position: 0,
+            finished: false,
+        }
+    }
+
+    /// Decide the encoding on first use; returns the raw bytes read for it
+    fn decide(&mut self) -> io::Result<Vec<u8>> {
+        self.decided = true;
+        match self.encoding {
+            InputEncoding::Utf8 => Ok(Vec::new()),
+            InputEncoding::Fixed(encoding) => {
+                self.decoder = Some(encoding.new_decoder());
+                Ok(Vec::new())
+            }
+            InputEncoding::Auto => {
+                let mut sample = Vec::new();
+                (&mut self.inner).take(ENCODING_SNIFF_BYTES as u64).read_to_end(&mut sample)?;
+                let encoding = match Encoding::for_bom(&sample) {
+                    Some((encoding, _)) => Some(encoding),
+                    None if looks_like_utf8(&sample) => None,
+                    None => Some(AUTO_FALLBACK_ENCODING),
+                };
+                self.decoder = encoding.map(Encoding::new_decoder);
+                Ok(sample)
+            }
+        }
+    }
+
+    /// Decode the next chunk into `decoded`; false once the source is exhausted
+    fn fill(&mut self) -> io::Result<bool> {
+        let mut raw = if self.decided { Vec::new() } else { self.decide()? };
+        if self.position < self.decoded.len() {
+            return Ok(true);
+        }
+        self.decoded.clear();
+        self.position = 0;
+
+        while self.decoded.is_empty() && !self.finished {
+            if raw.is_empty() {
+                raw.resize(DECODE_CHUNK_BYTES, 0);
+                let read = self.inner.read(&mut raw)?;
+                raw.truncate(read);
+            }
+            let last = raw.is_empty();
+            let decoder = match &mut self.decoder {
+                Some(decoder) => decoder,
+                None => {
+                    self.finished = last;
+                    self.decoded = std::mem::take(&mut raw);
+                    continue;
+                }
+            };
+
+            let capacity = decoder.max_utf8_buffer_length(raw.len()).unwrap_or(raw.len() * 3 + 16);
+            self.decoded.resize(capacity, 0);
+            let mut written_total = 0;
+            let mut remaining = &raw[..];
+            loop {
+                let (result, read, written, _) =
+                    decoder.decode_to_utf8(remaining, &mut self.decoded[written_total..], last);
+                written_total += written;
+                remaining = &remaining[read..];
+                match result {
+                    CoderResult::InputEmpty => break,
+                    CoderResult::OutputFull => {
+                        let grow = self.decoded.len() + 16;
+                        self.decoded.resize(grow * 2, 0);
+                    }
+                }
+            }
+            self.decoded.truncate(written_total);
+            self.finished = last;
+            raw.clear();
+        }
+        Ok(!self.decoded.is_empty())
+    }
+}
+
+impl<R: Read> Read for DecodingReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if self.position >= self.decoded.len() && !self.fill()? {
+            return Ok(0);
+        }
+        let available = &self.decoded[self.position..];
+        let n = available.len().min(buf.len());
+        buf[..n].copy_from_slice(&available[..n]);
+        self.position += n;
+        Ok(n)
+    }
+}
+
+/// What to do with characters that don't exist in the target encoding
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UnmappableAction {
+    /// Fail the write with an `InvalidData` error
+    Error,
+    /// Substitute a `?` and count the occurrence (default)
+    #[default]
+    Replace,
+}
+
+/// A `Write` adapter that transcodes UTF-8 input to a target encoding
+///
+/// Multi-byte characters split across `write` calls are buffered until they
+/// are complete. Call [`finish`](Self::finish) to flush and obtain the number
+/// of unmappable characters that were replaced.
+pub struct TranscodingWriter<W: Write> {
+    inner: W,
+    encoding: &'static Encoding,
+    action: UnmappableAction,
+    pending: Vec<u8>,
+    unmappable: usize,
+}
+
+impl<W: Write> TranscodingWriter<W> {
+    /// Wrap `inner`, encoding everything written to it as `encoding`
+    pub fn new(inner: W, encoding: &'static Encoding, action: UnmappableAction) -> Self {
+        Self {
+            inner,
+            encoding,
+            action,
+            pending: Vec::new(),
+            unmappable: 0,
+        }
+    }
+
+    /// Flush remaining data and return the count of replaced characters
+    pub fn finish(self) -> io::Result<usize> {
+        self.into_parts().map(|(_, unmappable)| unmappable)
+    }
+
+    /// Like [`finish`](Self::finish), but also hand back the inner writer
+    pub fn into_parts(mut self) -> io::Result<(W, usize)> {
+        if !self.pending.is_empty() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "output ended with an incomplete UTF-8 sequence",
+            ));
+        }
+        self.inner.flush()?;
+        Ok((self.inner, self.unmappable))
+    }
+
+    fn encode(&mut self, text: &str) -> io::Result<()> {
+        if self.encoding == UTF_8 {
+            return self.inner.write_all(text.as_bytes());
+        }
+
+        let mut encoder = self.encoding.new_encoder();
+        let mut output = vec![0u8; text.len() * 2 + 16];
+        let mut remaining = text;
+
+        loop {
+            let (result, read, written) =
+                encoder.encode_from_utf8_without_replacement(remaining, &mut output, true);
+            self.inner.write_all(&output[..written])?;
+            remaining = &remaining[read..];
+
+            match result {
+                EncoderResult::InputEmpty => return Ok(()),
+                EncoderResult::OutputFull => continue,
+                EncoderResult::Unmappable(c) => match self.action {
+                    UnmappableAction::Replace => {
+                        self.unmappable += 1;
+                        self.inner.write_all(b"?")?;
+                    }
+                    UnmappableAction::Error => {
+                        return Err(io::Error::new(
+                            io::ErrorKind::InvalidData,
+                            format!("character '{c}' cannot be represented in {}", self.encoding.name()),
+                        ));
+                    }
+                },
+            }
+        }
+    }
+}
+
+impl<W: Write> Write for TranscodingWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.pending.extend_from_slice(buf);
+
+        // Encode the longest valid UTF-8 prefix; keep a split character pending
+        let valid = match std::str::from_utf8(&self.pending) {
+            Ok(_) => self.pending.len(),
+            Err(e) if e.error_len().is_none() => e.valid_up_to(),
+            Err(e) => return Err(io::Error::new(io::ErrorKind::InvalidData, e)),
+        };
+        let complete: Vec<u8> = self.pending.drain(..valid).collect();
+        let text = std::str::from_utf8(&complete).expect("prefix validated above");
+        self.encode(text)?;
+
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+/// True if `sample` is UTF-8, allowing a character cut off at its end
+fn looks_like_utf8(sample: &[u8]) -> bool {
+    match std::str::from_utf8(sample) {
+        Ok(_) => true,
+        Err(e) => e.error_len().is_none(),
+    }
+}
+
+/// Look up an encoding by its WHATWG label (e.g. `windows-1252`, `utf-8`)
+pub fn encoding_for_label(label: &str) -> Option<&'static Encoding> {
+    Encoding::for_label(label.trim().as_bytes())
+}
+
+// ============================================
+// Unit Tests
+// ============================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use encoding_rs::{UTF_16LE, WINDOWS_1252};
+
+    fn decode(bytes: &[u8], encoding: InputEncoding) -> String {
+        let mut text = String::new();
+        DecodingReader::new(bytes, encoding).read_to_string(&mut text).unwrap();
+        text
+    }
+
+    #[test]
+    fn test_decoding_reader_converts_legacy_input() {
+        let (cp1251, _, _) = WINDOWS_1251.encode("ID,Имя\n1,Тбилиси\n");
+        assert_eq!(decode(&cp1251, InputEncoding::Fixed(WINDOWS_1251)), "ID,Имя\n1,Тбилиси\n");
+        assert_eq!(decode(&cp1251, InputEncoding::Auto), "ID,Имя\n1,Тбилиси\n");
+
+        let mut utf16 = vec![0xFF, 0xFE];
+        utf16.extend("ID,სახელი\n".encode_utf16().flat_map(u16::to_le_bytes));
+        assert_eq!(decode(&utf16, InputEncoding::Auto), "ID,სახელი\n");
+        assert_eq!(decode(&utf16, InputEncoding::Fixed(WINDOWS_1251)), "ID,სახელი\n");
+        assert_eq!(Encoding::for_bom(&utf16).map(|(e, _)| e), Some(UTF_16LE));
+
+        let utf8 = "\u{FEFF}ID,სახელი\n".as_bytes();
+        assert_eq!(decode(utf8, InputEncoding::Auto), "ID,სახელი\n");
+        assert_eq!(decode(utf8, InputEncoding::Utf8), "\u{FEFF}ID,სახელი\n");
+    }
+
+    #[test]
+    fn test_split_multibyte_character_is_reassembled() {
+        let mut writer = TranscodingWriter::new(Vec::new(), WINDOWS_1252, UnmappableAction::Replace);
+        let bytes = "é".as_bytes();
+        writer.write_all(&bytes[..1]).unwrap();
+        writer.write_all(&bytes[1..]).unwrap();
+        assert_eq!(writer.inner, vec![0xE9]);
+        assert_eq!(writer.finish().unwrap(), 0);
+    }
+
+    #[test]
+    fn test_unmappable_error_policy() {
+        let mut writer = TranscodingWriter::new(Vec::new(), WINDOWS_1252, UnmappableAction::Error);
+        let err = writer.write_all("ქ".as_bytes()).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    }
+}
@@ -7,17 +7,41 @@
 //! This module handles the "business logic" of the application. It is designed to be
 //! decoupled from the UI, allowing for future integration into other frontends (e.g., WebAssembly).
 
+use crate::aggregate::group_rows;
+use crate::collapse::collapse_delimiter_runs;
+use crate::config::{ReconstructConfig, TypeHintPolicy};
+use crate::detect::{detect_quote, unify_row_delimiters, QUOTE_SAMPLE_BYTES};
+use crate::encoding::DecodingReader;
+use crate::error::FixerrError;
+use crate::history::reconstruct_with_history;
+use crate::infer::{infer_column_types, typed_header, ColumnType, DEFAULT_INFERENCE_SAMPLE};
+use crate::mode::ProcessingMode;
+use crate::numeric::canonicalize_number;
+use crate::options::ReconstructOptions;
+use crate::retry::RetryingReader;
+use crate::reverse::reconstruct_reversed;
+use crate::scrub::scrub_leading_junk;
+use crate::sort::sort_rows;
+use crate::stitch::{resolve_dialect, strategy_width, RowSource, Stitcher};
+use crate::validate::{check_sequence, check_type_hints};
+use crate::warning::Warning;
 use csv::{Reader, ReaderBuilder, StringRecord, WriterBuilder};
-use std::error::Error;
-use std::fs::File;
-use std::io::{self, Write};
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use std::collections::{HashMap, HashSet};
+use std::fs::{self, File};
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
 
 // ============================================
 // Public Types
 // ============================================
 
 /// Header mode for CSV files
-#[derive(Default, Debug, Clone, Copy)]
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum HeaderMode {
     /// File has a header row (default)
     #[default]
@@ -33,8 +57,23 @@ impl HeaderMode {
     }
 }
 
+impl FromStr for HeaderMode {
+    type Err = String;
+
+    /// Parse `headers`/`has-headers`/`yes` or `none`/`no-headers`/`no`,
+    /// ignoring case and surrounding whitespace
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().replace('_', "-").as_str() {
+            "headers" | "has-headers" | "hasheaders" | "yes" | "true" => Ok(HeaderMode::HasHeaders),
+            "none" | "no-headers" | "noheaders" | "no" | "false" => Ok(HeaderMode::NoHeaders),
+            _ => Err(format!("unknown header mode '{}'", s.trim())),
+        }
+    }
+}
+
 /// Delimiter character for CSV files
-#[derive(Default, Debug, Clone, Copy)]
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Delimiter {
     /// Comma separator (default)
     #[default]
@@ -45,22 +84,310 @@ pub enum Delimiter {
     Tab,
     /// Pipe separator
     Pipe,
+    /// Detected from the start of the input when reconstructing; writers
+    /// and other consumers that see it unresolved use a comma
+    Auto,
+    /// Any other single byte, e.g. `^` or the ASCII unit separator `0x1F`
+    Custom(u8),
 }
 
 impl Delimiter {
     /// Convert to byte for CSV reader/writer
     pub fn as_byte(&self) -> u8 {
         match self {
-            Delimiter::Comma => b',',
+            Delimiter::Comma | Delimiter::Auto => b',',
             Delimiter::Semicolon => b';',
             Delimiter::Tab => b'\t',
             Delimiter::Pipe => b'|',
+            Delimiter::Custom(byte) => *byte,
+        }
+    }
+}
+
+/// Character that encloses quoted fields
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QuoteChar {
+    /// Double quote `"` (default)
+    #[default]
+    Double,
+    /// Single quote `'`
+    Single,
+    /// Any other byte
+    Custom(u8),
+    /// Detected from the start of the input, falling back to `"`
+    Auto,
+}
+
+impl QuoteChar {
+    /// Quote byte for the csv reader, detecting it from `sample` for `Auto`
+    pub fn resolve(&self, sample: &[u8], delimiter: Delimiter) -> u8 {
+        match self {
+            QuoteChar::Double => b'"',
+            QuoteChar::Single => b'\'',
+            QuoteChar::Custom(byte) => *byte,
+            QuoteChar::Auto => detect_quote(sample, delimiter).unwrap_or(b'"'),
+        }
+    }
+
+    /// Quote byte for written files; there is nothing to detect, so `Auto`
+    /// writes `"`
+    pub fn output_byte(&self) -> u8 {
+        match self {
+            QuoteChar::Auto => b'"',
+            quote => quote.resolve(&[], Delimiter::Comma),
+        }
+    }
+}
+
+impl FromStr for Delimiter {
+    type Err = String;
+
+    /// Parse a delimiter name (`comma`, `semicolon`, `tab`, `pipe`, `auto`),
+    /// the literal character, or a byte in hex such as `0x1F`, ignoring case
+    /// and surrounding whitespace. Other single ASCII characters become
+    /// [`Delimiter::Custom`]; quotes and line breaks are rejected
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        // A bare tab is whitespace itself, so check for it before trimming
+        if s.trim().is_empty() && s.contains('\t') {
+            return Ok(Delimiter::Tab);
+        }
+        match s.trim().to_ascii_lowercase().as_str() {
+            "comma" | "," => Ok(Delimiter::Comma),
+            "semicolon" | ";" => Ok(Delimiter::Semicolon),
+            "tab" | "\\t" => Ok(Delimiter::Tab),
+            "pipe" | "|" => Ok(Delimiter::Pipe),
+            "auto" => Ok(Delimiter::Auto),
+            other => {
+                let byte = match other.strip_prefix("0x").or_else(|| other.strip_prefix("\\x")) {
+                    Some(hex) => u8::from_str_radix(hex, 16).ok(),
+                    None if other.len() == 1 => Some(other.as_bytes()[0]),
+                    None => None,
+                };
+                match byte {
+                    Some(byte) if byte.is_ascii() && !matches!(byte, b'"' | b'\'' | b'\r' | b'\n') => {
+                        Ok(Delimiter::Custom(byte))
+                    }
+                    _ => Err(format!("unknown delimiter '{}'", s.trim())),
+                }
+            }
+        }
+    }
+}
+
+/// Line break sequence
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LineTerminator {
+    /// `\n` (default)
+    #[default]
+    Lf,
+    /// `\r\n`
+    CrLf,
+    /// `\r`
+    Cr,
+}
+
+impl LineTerminator {
+    /// The terminator as a string
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LineTerminator::Lf => "\n",
+            LineTerminator::CrLf => "\r\n",
+            LineTerminator::Cr => "\r",
+        }
+    }
+
+    /// Replace every `\r\n`, `\r` and `\n` in `text` with this terminator
+    pub fn normalize(&self, text: &str) -> String {
+        let unified = text.replace("\r\n", "\n").replace('\r', "\n");
+        match self {
+            LineTerminator::Lf => unified,
+            _ => unified.replace('\n', self.as_str()),
+        }
+    }
+}
+
+/// Which fields of written files are quoted
+///
+/// `Never` can produce output that no csv parser reads back correctly when a
+/// field still contains the delimiter, the quote or a line break.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QuoteStyle {
+    /// Every field
+    Always,
+    /// Only fields containing the delimiter, the quote or a line break
+    /// (default)
+    #[default]
+    Necessary,
+    /// Fields that are not numbers, plus those that need it
+    NonNumeric,
+    /// No field, not even those that need it
+    Never,
+}
+
+impl QuoteStyle {
+    /// The matching quoting style of the csv writer
+    pub fn as_csv(&self) -> csv::QuoteStyle {
+        match self {
+            QuoteStyle::Always => csv::QuoteStyle::Always,
+            QuoteStyle::Necessary => csv::QuoteStyle::Necessary,
+            QuoteStyle::NonNumeric => csv::QuoteStyle::NonNumeric,
+            QuoteStyle::Never => csv::QuoteStyle::Never,
+        }
+    }
+
+    /// Whether to quote `field`, given whether it `needs` quotes
+    pub fn quotes(&self, field: &str, needs: bool) -> bool {
+        match self {
+            QuoteStyle::Always => true,
+            QuoteStyle::Necessary => needs,
+            QuoteStyle::NonNumeric => needs || field.parse::<f64>().is_err(),
+            QuoteStyle::Never => false,
+        }
+    }
+}
+
+/// Line terminator of written files
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LineEnding {
+    /// `\n` (default)
+    #[default]
+    Lf,
+    /// `\r\n`
+    CrLf,
+    /// Whichever of `\n` and `\r\n` ends most lines of the input, see
+    /// [`Stats::dominant_line_ending`]
+    Auto,
+}
+
+impl LineEnding {
+    /// The terminator to write, given the statistics of the reconstruction
+    pub fn resolve(&self, stats: &Stats) -> LineTerminator {
+        match self {
+            LineEnding::Lf => LineTerminator::Lf,
+            LineEnding::CrLf => LineTerminator::CrLf,
+            LineEnding::Auto => stats.dominant_line_ending(),
+        }
+    }
+}
+
+/// How whitespace inside a field is cleaned up on output
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NormalizeMode {
+    /// Collapse every whitespace run, line breaks included, to one space (default)
+    #[default]
+    Collapse,
+    /// Collapse whitespace runs within each line but keep the line breaks;
+    /// the csv writer quotes such fields
+    PreserveNewlines,
+    /// Only trim leading and trailing whitespace
+    TrimOnly,
+}
+
+impl NormalizeMode {
+    /// Clean up `field` according to this mode
+    pub fn apply(&self, field: &str) -> String {
+        match self {
+            NormalizeMode::Collapse => clean_and_normalize_field(field),
+            NormalizeMode::PreserveNewlines => field
+                .trim()
+                .split('\n')
+                .map(|line| {
+                    let collapsed = clean_and_normalize_field(line);
+                    if line.ends_with('\r') {
+                        collapsed + "\r"
+                    } else {
+                        collapsed
+                    }
+                })
+                .collect::<Vec<_>>()
+                .join("\n"),
+            NormalizeMode::TrimOnly => field.trim().to_string(),
         }
     }
 }
 
+/// How to handle a physical row that is wider than the expected column count
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OverflowStrategy {
+    /// Discard the row (default)
+    #[default]
+    Discard,
+    /// If the width is an exact multiple of the expected count, treat the row
+    /// as several records glued together by a missing newline and split it.
+    /// Rows arriving while a split record is being stitched are discarded
+    SplitAtExpected,
+    /// Treat the surplus fields as text that contained unescaped delimiters
+    /// and merge them back into the free-text column: the first of
+    /// `free_text_columns`, or the last column when none are given. Rows
+    /// arriving while a split record is being stitched are discarded
+    SmartMerge,
+}
+
+/// How the expected column count of a file is determined
+///
+/// `Detect` trusts the header (or `expected_columns` in `NoHeaders` mode).
+/// That fails on files where fragmentation is so pervasive that the header is
+/// missing or unreliable and short rows outnumber complete ones.
+#[derive(Default, Debug, Clone, PartialEq)]
+pub enum ColumnCountStrategy {
+    /// Use the header width, or `expected_columns` / a prompt without headers (default)
+    #[default]
+    Detect,
+    /// Use the width at this quantile (in `(0, 1]`) of all observed data row
+    /// widths, e.g. `0.9` for the 90th percentile
+    ///
+    /// Requires a full pass over the input before reconstruction, so the
+    /// input is buffered in memory. A quantile set too high lets a few rows
+    /// with stray delimiters inflate the count; one set too low falls back
+    /// into the short-row majority. Overrides the header width when set.
+    Quantile(f64),
+    /// Use the width of the first record of a reference file that has the
+    /// correct structure, e.g. a maintained header-only CSV
+    FromReferenceFile(PathBuf),
+    /// Use the most common width among the first
+    /// [`COLUMN_VOTE_SAMPLE_ROWS`](crate::COLUMN_VOTE_SAMPLE_ROWS) data rows
+    /// (see [`vote_column_count`](crate::vote_column_count)). Works without a
+    /// header and overrides the header width when set
+    MajorityVote,
+}
+
+/// A reconstructed logical record together with how it was produced
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReconstructedRow {
+    /// The logical record
+    pub record: StringRecord,
+    /// True if the record had to be repaired (stitched or split) rather than
+    /// being read intact from a single physical row
+    pub fixed: bool,
+    /// 1-based line numbers at which each contributing physical row starts
+    /// (empty for synthesized rows such as generated headers)
+    pub source_lines: Vec<u64>,
+    /// True if this row is a header rather than data
+    pub header: bool,
+    /// 0-based section index (always 0 unless `multi_section` is enabled)
+    pub section: usize,
+    /// Whether each field was quoted in the input; recorded for rows read
+    /// intact when `preserve_quoting` is set, empty otherwise
+    pub quoted: Vec<bool>,
+}
+
+impl ReconstructedRow {
+    pub(crate) fn clean(record: StringRecord, source_lines: Vec<u64>) -> Self {
+        Self { record, fixed: false, source_lines, header: false, section: 0, quoted: Vec::new() }
+    }
+
+    pub(crate) fn fixed(record: StringRecord, source_lines: Vec<u64>) -> Self {
+        Self { fixed: true, ..Self::clean(record, source_lines) }
+    }
+
+    pub(crate) fn header(record: StringRecord, source_lines: Vec<u64>) -> Self {
+        Self { header: true, ..Self::clean(record, source_lines) }
+    }
+}
+
 /// Statistics about CSV processing
-#[derive(Default, Debug)]
+#[derive(Default, Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Stats {
     /// Total physical rows read from file
     pub total_rows: usize,
@@ -68,6 +395,155 @@ pub struct Stats {
     pub fixed_rows: usize,
     /// Number of rows that couldn't be reconstructed and were discarded
     pub removed_rows: usize,
+    /// Physical rows that ended up in the output; section headers count, a
+    /// detected leading header does not
+    pub rows_in_output: usize,
+    /// Physical rows belonging to discarded records
+    pub rows_discarded: usize,
+    /// 1-based lines of the physical rows counted in `rows_discarded`
+    pub discarded_lines: Vec<u64>,
+    /// 1-based line on which each repaired record starts
+    pub fixed_lines: Vec<u64>,
+    /// Blank lines skipped between records, including lines of nothing but
+    /// delimiters and whitespace; these are not counted in `total_rows`
+    pub blank_lines: usize,
+    /// Stitched rows with a field over `max_newlines_per_field`
+    pub runaway_fields: usize,
+    /// Data records the `validate_fn` hook kept unchanged
+    pub records_kept: usize,
+    /// Data records the `validate_fn` hook dropped
+    pub records_dropped: usize,
+    /// Data records the `validate_fn` hook rewrote
+    pub records_replaced: usize,
+    /// Input bytes of the discarded rows, exactly as read; only collected
+    /// when `rejects_path` is set
+    pub rejected_raw: Vec<u8>,
+    /// Physical records of the discarded rows (over-long rows and groups
+    /// that never completed); only collected when `collect_discarded` is set.
+    /// Not serialized
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub discarded_records: Vec<StringRecord>,
+    /// Number of junk bytes (BOMs, control bytes) stripped before the first record
+    pub stripped_leading_bytes: usize,
+    /// Line breaks in the input written as `\r\n`
+    pub crlf_line_endings: usize,
+    /// Line breaks in the input written as a bare `\n`
+    pub lf_line_endings: usize,
+    /// Number of characters replaced because the output encoding lacks them
+    pub unmappable_chars: usize,
+    /// How [`reconstruct_file`](crate::reconstruct_file) processed the input;
+    /// `None` for the other entry points
+    pub processing_mode: Option<ProcessingMode>,
+    /// Non-fatal problems detected during processing
+    pub warnings: Vec<Warning>,
+}
+
+/// Headline figures of a run, as shown in the summary table
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct StatsSummary {
+    /// Physical data rows read
+    pub total_rows: usize,
+    /// Records reconstructed from several physical rows or otherwise repaired
+    pub fixed_rows: usize,
+    /// Rows discarded as unrepairable
+    pub removed_rows: usize,
+    /// Blank lines skipped
+    pub blank_lines: usize,
+    /// Number of warnings raised
+    pub warnings: usize,
+    /// Percentage of rows read that were not discarded, see [`Stats::success_rate`]
+    pub success_rate: f64,
+}
+
+impl Stats {
+    /// Percentage of the rows read that were not discarded, `0.0` if no
+    /// rows were read
+    pub fn success_rate(&self) -> f64 {
+        if self.total_rows == 0 {
+            0.0
+        } else {
+            self.total_rows.saturating_sub(self.removed_rows) as f64 / self.total_rows as f64 * 100.0
+        }
+    }
+
+    /// The headline figures of these statistics, for callers that format
+    /// their own report
+    pub fn summary(&self) -> StatsSummary {
+        StatsSummary {
+            total_rows: self.total_rows,
+            fixed_rows: self.fixed_rows,
+            removed_rows: self.removed_rows,
+            blank_lines: self.blank_lines,
+            warnings: self.warnings.len(),
+            success_rate: self.success_rate(),
+        }
+    }
+
+    /// `CrLf` if more input lines ended in `\r\n` than in `\n`, else `Lf`
+    pub fn dominant_line_ending(&self) -> LineTerminator {
+        if self.crlf_line_endings > self.lf_line_endings {
+            LineTerminator::CrLf
+        } else {
+            LineTerminator::Lf
+        }
+    }
+
+    /// Count the physical rows starting on `lines` as discarded
+    pub(crate) fn discard_lines(&mut self, lines: &[u64]) {
+        self.rows_discarded += lines.len();
+        self.discarded_lines.extend_from_slice(lines);
+    }
+
+    /// Add the counters, warnings and rejected bytes of `other` to these
+    pub(crate) fn absorb(&mut self, other: Stats) {
+        self.total_rows += other.total_rows;
+        self.fixed_rows += other.fixed_rows;
+        self.removed_rows += other.removed_rows;
+        self.rows_in_output += other.rows_in_output;
+        self.rows_discarded += other.rows_discarded;
+        self.discarded_lines.extend(other.discarded_lines);
+        self.fixed_lines.extend(other.fixed_lines);
+        self.blank_lines += other.blank_lines;
+        self.runaway_fields += other.runaway_fields;
+        self.records_kept += other.records_kept;
+        self.records_dropped += other.records_dropped;
+        self.records_replaced += other.records_replaced;
+        self.stripped_leading_bytes += other.stripped_leading_bytes;
+        self.crlf_line_endings += other.crlf_line_endings;
+        self.lf_line_endings += other.lf_line_endings;
+        self.unmappable_chars += other.unmappable_chars;
+        self.processing_mode = self.processing_mode.or(other.processing_mode);
+        self.rejected_raw.extend(other.rejected_raw);
+        self.discarded_records.extend(other.discarded_records);
+        self.warnings.extend(other.warnings);
+    }
+
+    /// Render the summary as an aligned plain-text table
+    ///
+    /// Uses only ASCII so it lines up in any terminal or log file. Nothing is
+    /// printed; the caller decides where the table goes.
+    pub fn render_table(&self, total_records: usize, output: &str) -> String {
+        let summary = self.summary();
+        let rows = [
+            ("Total lines read", summary.total_rows.to_string()),
+            ("Fixed/Merged rows", summary.fixed_rows.to_string()),
+            ("Discarded rows", summary.removed_rows.to_string()),
+            ("Total valid records", total_records.to_string()),
+            ("Success rate", format!("{:.1}%", summary.success_rate)),
+            ("Warnings", summary.warnings.to_string()),
+            ("Output", output.to_string()),
+        ];
+
+        let label_width = rows.iter().map(|(label, _)| label.len()).max().unwrap_or(0);
+        let value_width = rows.iter().map(|(_, value)| value.len()).max().unwrap_or(0);
+
+        let mut table = String::new();
+        for (label, value) in &rows {
+            table.push_str(&format!("{label:<label_width$}  {value:>value_width$}\n"));
+        }
+        table
+    }
 }
 
 // ============================================
@@ -75,127 +551,367 @@ pub struct Stats {
 // ============================================
 
 /// Build a configured CSV reader
-pub fn build_csv_reader<R: std::io::Read>(
+pub fn build_csv_reader<R: Read>(
     reader: R,
     header_mode: HeaderMode,
     delimiter: Delimiter,
 ) -> Reader<R> {
-    ReaderBuilder::new()
-        .has_headers(header_mode.as_bool())
-        .delimiter(delimiter.as_byte())
-        .flexible(true) // Allow varying column counts to handle broken rows
-        .from_reader(reader)
+    build_quoted_reader(reader, header_mode, delimiter, b'"', None)
 }
 
 /// Reconstruct malformed CSV records into proper format
 ///
 /// This function reads a CSV file that may have malformed records (e.g., records
 /// split across multiple physical lines due to embedded newlines) and reconstructs
-/// them into proper CSV records.
+/// them into proper CSV records. Headerless files need their column count; use
+/// [`reconstruct_records_with_columns`] for those. A `.gz` file is
+/// decompressed on the fly.
+///
+/// Iterate a [`RecordReconstructor`](crate::RecordReconstructor) instead to
+/// process files too large to hold in memory.
 pub fn reconstruct_records(
     file_path: &str,
     header_mode: HeaderMode,
     delimiter: Delimiter,
     stats: &mut Stats,
-) -> Result<Vec<StringRecord>, Box<dyn Error>> {
-    let file = File::open(file_path)?;
-    let mut reader = build_csv_reader(file, header_mode, delimiter);
+) -> Result<Vec<StringRecord>, FixerrError> {
+    let config = ReconstructConfig::new(header_mode, delimiter);
+    reconstruct_records_with_config(file_path, &config, stats)
+}
 
-    // Detect expected column count
-    let (expected_columns, maybe_headers) = detect_column_count(&mut reader, header_mode)?;
+/// Reconstruct records, also returning the physical records that were discarded
+///
+/// The second vector holds every over-long row and every row of a group that
+/// never reached the expected width, as parsed, in input order. Write them
+/// out for review with [`write_discarded_csv`].
+pub fn reconstruct_records_with_discarded(
+    file_path: &str,
+    header_mode: HeaderMode,
+    delimiter: Delimiter,
+    stats: &mut Stats,
+) -> Result<(Vec<StringRecord>, Vec<StringRecord>), FixerrError> {
+    let config = ReconstructConfig {
+        collect_discarded: true,
+        ..ReconstructConfig::new(header_mode, delimiter)
+    };
+    let records = reconstruct_records_with_config(file_path, &config, stats)?;
+    Ok((records, std::mem::take(&mut stats.discarded_records)))
+}
 
-    let mut logical_rows: Vec<StringRecord> = Vec::new();
+/// Reconstruct records from any byte source: stdin, a buffer, a decoder, ...
+///
+/// The reader counterpart of [`reconstruct_records`].
+pub fn reconstruct_records_from_reader<R: Read>(
+    reader: R,
+    header_mode: HeaderMode,
+    delimiter: Delimiter,
+    stats: &mut Stats,
+) -> Result<Vec<StringRecord>, FixerrError> {
+    ReconstructOptions::new()
+        .header_mode(header_mode)
+        .delimiter(delimiter)
+        .reconstruct(reader, stats)
+}
 
-    // Add headers to output if present
-    if let Some(h) = maybe_headers {
-        logical_rows.push(h);
+/// Reconstruct a headerless file whose column count is known
+///
+/// The `NoHeaders` counterpart of [`reconstruct_records`], which has no way
+/// to learn the width of a headerless file.
+pub fn reconstruct_records_with_columns(
+    file_path: &str,
+    delimiter: Delimiter,
+    expected_columns: usize,
+    stats: &mut Stats,
+) -> Result<Vec<StringRecord>, FixerrError> {
+    let mut config = ReconstructConfig::new(HeaderMode::NoHeaders, delimiter);
+    config.expected_columns = Some(expected_columns);
+    reconstruct_records_with_config(file_path, &config, stats)
+}
+
+/// Reconstruct malformed CSV records using a full [`ReconstructConfig`]
+///
+/// Same algorithm as [`reconstruct_records`], with the optional behaviours
+/// enabled by the configuration applied on top.
+pub fn reconstruct_records_with_config(
+    file_path: &str,
+    config: &ReconstructConfig,
+    stats: &mut Stats,
+) -> Result<Vec<StringRecord>, FixerrError> {
+    let rows = reconstruct_rows_with_config(file_path, config, stats)?;
+    Ok(rows.into_iter().map(|row| row.record).collect())
+}
+
+/// Reconstruct records, keeping per-row metadata such as the repair flag
+///
+/// This is the core of the engine; the record-only functions are thin
+/// wrappers around it. Rows come back in source order (by the first of their
+/// `source_lines`), in reverse mode and when streaming too; only `sort_by`
+/// reorders them, and `group_by` folds later rows into the first of a group.
+pub fn reconstruct_rows_with_config(
+    file_path: &str,
+    config: &ReconstructConfig,
+    stats: &mut Stats,
+) -> Result<Vec<ReconstructedRow>, FixerrError> {
+    if let Some(history_path) = &config.history_file {
+        return reconstruct_with_history(file_path, history_path, config, stats);
+    }
+    let input = open_input(file_path, config)?;
+    // The decompressed size of gzip input is unknown
+    let size = if is_gzip_input(file_path, config) { None } else { fs::metadata(file_path).ok().map(|m| m.len()) };
+    reconstruct_rows_sized(input, size, config, stats)
+}
+
+/// Open an input file, gunzipping it when `config.decompress` is set or the
+/// path ends in `.gz`
+///
+/// Opening and reading are retried according to `config.retry`.
+pub fn open_input(file_path: &str, config: &ReconstructConfig) -> Result<Box<dyn Read + Send>, FixerrError> {
+    let file = RetryingReader::new(config.retry.run_unwrapped(|| File::open(file_path))?, config.retry);
+    if is_gzip_input(file_path, config) {
+        Ok(Box::new(GzDecoder::new(file)))
+    } else {
+        Ok(Box::new(file))
     }
+}
+
+/// Whether [`open_input`] decompresses `file_path`
+pub(crate) fn is_gzip_input(file_path: &str, config: &ReconstructConfig) -> bool {
+    config.decompress || file_path.ends_with(".gz")
+}
 
-    // Buffer for accumulating fields across multiple physical rows
-    let mut buffer: Vec<String> = Vec::new();
+/// Reconstruct rows from any byte source
+///
+/// Performs no file or terminal IO of its own, which makes it suitable for
+/// in-memory data and benchmarks. In `NoHeaders` mode the column count must
+/// come from `expected_columns` or the `column_count_strategy`.
+pub fn reconstruct_rows_from_reader<R: Read>(
+    source: R,
+    config: &ReconstructConfig,
+    stats: &mut Stats,
+) -> Result<Vec<ReconstructedRow>, FixerrError> {
+    reconstruct_rows_sized(source, None, config, stats)
+}
 
-    for result in reader.records() {
-        stats.total_rows += 1;
-        let record = result?;
-        let rec_len = record.len();
+/// [`reconstruct_rows_from_reader`] with the input size for progress events
+fn reconstruct_rows_sized<R: Read>(
+    source: R,
+    size: Option<u64>,
+    config: &ReconstructConfig,
+    stats: &mut Stats,
+) -> Result<Vec<ReconstructedRow>, FixerrError> {
+    let source = DecodingReader::new(source, config.input_encoding);
+    let (stripped, source) = scrub_leading_junk(source)?;
+    stats.stripped_leading_bytes += stripped;
 
-        // Check: Immediate Over-Length Check
-        //
-        // If the physical row itself has more columns than expected, it is 
-        // statistically impossible for it to be a valid part of a split record 
-        // (which should be shorter) or a valid full record. Discard immediately.
-        if rec_len > expected_columns {
-            stats.removed_rows += 1;
-            continue;
+    // Sample the start of the input for dialect detection, then put it back
+    let mut sample = Vec::new();
+    let mut source = source;
+    let vote_width = config.column_count_strategy == ColumnCountStrategy::MajorityVote;
+    if config.quote == QuoteChar::Auto || config.delimiter == Delimiter::Auto || vote_width {
+        (&mut source).take(QUOTE_SAMPLE_BYTES as u64).read_to_end(&mut sample)?;
+    }
+    let (delimiter, quote) = resolve_dialect(&sample, config, stats);
+    let resolved;
+    let config = if config.delimiter == delimiter {
+        config
+    } else {
+        resolved = ReconstructConfig { delimiter, ..config.clone() };
+        &resolved
+    };
+
+    // Passes that rewrite the whole input before it is parsed
+    if config.collapse_consecutive_delimiters || config.per_row_delimiter_detection {
+        let mut data = Vec::new();
+        io::Cursor::new(sample).chain(source).read_to_end(&mut data)?;
+        if config.collapse_consecutive_delimiters {
+            data = collapse_delimiter_runs(&data, config.delimiter.as_byte(), quote);
         }
+        // Rows written with other delimiters are rewritten with the configured one
+        if config.per_row_delimiter_detection {
+            data = unify_row_delimiters(&data, config, quote)?;
+        }
+        let rewritten = ReconstructConfig {
+            collapse_consecutive_delimiters: false,
+            per_row_delimiter_detection: false,
+            ..config.clone()
+        };
+        return reconstruct_rows_sized(data.as_slice(), Some(data.len() as u64), &rewritten, stats);
+    }
 
-        // Case 1: Starting a new logical row
-        if buffer.is_empty() {
-            if rec_len == expected_columns {
-                // Complete row - add directly
-                logical_rows.push(record);
-            } else {
-                // Incomplete row - start buffering
-                buffer.extend(record.iter().map(|s| s.to_string()));
-            }
-            continue;
+    let width = strategy_width(config, quote, &sample)?;
+    let mut source = io::Cursor::new(sample).chain(source);
+
+    if let ColumnCountStrategy::Quantile(quantile) = config.column_count_strategy {
+        // Widths are sampled over the whole input before reconstructing it
+        let mut data = Vec::new();
+        source.read_to_end(&mut data)?;
+        let width = quantile_width(&data, config, quote, quantile)?;
+        return reconstruct_rows(data.as_slice(), Some(data.len() as u64), config, quote, width, stats);
+    }
+    let size = size.map(|size| size.saturating_sub(stripped as u64));
+    reconstruct_rows(source, size, config, quote, width, stats)
+}
+
+/// Reconstruct rows from physical lines held in memory
+///
+/// The lines are joined with `\n`, so source line numbers in the result are
+/// 1-based positions in `lines`. Handy for experimenting with snippets.
+pub fn reconstruct_from_lines<S: AsRef<str>>(
+    lines: &[S],
+    config: &ReconstructConfig,
+    stats: &mut Stats,
+) -> Result<Vec<ReconstructedRow>, FixerrError> {
+    let mut text = String::new();
+    for line in lines {
+        text.push_str(line.as_ref());
+        text.push('\n');
+    }
+    reconstruct_rows_from_reader(text.as_bytes(), config, stats)
+}
+
+/// Reconstruction pass; `width_override` replaces the detected column count
+fn reconstruct_rows<R: Read>(
+    source: R,
+    size: Option<u64>,
+    config: &ReconstructConfig,
+    quote: u8,
+    width_override: Option<usize>,
+    stats: &mut Stats,
+) -> Result<Vec<ReconstructedRow>, FixerrError> {
+    let header_mode = config.header_mode;
+    let mut rows = RowSource::open(source, config, quote, width_override, size)?;
+    let mut expected_columns = rows.expected_columns;
+    let detected_header_line = rows.header_line;
+
+    // Add headers to output if present
+    let mut logical_rows: Vec<ReconstructedRow> = rows.take_header().into_iter().collect();
+
+    if config.reverse {
+        // Reverse mode buffers and stitches the whole input on its own
+        logical_rows.extend(reconstruct_reversed(rows.reader_mut(), expected_columns, &config.stitch_separator, stats)?);
+        rows.finish(stats);
+    } else {
+        let mut stitcher = Stitcher::new(quote, expected_columns, detected_header_line);
+        // Raw bytes before a record boundary can no longer be rejected
+        while let Some(row) = rows.next_row(!stitcher.is_buffering(), stats)? {
+            stitcher.push(row, config, stats, &mut logical_rows);
         }
+        let unbalanced = rows.finish(stats);
+        stitcher.finish(unbalanced, config, stats, &mut logical_rows);
+        expected_columns = stitcher.expected_columns();
+    }
+    stats
+        .fixed_lines
+        .extend(logical_rows.iter().filter(|row| row.fixed).filter_map(|row| row.source_lines.first()));
 
-        // Case 2: Continuing a buffered row
-        // Append first field to last buffered field (handles embedded newlines)
-        if let Some(first_part) = record.get(0) {
-            if let Some(last_col) = buffer.last_mut() {
-                if !last_col.is_empty() {
-                    // DESIGN DECISION: Preserve the newline in the in-memory representation.
-                    // We maintain the data fidelity here (stitching exactly as it was broken).
-                    // Sanitization is deferred to the writing phase to separate concerns.
-                    last_col.push('\n'); 
+    // Synthesize a descriptive header for headerless files
+    if config.generate_typed_headers && matches!(header_mode, HeaderMode::NoHeaders) {
+        let records: Vec<StringRecord> = logical_rows.iter().map(|r| r.record.clone()).collect();
+        let mut types = infer_column_types(&records, DEFAULT_INFERENCE_SAMPLE);
+        types.resize(expected_columns, ColumnType::Text);
+        logical_rows.insert(0, ReconstructedRow::header(typed_header(&types), Vec::new()));
+    }
+
+    apply_row_transforms(&mut logical_rows, config)?;
+
+    // Validate values against the types declared by header suffixes
+    if config.header_type_hints {
+        let violations = check_type_hints(&logical_rows, &config.type_hint_suffixes);
+        match config.type_hint_policy {
+            TypeHintPolicy::Warn => stats.warnings.extend(violations),
+            TypeHintPolicy::Reject => {
+                let rejected: HashSet<usize> = violations
+                    .iter()
+                    .filter_map(|w| match w {
+                        Warning::TypeHintViolation { out_row, .. } => Some(out_row - 1),
+                        _ => None,
+                    })
+                    .collect();
+                stats.removed_rows += rejected.len();
+                let mut kept = Vec::with_capacity(logical_rows.len() - rejected.len());
+                for (index, row) in logical_rows.into_iter().enumerate() {
+                    if rejected.contains(&index) {
+                        stats.discard_lines(&row.source_lines);
+                    } else {
+                        kept.push(row);
+                    }
                 }
-                last_col.push_str(first_part);
+                logical_rows = kept;
             }
         }
+    }
 
-        // Append remaining fields
-        for i in 1..rec_len {
-            buffer.push(record.get(i).unwrap_or("").to_string());
-        }
+    // Validate sequential record numbers
+    if let Some(column) = config.sequence_column {
+        stats.warnings.extend(check_sequence(&logical_rows, column, config.sequence_step));
+    }
 
-        // Case 3: Check if row is now complete
-        if buffer.len() == expected_columns {
-            logical_rows.push(StringRecord::from(buffer.clone()));
-            stats.fixed_rows += 1;
-            buffer.clear();
-        } else if buffer.len() > expected_columns {
-            // Row has too many columns - discard and log
-            stats.removed_rows += 1;
-            buffer.clear();
-        }
+    // Roll up records sharing a key
+    if let Some(key) = config.group_by {
+        logical_rows = group_rows(logical_rows, key, &config.concat_columns, &config.concat_separator);
+    }
+
+    // Order records by a key column
+    if let Some((key, order)) = config.sort_by {
+        sort_rows(&mut logical_rows, key, order, config.sort_comparison);
     }
 
-    // Handle any remaining incomplete row
-    if !buffer.is_empty() {
-        stats.removed_rows += 1;
+    // Let the caller keep, drop or rewrite each record
+    if let Some(validator) = &config.validate_fn {
+        logical_rows = validator.apply(logical_rows, stats);
     }
 
+    // Count the distinct physical rows behind the output, for reconciliation
+    let used: HashSet<u64> = logical_rows
+        .iter()
+        .flat_map(|row| row.source_lines.iter().copied())
+        .filter(|line| Some(*line) != detected_header_line)
+        .collect();
+    stats.rows_in_output += used.len();
+
     Ok(logical_rows)
 }
 
 /// Write cleaned CSV records to output file
 ///
 /// This function handles the final output generation. It applies whitespace
-/// normalization to every field to ensure clean data.
+/// normalization to every field to ensure clean data; `normalize` chooses
+/// whether embedded line breaks survive it. `delimiter` need not be the one
+/// the records were read with; fields containing it are quoted. A `.gz`
+/// path is gzip-compressed. [`convert_delimiter`] does both steps in one call.
 pub fn write_output_csv(
     output_path: &str,
     rows: &[StringRecord],
     delimiter: Delimiter,
-) -> Result<(), Box<dyn Error>> {
+    normalize: NormalizeMode,
+) -> Result<(), FixerrError> {
+    let file = File::create(output_path)?;
+    if output_path.ends_with(".gz") {
+        let mut encoder = GzEncoder::new(file, Compression::default());
+        write_output_csv_to_writer(&mut encoder, rows, delimiter, normalize)?;
+        encoder.finish()?;
+        return Ok(());
+    }
+    write_output_csv_to_writer(file, rows, delimiter, normalize)
+}
+
+/// Write cleaned CSV records to any sink: a buffer, stdout, a socket, ...
+///
+/// Same output as [`write_output_csv`]. The sink is flushed before returning
+/// and its errors are passed on.
+pub fn write_output_csv_to_writer<W: Write>(
+    writer: W,
+    rows: &[StringRecord],
+    delimiter: Delimiter,
+    normalize: NormalizeMode,
+) -> Result<(), FixerrError> {
     let mut writer = WriterBuilder::new()
         .delimiter(delimiter.as_byte())
-        .from_path(output_path)?;
+        .from_writer(writer);
 
     for record in rows {
         // Apply cleaning logic to every field before writing
-        let cleaned = record.iter().map(clean_and_normalize_field);
+        let cleaned = record.iter().map(|field| normalize.apply(field));
         writer.write_record(cleaned)?;
     }
 
@@ -203,66 +919,643 @@ pub fn write_output_csv(
     Ok(())
 }
 
+/// Write discarded records to a file for review
+///
+/// Fields are written exactly as read, without normalization, and records
+/// keep their own widths.
+pub fn write_discarded_csv(
+    output_path: &str,
+    discarded: &[StringRecord],
+    delimiter: Delimiter,
+) -> Result<(), FixerrError> {
+    let mut writer = WriterBuilder::new()
+        .delimiter(delimiter.as_byte())
+        .flexible(true)
+        .from_writer(File::create(output_path)?);
+    for record in discarded {
+        writer.write_record(record)?;
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+/// Repair a file and write it out with a different delimiter
+///
+/// Reconstructs `input_path` as [`reconstruct_records`] does with
+/// `input_delimiter`, then writes the collapsed records to `output_path`
+/// separated by `output_delimiter`, e.g. to turn a broken semicolon export
+/// into a clean comma-separated file.
+pub fn convert_delimiter(
+    input_path: &str,
+    output_path: &str,
+    header_mode: HeaderMode,
+    input_delimiter: Delimiter,
+    output_delimiter: Delimiter,
+    stats: &mut Stats,
+) -> Result<(), FixerrError> {
+    let records = reconstruct_records(input_path, header_mode, input_delimiter, stats)?;
+    write_output_csv(output_path, &records, output_delimiter, NormalizeMode::Collapse)
+}
+
 // ============================================
 // Private Helper Functions
 // ============================================
 
-fn detect_column_count(
-    reader: &mut Reader<File>,
+pub(crate) fn detect_column_count<R: Read>(
+    reader: &mut Reader<R>,
     header_mode: HeaderMode,
-) -> Result<(usize, Option<StringRecord>), Box<dyn Error>> {
+) -> Result<(usize, Option<StringRecord>), FixerrError> {
     match header_mode {
         HeaderMode::HasHeaders => {
             let headers = reader.headers()?.clone();
             let col_count = headers.len();
             Ok((col_count, Some(headers)))
         }
-        HeaderMode::NoHeaders => {
-            print!("Enter expected number of columns: ");
-            io::stdout().flush()?;
+        // Asking the operator is the frontend's job; the engine never reads stdin
+        HeaderMode::NoHeaders => Err(FixerrError::InvalidColumnCount(
+            "expected_columns must be set for input without headers".to_string(),
+        )),
+    }
+}
+
+/// Field rewrites that look at one row at a time
+///
+/// Fills blank header names and applies `internal_newline`, `numeric_columns`
+/// and `column_normalizers`, in that order.
+pub(crate) fn apply_row_transforms(rows: &mut [ReconstructedRow], config: &ReconstructConfig) -> Result<(), FixerrError> {
+    // Name blank header cells so downstream tools don't see empty columns
+    if config.fill_empty_header_names {
+        for row in rows.iter_mut().filter(|row| row.header) {
+            row.record = row
+                .record
+                .iter()
+                .enumerate()
+                .map(|(index, name)| if name.trim().is_empty() { format!("col_{index}") } else { name.to_string() })
+                .collect();
+        }
+    }
+
+    // Give preserved multi-line values uniform internal line breaks
+    if let Some(terminator) = config.internal_newline {
+        for row in rows.iter_mut() {
+            if row.record.iter().any(|field| field.contains(['\r', '\n'])) {
+                row.record = row.record.iter().map(|field| terminator.normalize(field)).collect();
+            }
+        }
+    }
 
-            let mut input = String::new();
-            io::stdin().read_line(&mut input)?;
-            let col_count = input.trim().parse::<usize>()?;
+    // Canonicalize numbers written with either decimal convention
+    if !config.numeric_columns.is_empty() {
+        for row in rows.iter_mut().filter(|row| !row.header) {
+            let mut fields: Vec<String> = row.record.iter().map(str::to_string).collect();
+            for &column in &config.numeric_columns {
+                if let Some(canonical) = fields.get(column).and_then(|value| canonicalize_number(value)) {
+                    fields[column] = canonical;
+                }
+            }
+            row.record = StringRecord::from(fields);
+        }
+    }
 
-            Ok((col_count, None))
+    // Apply named normalizers to their columns
+    if !config.column_normalizers.is_empty() {
+        let mut normalizers = Vec::with_capacity(config.column_normalizers.len());
+        for entry in &config.column_normalizers {
+            match config.normalizers.get(&entry.normalizer) {
+                Some(f) => normalizers.push((entry.column, f)),
+                None => return Err(FixerrError::InvalidConfig(format!("unknown normalizer '{}'", entry.normalizer))),
+            }
+        }
+        for row in rows.iter_mut().filter(|row| !row.header) {
+            let mut fields: Vec<String> = row.record.iter().map(str::to_string).collect();
+            for (column, normalize) in &normalizers {
+                if let Some(field) = fields.get_mut(*column) {
+                    *field = normalize(field);
+                }
+            }
+            row.record = StringRecord::from(fields);
         }
     }
+    Ok(())
 }
 
-// DESIGN DECISION: Whitespace Normalization
-// During the reconstruction process, joining split lines often results in "double spaces"
-// (one original trailing space + one space replacing the newline).
-//
-// Instead of a simple .replace('\n', " "), we use full tokenization via split_whitespace().
-// This automatically:
-// 1. Trims leading/trailing whitespace (common artifact of manual data entry).
-// 2. Collapses multiple internal spaces into a single space.
-// 3. Flattens newlines and tabs.
-fn clean_and_normalize_field(input: &str) -> String {
-    input.split_whitespace().collect::<Vec<&str>>().join(" ")
+/// Join the cells of a multi-row header column by column
+///
+/// Empty cells are skipped, so a group label above only some columns does not
+/// leave stray separators in the others.
+pub(crate) fn combine_header_rows(rows: &[StringRecord], separator: &str) -> StringRecord {
+    let width = rows.iter().map(StringRecord::len).max().unwrap_or(0);
+    (0..width)
+        .map(|column| {
+            rows.iter()
+                .filter_map(|row| row.get(column).map(str::trim).filter(|cell| !cell.is_empty()))
+                .collect::<Vec<_>>()
+                .join(separator)
+        })
+        .collect()
 }
 
-// ============================================
-// Unit Tests
-// ============================================
+/// Width at `quantile` of all data row widths, or `None` for an empty input
+///
+/// Uses the nearest-rank method, so the result is always an observed width.
+fn quantile_width(
+    data: &[u8],
+    config: &ReconstructConfig,
+    quote: u8,
+    quantile: f64,
+) -> Result<Option<usize>, FixerrError> {
+    if !(quantile > 0.0 && quantile <= 1.0) {
+        return Err(FixerrError::InvalidColumnCount(format!(
+            "column count quantile must be in (0, 1], got {quantile}"
+        )));
+    }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use std::fs;
-    use std::io::Write;
+    let mut reader = build_quoted_reader(data, config.header_mode, config.delimiter, quote, config.comment);
+    let mut widths = Vec::new();
+    for record in reader.records() {
+        widths.push(record?.len());
+    }
+    if widths.is_empty() {
+        return Ok(None);
+    }
 
-    #[test]
-    fn test_case_1_trailing_newline_split() {
-        // Case 1: Newline character is added at the end of field value.
-        // Data: 941315,Tbilisi Waters,Georgian Product \n ,1722.63
-        let filename = "test_case_1.csv";
-        let content = "ID,Organization,Details,Amount\n9413154,Tbilisi Waters,Georgian Product\n,1722.63";
-        
-        {
-            let mut file = File::create(filename).unwrap();
-            file.write_all(content.as_bytes()).unwrap();
+    widths.sort_unstable();
+    let rank = (quantile * widths.len() as f64).ceil() as usize;
+    Ok(Some(widths[rank.max(1) - 1]))
+}
+
+/// Build a configured CSV reader with an explicit quote character, skipping
+/// lines that start with `comment`
+pub(crate) fn build_quoted_reader<R: Read>(
+    reader: R,
+    header_mode: HeaderMode,
+    delimiter: Delimiter,
+    quote: u8,
+    comment: Option<u8>,
+) -> Reader<R> {
+    ReaderBuilder::new()
+        .has_headers(header_mode.as_bool())
+        .delimiter(delimiter.as_byte())
+        .quote(quote)
+        .comment(comment)
+        .flexible(true) // Allow varying column counts to handle broken rows
+        .from_reader(reader)
+}
+
+/// Where the bytes read so far leave the CSV parser
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum QuoteState {
+    /// At the start of a field; `true` if also at the start of a record
+    FieldStart(bool),
+    /// Inside an unquoted field, where quotes are literal
+    Unquoted,
+    /// Inside a quoted field
+    Quoted,
+    /// Just after a quote inside a quoted field: a closing or escaped quote
+    QuoteInQuoted,
+    /// Inside a comment line
+    Comment,
+}
+
+/// Byte source wrapper that follows the quoting of the bytes passing through
+///
+/// Mirrors the csv reader's rules: a quote opens a quoted field only at the
+/// start of a field, doubled quotes inside one are escaped literals, and
+/// quotes elsewhere are plain text. Also counts line endings and optionally
+/// keeps the raw bytes read, so discarded rows can be written out exactly as
+/// they appeared.
+pub(crate) struct QuoteTracker<R> {
+    inner: R,
+    quote: u8,
+    delimiter: u8,
+    comment: Option<u8>,
+    state: QuoteState,
+    /// Line breaks seen, by style
+    crlf: usize,
+    lf: usize,
+    /// The last byte read was `\r`, for a CRLF split across reads
+    after_cr: bool,
+    /// Raw bytes from stream offset `raw_start` onwards, when capturing
+    raw: Option<Vec<u8>>,
+    raw_start: u64,
+}
+
+impl<R: Read> QuoteTracker<R> {
+    pub(crate) fn new(inner: R, quote: u8, delimiter: u8, comment: Option<u8>) -> Self {
+        Self {
+            inner,
+            quote,
+            delimiter,
+            comment,
+            state: QuoteState::FieldStart(true),
+            crlf: 0,
+            lf: 0,
+            after_cr: false,
+            raw: None,
+            raw_start: 0,
+        }
+    }
+
+    /// Parser state after `b`
+    fn advance(&self, b: u8) -> QuoteState {
+        let line_break = b == b'\n' || b == b'\r';
+        match self.state {
+            QuoteState::FieldStart(true) if Some(b) == self.comment => QuoteState::Comment,
+            QuoteState::Comment if b == b'\n' => QuoteState::FieldStart(true),
+            QuoteState::Comment => QuoteState::Comment,
+            QuoteState::FieldStart(_) if b == self.quote => QuoteState::Quoted,
+            QuoteState::Quoted if b == self.quote => QuoteState::QuoteInQuoted,
+            QuoteState::Quoted => QuoteState::Quoted,
+            QuoteState::QuoteInQuoted if b == self.quote => QuoteState::Quoted,
+            _ if line_break => QuoteState::FieldStart(true),
+            _ if b == self.delimiter => QuoteState::FieldStart(false),
+            _ => QuoteState::Unquoted,
+        }
+    }
+
+    /// Start keeping raw bytes; must be called before the first read
+    pub(crate) fn capture_raw(&mut self) {
+        self.raw = Some(Vec::new());
+    }
+
+    /// Raw bytes of the row spanning stream offsets `start..end`
+    ///
+    /// Blank lines the reader skipped before the row are left out. Empty
+    /// unless capturing.
+    pub(crate) fn raw_line(&self, start: u64, end: u64) -> Vec<u8> {
+        let raw = match &self.raw {
+            Some(raw) => raw,
+            None => return Vec::new(),
+        };
+        let from = (start.saturating_sub(self.raw_start) as usize).min(raw.len());
+        let mut to = (end.saturating_sub(self.raw_start) as usize).clamp(from, raw.len());
+        // The reader stops after the `\r` of a CRLF terminator
+        if to > from && raw[to - 1] == b'\r' && raw.get(to) == Some(&b'\n') {
+            to += 1;
+        }
+        let span = &raw[from..to];
+        let blank = span.iter().take_while(|&&b| b == b'\r' || b == b'\n').count();
+        span[blank..].to_vec()
+    }
+
+    /// Drop captured bytes before stream offset `offset`
+    pub(crate) fn release_raw(&mut self, offset: u64) {
+        if let Some(raw) = &mut self.raw {
+            let n = (offset.saturating_sub(self.raw_start) as usize).min(raw.len());
+            raw.drain(..n);
+            self.raw_start += n as u64;
+        }
+    }
+
+    /// True if the input so far ends inside a quoted field
+    pub(crate) fn unbalanced(&self) -> bool {
+        self.state == QuoteState::Quoted
+    }
+
+    /// Add the line breaks seen so far to `stats`
+    pub(crate) fn record_line_endings(&self, stats: &mut Stats) {
+        stats.crlf_line_endings += self.crlf;
+        stats.lf_line_endings += self.lf;
+    }
+}
+
+impl<R: Read> Read for QuoteTracker<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let n = self.inner.read(buf)?;
+        for &b in &buf[..n] {
+            self.state = self.advance(b);
+            if b == b'\n' {
+                if self.after_cr {
+                    self.crlf += 1;
+                } else {
+                    self.lf += 1;
+                }
+            }
+            self.after_cr = b == b'\r';
+        }
+        if let Some(raw) = &mut self.raw {
+            raw.extend_from_slice(&buf[..n]);
+        }
+        Ok(n)
+    }
+}
+
+/// Which fields of the raw row `raw` start with a quote
+///
+/// Returns an empty vector unless the row splits into exactly `width` fields.
+pub(crate) fn quoted_fields(raw: &[u8], width: usize, delimiter: u8, quote: u8) -> Vec<bool> {
+    let raw = raw.strip_suffix(b"\n").unwrap_or(raw);
+    let raw = raw.strip_suffix(b"\r").unwrap_or(raw);
+    let mut quoted = Vec::with_capacity(width);
+    let mut pos = 0;
+    loop {
+        let is_quoted = raw.get(pos) == Some(&quote);
+        quoted.push(is_quoted);
+        if is_quoted {
+            // Skip to the closing quote; doubled quotes are escaped literals
+            pos += 1;
+            while pos < raw.len() {
+                if raw[pos] == quote && raw.get(pos + 1) != Some(&quote) {
+                    break;
+                }
+                pos += if raw[pos] == quote { 2 } else { 1 };
+            }
+        }
+        match raw[pos.min(raw.len())..].iter().position(|&b| b == delimiter) {
+            Some(offset) => pos += offset + 1,
+            None => break,
+        }
+    }
+    if quoted.len() == width {
+        quoted
+    } else {
+        Vec::new()
+    }
+}
+
+/// Width of the first record of a reference file
+///
+/// The file is opened, decoded and parsed like the input itself, with the
+/// quote already resolved for the input.
+pub(crate) fn reference_width(path: &Path, config: &ReconstructConfig, quote: u8) -> Result<usize, FixerrError> {
+    let source = DecodingReader::new(open_input(&path.to_string_lossy(), config)?, config.input_encoding);
+    let mut reader = build_quoted_reader(source, HeaderMode::NoHeaders, config.delimiter, quote, config.comment);
+    match reader.records().next() {
+        Some(record) => Ok(record?.len()),
+        None => Err(FixerrError::EmptyInput),
+    }
+}
+
+/// Line on which a physical row starts
+///
+/// `parse_line` is where the reader began parsing (before skipped blank lines)
+/// and `end_line` the reader position after the row. Quoted fields keep their
+/// embedded newlines, so counting them gives the row's own line span.
+pub(crate) fn physical_start_line(record: &StringRecord, parse_line: u64, end_line: u64) -> u64 {
+    let embedded = record.iter().map(|f| f.matches('\n').count()).sum::<usize>() as u64;
+    if end_line > parse_line + embedded {
+        // Row was terminated by a newline
+        end_line - 1 - embedded
+    } else {
+        // Last row of the input without a trailing newline
+        parse_line
+    }
+}
+
+/// Count a record of only empty or whitespace fields as blank lines
+///
+/// Such rows carry no data, so they are skipped like the empty lines the
+/// reader drops by itself rather than buffered or discarded. `end_line` is
+/// the reader's line after the record. Returns whether `record` was blank.
+pub(crate) fn skip_blank_record(record: &StringRecord, end_line: u64, stats: &mut Stats) -> bool {
+    if !record.iter().all(|field| field.trim().is_empty()) {
+        return false;
+    }
+    let parse_line = record.position().map_or(0, |p| p.line());
+    stats.blank_lines += end_line.saturating_sub(parse_line).max(1) as usize;
+    true
+}
+
+/// Stitch `fragments` again without the empty fields at their line breaks
+///
+/// A line ending in a delimiter, or continuing with one, adds an empty field
+/// that pushes a stitched row past the expected width. Such fields (the last
+/// of a fragment followed by another, the first of a fragment following
+/// another) are dropped from the end backwards until the row is exactly
+/// `expected_columns` wide. Returns `None` if it cannot be made to fit.
+pub(crate) fn restitch_without_boundary_empties(
+    fragments: &[StringRecord],
+    expected_columns: usize,
+    separator: &str,
+) -> Option<Vec<String>> {
+    let mut pieces: Vec<Vec<&str>> = fragments.iter().map(|fragment| fragment.iter().collect()).collect();
+    let last = pieces.len().checked_sub(1)?;
+
+    for index in (0..=last).rev() {
+        // Trailing empty field before a break, then leading one after a break
+        for trailing in [true, false] {
+            let piece = &mut pieces[index];
+            let applies = if trailing { index < last } else { index > 0 };
+            let position = if trailing { piece.len().saturating_sub(1) } else { 0 };
+            if !applies || piece.len() < 2 || !piece[position].is_empty() {
+                continue;
+            }
+            piece.remove(position);
+
+            let fields = stitch_pieces(&pieces, separator);
+            match fields.len().cmp(&expected_columns) {
+                std::cmp::Ordering::Equal => return Some(fields),
+                std::cmp::Ordering::Less => return None,
+                std::cmp::Ordering::Greater => {}
+            }
+        }
+    }
+    None
+}
+
+/// Join fragments the way the main loop does: the first field of each
+/// continues the last field so far
+fn stitch_pieces(pieces: &[Vec<&str>], separator: &str) -> Vec<String> {
+    let mut fields: Vec<String> = Vec::new();
+    for piece in pieces {
+        let mut parts = piece.iter();
+        if let Some(last) = fields.last_mut() {
+            if let Some(first) = parts.next() {
+                if !last.is_empty() {
+                    last.push_str(separator);
+                }
+                last.push_str(first);
+            }
+        }
+        fields.extend(parts.map(|part| part.to_string()));
+    }
+    fields
+}
+
+/// Rejoin the pieces of an enumerated value split by a stray delimiter
+///
+/// For each column in `enum_columns` (lowest first), the surplus fields
+/// following it are merged into it, rejoined with the delimiter, a space or
+/// nothing. The first result matching a valid value (ignoring case and
+/// whitespace) is replaced by that value and the record returned.
+pub(crate) fn merge_enum_split(
+    record: &StringRecord,
+    expected_columns: usize,
+    enum_columns: &HashMap<usize, Vec<String>>,
+    delimiter: Delimiter,
+) -> Option<StringRecord> {
+    let surplus = record.len() - expected_columns;
+    let delimiter = (delimiter.as_byte() as char).to_string();
+    let mut columns: Vec<usize> = enum_columns.keys().copied().collect();
+    columns.sort_unstable();
+
+    for column in columns {
+        if column + surplus >= record.len() {
+            continue;
+        }
+        let pieces: Vec<&str> = record.iter().skip(column).take(surplus + 1).collect();
+        let valid = &enum_columns[&column];
+        let found = [delimiter.as_str(), " ", ""].iter().find_map(|joiner| {
+            let candidate = clean_and_normalize_field(&pieces.join(joiner));
+            valid
+                .iter()
+                .find(|value| clean_and_normalize_field(value).eq_ignore_ascii_case(&candidate))
+        });
+        if let Some(value) = found {
+            let fields: Vec<&str> = record.iter().take(column).collect();
+            let rest = record.iter().skip(column + surplus + 1);
+            return Some(fields.into_iter().chain(std::iter::once(value.as_str())).chain(rest).collect());
+        }
+    }
+    None
+}
+
+/// Rejoin numbers whose decimal comma was read as a delimiter
+///
+/// A candidate is an integer field (`12`, `-3`, `1.234`) followed by a field
+/// of exactly two digits, the usual number of decimals. The record is only
+/// repaired when the candidates don't overlap and their number equals the
+/// surplus exactly; otherwise it is unclear which pairs are genuinely
+/// separate numeric columns, so nothing is merged.
+pub(crate) fn merge_decimal_commas(record: &StringRecord, expected_columns: usize, to_point: bool) -> Option<StringRecord> {
+    let surplus = record.len() - expected_columns;
+    let fields: Vec<&str> = record.iter().collect();
+    let is_integer = |field: &str| {
+        let digits = field.trim().strip_prefix('-').unwrap_or(field.trim());
+        !digits.is_empty()
+            && digits.split('.').all(|group| !group.is_empty() && group.bytes().all(|b| b.is_ascii_digit()))
+    };
+    let is_fraction = |field: &str| field.len() == 2 && field.bytes().all(|b| b.is_ascii_digit());
+
+    let pairs: Vec<usize> = (0..fields.len().saturating_sub(1))
+        .filter(|&i| is_integer(fields[i]) && is_fraction(fields[i + 1]))
+        .collect();
+    if pairs.len() != surplus || pairs.windows(2).any(|w| w[1] == w[0] + 1) {
+        return None;
+    }
+
+    let separator = if to_point { "." } else { "," };
+    let mut merged = Vec::with_capacity(expected_columns);
+    let mut i = 0;
+    while i < fields.len() {
+        if pairs.contains(&i) {
+            let integer = if to_point { fields[i].replace('.', "") } else { fields[i].to_string() };
+            merged.push(format!("{integer}{separator}{}", fields[i + 1]));
+            i += 2;
+        } else {
+            merged.push(fields[i].to_string());
+            i += 1;
+        }
+    }
+    Some(StringRecord::from(merged))
+}
+
+/// Narrow an over-wide record by joining the surplus fields into `target`
+///
+/// The fields from `target` on are rejoined with the delimiter that split
+/// them, so the free text is restored as written.
+pub(crate) fn merge_surplus_into(record: &StringRecord, expected_columns: usize, target: usize, delimiter: Delimiter) -> StringRecord {
+    let surplus = record.len() - expected_columns;
+    let fields: Vec<&str> = record.iter().collect();
+    let joined = fields[target..=target + surplus].join(&(delimiter.as_byte() as char).to_string());
+
+    fields[..target]
+        .iter()
+        .copied()
+        .chain(std::iter::once(joined.as_str()))
+        .chain(fields[target + surplus + 1..].iter().copied())
+        .collect()
+}
+
+/// First field with more than `max` newlines, with its newline count
+pub(crate) fn runaway_field(fields: &[String], max: usize) -> Option<(usize, usize)> {
+    fields
+        .iter()
+        .map(|field| field.matches('\n').count())
+        .enumerate()
+        .find(|&(_, newlines)| newlines > max)
+}
+
+/// True if every cell of `record` is one of `known` (case-insensitive)
+pub(crate) fn matches_known_header_names(record: &StringRecord, known: &[String]) -> bool {
+    !known.is_empty()
+        && !record.is_empty()
+        && record
+            .iter()
+            .all(|cell| known.iter().any(|name| name.trim().eq_ignore_ascii_case(cell.trim())))
+}
+
+/// Fail unless `header` holds exactly the `expected` names (trimmed)
+///
+/// With `allow_reorder` only the set of names has to match, not their order.
+pub(crate) fn check_expected_headers(header: &StringRecord, expected: &[String], allow_reorder: bool) -> Result<(), FixerrError> {
+    let found: Vec<String> = header.iter().map(|name| name.trim().to_string()).collect();
+    let mut wanted: Vec<String> = expected.iter().map(|name| name.trim().to_string()).collect();
+
+    let matches = if allow_reorder {
+        let mut sorted = found.clone();
+        sorted.sort();
+        wanted.sort();
+        sorted == wanted
+    } else {
+        found == wanted
+    };
+
+    if matches {
+        Ok(())
+    } else {
+        Err(FixerrError::HeaderMismatch {
+            expected: expected.to_vec(),
+            found,
+        })
+    }
+}
+
+/// Heuristic for a section header: every cell is non-empty, non-numeric text
+pub(crate) fn looks_like_section_header(record: &StringRecord) -> bool {
+    !record.is_empty()
+        && record.iter().all(|cell| {
+            let cell = cell.trim();
+            !cell.is_empty() && ColumnType::of_value(cell) == ColumnType::Text
+        })
+}
+
+// DESIGN DECISION: Whitespace Normalization
+// During the reconstruction process, joining split lines often results in "double spaces"
+// (one original trailing space + one space replacing the newline).
+//
+// Instead of a simple .replace('\n', " "), we use full tokenization via split_whitespace().
+// This automatically:
+// 1. Trims leading/trailing whitespace (common artifact of manual data entry).
+// 2. Collapses multiple internal spaces into a single space.
+// 3. Flattens newlines and tabs.
+pub(crate) fn clean_and_normalize_field(input: &str) -> String {
+    input.split_whitespace().collect::<Vec<&str>>().join(" ")
+}
+
+// ============================================
+// Unit Tests
+// ============================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::encoding::InputEncoding;
+    use crate::validate::{RecordAction, RecordValidator};
+    use regex::Regex;
+    use std::fs;
+    use std::io::Write;
+
+    #[test]
+    fn test_case_1_trailing_newline_split() {
+        // Case 1: Newline character is added at the end of field value.
+        // Data: 941315,Tbilisi Waters,Georgian Product \n ,1722.63
+        let filename = "test_case_1.csv";
+        let content = "ID,Organization,Details,Amount\n9413154,Tbilisi Waters,Georgian Product\n,1722.63";
+        
+        {
+            let mut file = File::create(filename).unwrap();
+            file.write_all(content.as_bytes()).unwrap();
         }
 
         let mut stats = Stats::default();
@@ -382,10 +1675,1048 @@ mod tests {
         assert_eq!(clean_and_normalize_field(details), "Mestia, Georgia");
     }
 
+    #[test]
+    fn test_quoted_delimiter_and_newline_in_same_field() {
+        // Quoted field that contains the delimiter AND spans lines, both on a
+        // clean row and on a row that also needs unquoted-newline stitching.
+        let filename = "test_quoted_delimiter_newline.csv";
+        let content = "ID,Organization,Address,Amount\n\
+                       1,Acme,\"123 Main St, Apt 4,\nTbilisi, Georgia\",100\n\
+                       2,Tbilisi\nWaters,\"5 Rustaveli Ave, Fl 2,\nTbilisi, Georgia\",200";
+
+        {
+            let mut file = File::create(filename).unwrap();
+            file.write_all(content.as_bytes()).unwrap();
+        }
+
+        let mut stats = Stats::default();
+        let result = reconstruct_records(filename, HeaderMode::HasHeaders, Delimiter::Comma, &mut stats).unwrap();
+        let _ = fs::remove_file(filename);
+
+        assert_eq!(result.len(), 3);
+        assert_eq!(stats.removed_rows, 0);
+        assert_eq!(stats.fixed_rows, 1);
+
+        // The quoted field stays a single value with its commas intact
+        assert_eq!(result[1].len(), 4);
+        assert_eq!(clean_and_normalize_field(&result[1][2]), "123 Main St, Apt 4, Tbilisi, Georgia");
+        assert_eq!(&result[1][3], "100");
+
+        assert_eq!(result[2].len(), 4);
+        assert_eq!(clean_and_normalize_field(&result[2][1]), "Tbilisi Waters");
+        assert_eq!(clean_and_normalize_field(&result[2][2]), "5 Rustaveli Ave, Fl 2, Tbilisi, Georgia");
+        assert_eq!(&result[2][3], "200");
+    }
+
+    #[test]
+    fn test_quoted_header_with_delimiter_column_count() {
+        // The expected width is the foundation of the algorithm: a quoted header
+        // cell containing the delimiter must count as a single column.
+        let content = "ID,\"Name, Full\",Amount\n1,\"Beridze, Nino\",10.00\n2,Gela\nKapanadze,20.00";
+
+        let mut reader = build_csv_reader(content.as_bytes(), HeaderMode::HasHeaders, Delimiter::Comma);
+        let (columns, headers) = detect_column_count(&mut reader, HeaderMode::HasHeaders).unwrap();
+        assert_eq!(columns, 3);
+        assert_eq!(&headers.unwrap()[1], "Name, Full");
+
+        let config = ReconstructConfig::new(HeaderMode::HasHeaders, Delimiter::Comma);
+        let mut stats = Stats::default();
+        let result = reconstruct_rows_from_reader(content.as_bytes(), &config, &mut stats).unwrap();
+
+        assert_eq!(result.len(), 3);
+        assert_eq!(&result[1].record[1], "Beridze, Nino");
+        assert_eq!(clean_and_normalize_field(&result[2].record[1]), "Gela Kapanadze");
+        assert_eq!(stats.removed_rows, 0);
+    }
+
+    #[test]
+    fn test_quoted_empty_header_cell_column_count() {
+        let content = "ID,\"\",Amount\n1,Tbilisi\nWaters,10";
+
+        let mut reader = build_csv_reader(content.as_bytes(), HeaderMode::HasHeaders, Delimiter::Comma);
+        let (columns, headers) = detect_column_count(&mut reader, HeaderMode::HasHeaders).unwrap();
+        assert_eq!(columns, 3);
+        assert_eq!(&headers.unwrap()[1], "");
+
+        let rows = reconstruct_rows_from_reader(content.as_bytes(), &ReconstructConfig::default(), &mut Stats::default()).unwrap();
+        assert_eq!(rows.len(), 2);
+        assert_eq!(&rows[0].record[1], "");
+    }
+
+    #[test]
+    fn test_fill_empty_header_names() {
+        let content = "ID,\"\",Amount, \n1,Tbilisi,10,x";
+        let config = ReconstructConfig {
+            fill_empty_header_names: true,
+            ..ReconstructConfig::default()
+        };
+        let rows = reconstruct_rows_from_reader(content.as_bytes(), &config, &mut Stats::default()).unwrap();
+
+        assert_eq!(rows[0].record.iter().collect::<Vec<_>>(), vec!["ID", "col_1", "Amount", "col_3"]);
+        assert_eq!(rows[1].record.iter().collect::<Vec<_>>(), vec!["1", "Tbilisi", "10", "x"]);
+    }
+
+    #[test]
+    fn test_headerless_input_needs_a_column_count_instead_of_prompting() {
+        let filename = "test_with_columns.csv";
+        fs::write(filename, "1,Tbilisi\nWaters,10\n2,Gori,20").unwrap();
+
+        let prompted = reconstruct_records(filename, HeaderMode::NoHeaders, Delimiter::Comma, &mut Stats::default());
+        let mut stats = Stats::default();
+        let records = reconstruct_records_with_columns(filename, Delimiter::Comma, 3, &mut stats).unwrap();
+        let _ = fs::remove_file(filename);
+
+        assert!(prompted.is_err());
+        assert_eq!(records.len(), 2);
+        assert_eq!(stats.fixed_rows, 1);
+    }
+
+    #[test]
+    fn test_reconstruct_records_from_in_memory_reader() {
+        let input = io::Cursor::new(b"ID,Name,Amount\n1,Tbilisi\nWaters,10\n2,Gori,20".to_vec());
+        let mut stats = Stats::default();
+        let records = reconstruct_records_from_reader(input, HeaderMode::HasHeaders, Delimiter::Comma, &mut stats).unwrap();
+
+        assert_eq!(records.len(), 3);
+        assert_eq!(clean_and_normalize_field(&records[1][1]), "Tbilisi Waters");
+        assert_eq!(stats.fixed_rows, 1);
+    }
+
+    #[test]
+    fn test_enum_column_split_by_stray_delimiter_is_remerged() {
+        let input = "ID,Status,Amount\n1,ACTIVE,10\n2,PENDING,REVIEW,20\n3,UNKNOWN,X,30";
+        let mut enum_columns = HashMap::new();
+        enum_columns.insert(
+            1,
+            vec!["ACTIVE".to_string(), "INACTIVE".to_string(), "PENDING REVIEW".to_string()],
+        );
+        let config = ReconstructConfig {
+            enum_columns,
+            ..ReconstructConfig::default()
+        };
+        let mut stats = Stats::default();
+        let rows = reconstruct_rows_from_reader(input.as_bytes(), &config, &mut stats).unwrap();
+
+        assert_eq!(rows.len(), 3);
+        assert_eq!(rows[2].record.iter().collect::<Vec<_>>(), vec!["2", "PENDING REVIEW", "20"]);
+        assert!(rows[2].fixed);
+        assert_eq!(stats.removed_rows, 1);
+    }
+
+    #[test]
+    fn test_write_output_to_in_memory_sink() {
+        let rows = vec![
+            StringRecord::from(vec!["ID", "Name"]),
+            StringRecord::from(vec!["1", "Tbilisi\nWaters"]),
+        ];
+        let mut buffer = Vec::new();
+        write_output_csv_to_writer(&mut buffer, &rows, Delimiter::Semicolon, NormalizeMode::Collapse).unwrap();
+        assert_eq!(buffer, b"ID;Name\n1;Tbilisi Waters\n");
+
+        struct Broken;
+        impl Write for Broken {
+            fn write(&mut self, _: &[u8]) -> io::Result<usize> {
+                Err(io::Error::new(io::ErrorKind::BrokenPipe, "closed"))
+            }
+            fn flush(&mut self) -> io::Result<()> {
+                Ok(())
+            }
+        }
+        assert!(write_output_csv_to_writer(Broken, &rows, Delimiter::Comma, NormalizeMode::default()).is_err());
+    }
+
+    #[test]
+    fn test_smart_merge_restores_pipe_in_free_text_column() {
+        let input = "ID|Notes|Amount|Date\n1|plain note|10|2024-01-01\n2|either a | b, then c|20|2024-01-02";
+        let config = ReconstructConfig {
+            overflow_strategy: OverflowStrategy::SmartMerge,
+            free_text_columns: vec![1],
+            ..ReconstructConfig::new(HeaderMode::HasHeaders, Delimiter::Pipe)
+        };
+        let mut stats = Stats::default();
+        let rows = reconstruct_rows_from_reader(input.as_bytes(), &config, &mut stats).unwrap();
+
+        assert_eq!(rows.len(), 3);
+        assert_eq!(
+            rows[2].record.iter().collect::<Vec<_>>(),
+            vec!["2", "either a | b, then c", "20", "2024-01-02"]
+        );
+        assert_eq!(stats.removed_rows, 0);
+
+        // Without the hint the surplus lands in the last column
+        let config = ReconstructConfig { free_text_columns: Vec::new(), ..config };
+        let rows = reconstruct_rows_from_reader(input.as_bytes(), &config, &mut Stats::default()).unwrap();
+        assert_eq!(&rows[2].record[3], "20|2024-01-02");
+    }
+
     #[test]
     fn test_clean_and_normalize_logic() {
         assert_eq!(clean_and_normalize_field("Word \n"), "Word");
         assert_eq!(clean_and_normalize_field("Hello  World"), "Hello World");
         assert_eq!(clean_and_normalize_field(" Item \t 1 "), "Item 1");
     }
+
+    #[test]
+    fn test_generate_typed_headers_for_headerless_file() {
+        let filename = "test_typed_headers.csv";
+        let content = "9413154,Tbilisi Waters,2024-03-15,1722.63\n9413155,Bodorna\nWaters,2024-03-16,2909.20";
+
+        {
+            let mut file = File::create(filename).unwrap();
+            file.write_all(content.as_bytes()).unwrap();
+        }
+
+        let config = ReconstructConfig {
+            expected_columns: Some(4),
+            generate_typed_headers: true,
+            ..ReconstructConfig::new(HeaderMode::NoHeaders, Delimiter::Comma)
+        };
+        let mut stats = Stats::default();
+        let result = reconstruct_records_with_config(filename, &config, &mut stats).unwrap();
+        let _ = fs::remove_file(filename);
+
+        assert_eq!(result.len(), 3);
+        assert_eq!(
+            result[0].iter().collect::<Vec<_>>(),
+            vec!["int_col_0", "text_col_1", "date_col_2", "decimal_col_3"]
+        );
+    }
+
+    #[test]
+    fn test_split_at_expected_recovers_glued_records() {
+        let filename = "test_split_at_expected.csv";
+        let content = "ID,Organization,Details,Amount\n9413154,Tbilisi Waters,Georgian Product,1722.63,9413155,Bodorna Waters,Mineral water,2909.20";
+
+        {
+            let mut file = File::create(filename).unwrap();
+            file.write_all(content.as_bytes()).unwrap();
+        }
+
+        let mut config = ReconstructConfig::new(HeaderMode::HasHeaders, Delimiter::Comma);
+        let mut stats = Stats::default();
+        let discarded = reconstruct_records_with_config(filename, &config, &mut stats).unwrap();
+        assert_eq!(discarded.len(), 1);
+        assert_eq!(stats.removed_rows, 1);
+
+        config.overflow_strategy = OverflowStrategy::SplitAtExpected;
+        let mut stats = Stats::default();
+        let result = reconstruct_records_with_config(filename, &config, &mut stats).unwrap();
+        let _ = fs::remove_file(filename);
+
+        assert_eq!(result.len(), 3);
+        assert_eq!(&result[1][0], "9413154");
+        assert_eq!(&result[2][0], "9413155");
+        assert_eq!(&result[2][3], "2909.20");
+        assert_eq!(stats.fixed_rows, 2);
+        assert_eq!(stats.removed_rows, 0);
+    }
+
+    #[test]
+    fn test_overflow_strategies_keep_source_order_while_buffering() {
+        let content = "A,B,C\n1,x\n2,y,z,3,w,v\ny,9\n";
+        for strategy in [OverflowStrategy::SplitAtExpected, OverflowStrategy::SmartMerge] {
+            let config = ReconstructConfig { overflow_strategy: strategy, ..ReconstructConfig::default() };
+            let mut stats = Stats::default();
+            let rows = reconstruct_rows_from_reader(content.as_bytes(), &config, &mut stats).unwrap();
+
+            assert_eq!(rows.len(), 2, "{strategy:?}");
+            assert_eq!(rows[1].record, StringRecord::from(vec!["1", "x\ny", "9"]), "{strategy:?}");
+            assert_eq!((stats.fixed_rows, stats.removed_rows), (1, 1), "{strategy:?}");
+        }
+    }
+
+    #[test]
+    fn test_rows_keep_source_order_on_every_path() {
+        fn data_lines(rows: &[ReconstructedRow]) -> Vec<u64> {
+            rows.iter().filter(|row| !row.header).map(|row| row.source_lines[0]).collect()
+        }
+        let content = "A,B,C\n1,x\n2,y,z,3,w,v\ny,9\n4,ok,5\n6,a\nb,7\n8,c,9\n";
+
+        let mut configs = Vec::new();
+        for strategy in [OverflowStrategy::Discard, OverflowStrategy::SplitAtExpected, OverflowStrategy::SmartMerge] {
+            configs.push(ReconstructConfig { overflow_strategy: strategy, ..ReconstructConfig::default() });
+        }
+        configs.push(ReconstructConfig { reverse: true, ..ReconstructConfig::default() });
+        for config in &configs {
+            let mut stats = Stats::default();
+            let rows = reconstruct_rows_from_reader(content.as_bytes(), config, &mut stats).unwrap();
+            let lines = data_lines(&rows);
+            assert!(lines.len() >= 3 && lines.windows(2).all(|pair| pair[0] < pair[1]), "{lines:?} for {config:?}");
+        }
+
+        let streamed: Vec<ReconstructedRow> = crate::Reconstructor::new(content.as_bytes(), &ReconstructConfig::default())
+            .unwrap()
+            .map(Result::unwrap)
+            .collect();
+        let mut stats = Stats::default();
+        let in_memory = reconstruct_rows_from_reader(content.as_bytes(), &ReconstructConfig::default(), &mut stats).unwrap();
+        assert_eq!(data_lines(&streamed), data_lines(&in_memory));
+    }
+
+    #[test]
+    fn test_sequence_column_clean_and_with_gap() {
+        let mut config = ReconstructConfig::new(HeaderMode::HasHeaders, Delimiter::Comma);
+        config.sequence_column = Some(0);
+
+        let clean = "ID,Details,Amount\n1,A,10\n2,B\nsplit,20\n3,C,30";
+        let mut stats = Stats::default();
+        reconstruct_rows_from_reader(clean.as_bytes(), &config, &mut stats).unwrap();
+        assert!(stats.warnings.is_empty());
+
+        // Record 3 was over-long and discarded, record 5 is duplicated
+        let gapped = "ID,Details,Amount\n1,A,10\n2,B,20\n3,C,30,extra\n4,D,40\n4,D,40";
+        let mut stats = Stats::default();
+        reconstruct_rows_from_reader(gapped.as_bytes(), &config, &mut stats).unwrap();
+        assert_eq!(
+            stats.warnings,
+            vec![
+                Warning::OverLengthRow { line: 4, got: 4, expected: 3 },
+                Warning::SequenceGap { out_row: 4, expected: 3, found: 4 },
+                Warning::SequenceDuplicate { out_row: 5, value: 4 },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_known_header_names_flag_headed_file_in_no_headers_mode() {
+        let mut config = ReconstructConfig::new(HeaderMode::NoHeaders, Delimiter::Comma);
+        config.expected_columns = Some(3);
+        config.known_header_names = vec!["id".to_string(), "name".to_string(), "amount".to_string()];
+
+        let headed = "id,name,amount\n1,Tbilisi Waters,10";
+        let mut stats = Stats::default();
+        reconstruct_rows_from_reader(headed.as_bytes(), &config, &mut stats).unwrap();
+        assert_eq!(stats.warnings, vec![Warning::PossibleHeaderRow { line: 1 }]);
+
+        let headerless = "1,Tbilisi Waters,10\n2,Gori,20";
+        let mut stats = Stats::default();
+        reconstruct_rows_from_reader(headerless.as_bytes(), &config, &mut stats).unwrap();
+        assert!(stats.warnings.is_empty());
+    }
+
+    #[test]
+    fn test_expect_headers_rejects_schema_drift() {
+        let input = "ID,Name,Amount\n1,Tbilisi Waters,10";
+        let run = |expected: &[&str], allow_reorder: bool| {
+            let config = ReconstructConfig {
+                expect_headers: Some(expected.iter().map(|name| name.to_string()).collect()),
+                allow_reorder,
+                ..ReconstructConfig::default()
+            };
+            reconstruct_rows_from_reader(input.as_bytes(), &config, &mut Stats::default())
+        };
+        let is_mismatch =
+            |result: Result<Vec<ReconstructedRow>, FixerrError>| match result.unwrap_err() {
+                FixerrError::HeaderMismatch { found, .. } => found == ["ID", "Name", "Amount"],
+                _ => false,
+            };
+
+        assert_eq!(run(&["ID", "Name", "Amount"], false).unwrap().len(), 2);
+        assert!(is_mismatch(run(&["Name", "ID", "Amount"], false)));
+        assert_eq!(run(&["Name", "ID", "Amount"], true).unwrap().len(), 2);
+        assert!(is_mismatch(run(&["ID", "Company", "Amount"], true)));
+    }
+
+    #[test]
+    fn test_runaway_stitch_is_rejected() {
+        // A lost closing field makes every following line look like a fragment
+        let input = "ID,Name,Amount\n1,Tbilisi\na\nb\nc\nd\ne\nf,10\n2,Gori,20";
+        let config = ReconstructConfig {
+            max_newlines_per_field: Some(5),
+            ..ReconstructConfig::default()
+        };
+        let mut stats = Stats::default();
+        let rows = reconstruct_rows_from_reader(input.as_bytes(), &config, &mut stats).unwrap();
+
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[1].record.get(1), Some("Gori"));
+        assert_eq!(stats.runaway_fields, 1);
+        assert_eq!(stats.rows_discarded, 7);
+        assert_eq!(stats.warnings, vec![Warning::RunawayField { line: 2, column: 1, newlines: 6 }]);
+    }
+
+    #[test]
+    fn test_validate_fn_keeps_drops_and_replaces() {
+        let input = "ID,Status,Amount\n1,PAID,10\n2,VOID,0\n3,PAID,-30\n4,PAID\n,40";
+        let config = ReconstructConfig {
+            validate_fn: Some(RecordValidator::new(Box::new(|record: &StringRecord| {
+                assert_ne!(&record[0], "ID", "header must not be validated");
+                match (&record[1], record[2].trim()) {
+                    ("VOID", _) => RecordAction::Drop,
+                    (_, amount) if amount.starts_with('-') => {
+                        RecordAction::Replace(StringRecord::from(vec![&record[0], &record[1], &amount[1..]]))
+                    }
+                    _ => RecordAction::Keep,
+                }
+            }))),
+            ..ReconstructConfig::default()
+        };
+        let mut stats = Stats::default();
+        let rows = reconstruct_rows_from_reader(input.as_bytes(), &config, &mut stats).unwrap();
+
+        let ids: Vec<&str> = rows.iter().map(|row| &row.record[0]).collect();
+        assert_eq!(ids, ["ID", "1", "3", "4"]);
+        assert_eq!(&rows[2].record[2], "30");
+        assert_eq!((stats.records_kept, stats.records_dropped, stats.records_replaced), (2, 1, 1));
+        assert!(stats.reconcile().is_balanced());
+    }
+
+    #[test]
+    fn test_render_table_aligns_values() {
+        let stats = Stats {
+            total_rows: 120,
+            fixed_rows: 10,
+            removed_rows: 6,
+            ..Stats::default()
+        };
+        let table = stats.render_table(104, "out.csv");
+
+        assert_eq!(
+            table,
+            "Total lines read         120\n\
+             Fixed/Merged rows         10\n\
+             Discarded rows             6\n\
+             Total valid records      104\n\
+             Success rate           95.0%\n\
+             Warnings                   0\n\
+             Output               out.csv\n"
+        );
+    }
+
+    #[test]
+    fn test_summary_computes_success_rate() {
+        assert_eq!(Stats::default().success_rate(), 0.0);
+
+        let content = "ID,Name,Amount\n1,Tbilisi\nWaters,10\n2,Gori,20,x\n\n3,Mestia,30\n";
+        let mut stats = Stats::default();
+        reconstruct_rows_from_reader(content.as_bytes(), &ReconstructConfig::default(), &mut stats).unwrap();
+
+        assert_eq!(
+            stats.summary(),
+            StatsSummary {
+                total_rows: 4,
+                fixed_rows: 1,
+                removed_rows: 1,
+                blank_lines: 1,
+                warnings: 1,
+                success_rate: 75.0,
+            }
+        );
+    }
+
+    #[test]
+    fn test_quantile_column_count_when_short_rows_dominate() {
+        // Three 3-column records each split in two, plus two intact ones:
+        // the most common width is 2, the 90th percentile is the true 3
+        let content = "1,Tbilisi\nWaters,10\n2,Gori\nBeverages,20\n3,Kutaisi\nFoods,30\n4,Batumi,40\n5,Rustavi,50\n";
+        let mut config = ReconstructConfig::new(HeaderMode::NoHeaders, Delimiter::Comma);
+        config.column_count_strategy = ColumnCountStrategy::Quantile(0.9);
+
+        assert_eq!(quantile_width(content.as_bytes(), &config, b'"', 0.9).unwrap(), Some(3));
+        assert_eq!(quantile_width(content.as_bytes(), &config, b'"', 0.5).unwrap(), Some(2));
+
+        let mut stats = Stats::default();
+        let rows = reconstruct_rows_from_reader(content.as_bytes(), &config, &mut stats).unwrap();
+        assert_eq!(rows.len(), 5);
+        assert!(rows.iter().all(|row| row.record.len() == 3));
+        assert_eq!(&rows[1].record[1], "Gori\nBeverages");
+        assert_eq!(stats.fixed_rows, 3);
+    }
+
+    #[test]
+    fn test_utf8_bom_is_stripped_once_before_column_detection() {
+        let path = "test_bom_input.csv";
+        fs::write(path, "\u{FEFF}ID,Name,Amount\n1,Tbilisi\nWaters,10\n\u{FEFF}2,Gori,20\n").unwrap();
+
+        let mut stats = Stats::default();
+        let records = reconstruct_records(path, HeaderMode::HasHeaders, Delimiter::Comma, &mut stats).unwrap();
+        let mut headerless_stats = Stats::default();
+        let headerless = reconstruct_records_with_columns(path, Delimiter::Comma, 3, &mut headerless_stats).unwrap();
+        fs::remove_file(path).unwrap();
+
+        assert_eq!(&records[0][0], "ID");
+        assert_eq!(stats.stripped_leading_bytes, 3);
+        assert_eq!(&headerless[0][0], "ID");
+        assert_eq!(headerless_stats.stripped_leading_bytes, 3);
+        // Only the start of the stream is scrubbed
+        assert_eq!(&records[2][0], "\u{FEFF}2");
+    }
+
+    #[test]
+    fn test_comment_lines_before_header_are_skipped() {
+        let content = "# exported 2024-03-01\n# source: rs.ge\nID,Name,Amount\n1,Tbilisi\nWaters,10\n2,Gori,20\n";
+        let config = ReconstructConfig { comment: Some(b'#'), ..ReconstructConfig::default() };
+        let mut stats = Stats::default();
+        let rows = reconstruct_rows_from_reader(content.as_bytes(), &config, &mut stats).unwrap();
+        let records: Vec<Vec<&str>> = rows.iter().map(|row| row.record.iter().collect()).collect();
+
+        assert_eq!(records, [vec!["ID", "Name", "Amount"], vec!["1", "Tbilisi\nWaters", "10"], vec!["2", "Gori", "20"]]);
+        assert_eq!(stats.fixed_rows, 1);
+        assert_eq!(stats.removed_rows, 0);
+    }
+
+    #[test]
+    fn test_blank_rows_are_skipped_without_counting_as_removed() {
+        let content = "ID,Name,Amount\n1,Tbilisi\n\n , ,\nWaters,10\n,,\n\n2,Gori,20\n  \n";
+        let mut stats = Stats::default();
+        let rows = reconstruct_rows_from_reader(content.as_bytes(), &ReconstructConfig::default(), &mut stats).unwrap();
+        let mut streamed = crate::stream::RecordReconstructor::new(content.as_bytes(), &ReconstructConfig::default()).unwrap();
+        let streamed_rows = streamed.by_ref().count();
+        let stream_stats = streamed.into_stats();
+
+        assert_eq!(rows.len(), 3);
+        assert_eq!(&rows[1].record[1], "Tbilisi\nWaters");
+        assert_eq!((stats.total_rows, stats.fixed_rows, stats.removed_rows), (3, 1, 0));
+        assert_eq!(stats.blank_lines, 5);
+        assert!(stats.reconcile().is_balanced());
+        assert_eq!(streamed_rows, 3);
+        assert_eq!((stream_stats.removed_rows, stream_stats.blank_lines), (0, 5));
+    }
+
+    #[test]
+    fn test_stray_control_byte_before_header_is_stripped() {
+        let content = "\x00ID,Name,Amount\n1,Tbilisi\nWaters,10\n";
+        let mut stats = Stats::default();
+        let rows = reconstruct_rows_from_reader(content.as_bytes(), &ReconstructConfig::default(), &mut stats).unwrap();
+
+        assert_eq!(stats.stripped_leading_bytes, 1);
+        assert_eq!(&rows[0].record[0], "ID");
+        assert_eq!(rows.len(), 2);
+    }
+
+    #[test]
+    fn test_delimiter_and_header_mode_parsing_is_forgiving() {
+        assert!(matches!("SEMICOLON".parse(), Ok(Delimiter::Semicolon)));
+        assert!(matches!(" ; ".parse(), Ok(Delimiter::Semicolon)));
+        assert!(matches!("Comma".parse(), Ok(Delimiter::Comma)));
+        assert!(matches!("  pipe\n".parse(), Ok(Delimiter::Pipe)));
+        assert!(matches!("AUTO".parse(), Ok(Delimiter::Auto)));
+        assert!(matches!("^".parse(), Ok(Delimiter::Custom(b'^'))));
+        assert!(matches!("0x1F".parse(), Ok(Delimiter::Custom(0x1F))));
+        assert!("\"".parse::<Delimiter>().is_err());
+        assert!(matches!("\t".parse(), Ok(Delimiter::Tab)));
+        assert!(matches!("\\t".parse(), Ok(Delimiter::Tab)));
+        assert!("colon".parse::<Delimiter>().is_err());
+
+        assert!(matches!(" No-Headers ".parse(), Ok(HeaderMode::NoHeaders)));
+        assert!(matches!("HAS_HEADERS".parse(), Ok(HeaderMode::HasHeaders)));
+        assert!("maybe".parse::<HeaderMode>().is_err());
+    }
+
+    #[test]
+    fn test_header_type_hints_flag_or_reject_violations() {
+        let content = "id_int,name,amount_decimal\n1,Tbilisi Waters,10.50\n2,Gori,twenty\n3,Kutaisi,30\n";
+        let mut config = ReconstructConfig::new(HeaderMode::HasHeaders, Delimiter::Comma);
+        config.header_type_hints = true;
+
+        let mut stats = Stats::default();
+        let rows = reconstruct_rows_from_reader(content.as_bytes(), &config, &mut stats).unwrap();
+        assert_eq!(rows.len(), 4);
+        assert_eq!(
+            stats.warnings,
+            vec![Warning::TypeHintViolation {
+                out_row: 3,
+                column: 2,
+                expected: ColumnType::Decimal,
+                value: "twenty".to_string(),
+            }]
+        );
+
+        config.type_hint_policy = TypeHintPolicy::Reject;
+        let mut stats = Stats::default();
+        let rows = reconstruct_rows_from_reader(content.as_bytes(), &config, &mut stats).unwrap();
+        assert_eq!(rows.len(), 3);
+        assert_eq!(&rows[2].record[0], "3");
+        assert_eq!(stats.removed_rows, 1);
+        assert!(stats.warnings.is_empty());
+    }
+
+    #[test]
+    fn test_reverse_mode_anchors_records_on_their_end() {
+        // A leading orphan fragment glues onto the first record going forward
+        let content = "ID,Name,Amount\norphan\n1,Tbilisi\nWaters,10\n2,Gori,20\n";
+        let mut config = ReconstructConfig::new(HeaderMode::HasHeaders, Delimiter::Comma);
+        config.reverse = true;
+
+        let mut stats = Stats::default();
+        let rows = reconstruct_rows_from_reader(content.as_bytes(), &config, &mut stats).unwrap();
+
+        let records: Vec<Vec<&str>> = rows.iter().map(|row| row.record.iter().collect()).collect();
+        assert_eq!(
+            records,
+            vec![
+                vec!["ID", "Name", "Amount"],
+                vec!["1", "Tbilisi\nWaters", "10"],
+                vec!["2", "Gori", "20"],
+            ]
+        );
+        assert_eq!(rows[1].source_lines, vec![3, 4]);
+        assert_eq!(stats.total_rows, 4);
+        assert_eq!(stats.fixed_rows, 1);
+        assert_eq!(stats.removed_rows, 1);
+    }
+
+    #[test]
+    fn test_internal_newline_unifies_mixed_line_endings() {
+        let content = "ID,Note,Amount\n1,\"first\r\nsecond\rthird\",10\n2,Split\nacross,20\n";
+        let mut config = ReconstructConfig::new(HeaderMode::HasHeaders, Delimiter::Comma);
+        config.internal_newline = Some(LineTerminator::Lf);
+
+        let mut stats = Stats::default();
+        let rows = reconstruct_rows_from_reader(content.as_bytes(), &config, &mut stats).unwrap();
+        assert_eq!(&rows[1].record[1], "first\nsecond\nthird");
+        assert_eq!(&rows[2].record[1], "Split\nacross");
+
+        config.internal_newline = Some(LineTerminator::CrLf);
+        let mut stats = Stats::default();
+        let rows = reconstruct_rows_from_reader(content.as_bytes(), &config, &mut stats).unwrap();
+        assert_eq!(&rows[1].record[1], "first\r\nsecond\r\nthird");
+        assert_eq!(&rows[2].record[1], "Split\r\nacross");
+    }
+
+    #[test]
+    fn test_column_count_from_reference_file() {
+        let reference = "test_reference_columns.csv";
+        fs::write(reference, "id,organization,amount\n").unwrap();
+
+        let mut config = ReconstructConfig::new(HeaderMode::NoHeaders, Delimiter::Comma);
+        config.column_count_strategy = ColumnCountStrategy::FromReferenceFile(reference.into());
+
+        let content = "1,Tbilisi\nWaters,10\n2,Gori,20\n";
+        let mut stats = Stats::default();
+        let rows = reconstruct_rows_from_reader(content.as_bytes(), &config, &mut stats).unwrap();
+        let _ = fs::remove_file(reference);
+
+        assert_eq!(rows.len(), 2);
+        assert_eq!(&rows[0].record[1], "Tbilisi\nWaters");
+        assert_eq!(stats.fixed_rows, 1);
+    }
+
+    #[test]
+    fn test_width_strategies_parse_like_the_input() {
+        // Read with `"` quotes, the commas inside the quoted names would add a column
+        let content = "1,'Tbilisi, Waters',10\n2,'Gori, Ltd',20\n3,Kutaisi\nFoods,30\n";
+        let mut config = ReconstructConfig::new(HeaderMode::NoHeaders, Delimiter::Comma);
+        config.quote = QuoteChar::Single;
+        config.column_count_strategy = ColumnCountStrategy::Quantile(0.9);
+
+        let mut stats = Stats::default();
+        let rows = reconstruct_rows_from_reader(content.as_bytes(), &config, &mut stats).unwrap();
+        assert_eq!(rows.len(), 3);
+        assert!(rows.iter().all(|row| row.record.len() == 3));
+        assert_eq!(&rows[2].record[1], "Kutaisi\nFoods");
+
+        let reference = "test_reference_quoted.csv.gz";
+        let mut encoder = GzEncoder::new(File::create(reference).unwrap(), Compression::default());
+        encoder.write_all(b"# layout v2\nid,'organization, name',amount\n").unwrap();
+        encoder.finish().unwrap();
+        config.comment = Some(b'#');
+        config.column_count_strategy = ColumnCountStrategy::FromReferenceFile(reference.into());
+        let mut stats = Stats::default();
+        let rows = reconstruct_rows_from_reader(content.as_bytes(), &config, &mut stats).unwrap();
+        let _ = fs::remove_file(reference);
+
+        assert_eq!(rows.len(), 3);
+        assert_eq!(stats.fixed_rows, 1);
+    }
+
+    #[test]
+    fn test_numeric_columns_are_canonicalized() {
+        let content = "ID;Amount\n1;1.234,56\n2;1,234.56\n3;1234\n4;1234.5\n5;n/a\n";
+        let mut config = ReconstructConfig::new(HeaderMode::HasHeaders, Delimiter::Semicolon);
+        config.numeric_columns = vec![1];
+
+        let mut stats = Stats::default();
+        let rows = reconstruct_rows_from_reader(content.as_bytes(), &config, &mut stats).unwrap();
+        let amounts: Vec<&str> = rows.iter().map(|row| &row.record[1]).collect();
+        assert_eq!(amounts, vec!["Amount", "1234.56", "1234.56", "1234", "1234.5", "n/a"]);
+    }
+
+    #[test]
+    fn test_unterminated_final_quote_is_salvaged() {
+        let content = "ID,Name,Amount\n1,Tbilisi Waters,10\n2,\"Gori\nBeverages,20\n";
+        let mut config = ReconstructConfig::new(HeaderMode::HasHeaders, Delimiter::Comma);
+
+        let mut stats = Stats::default();
+        let rows = reconstruct_rows_from_reader(content.as_bytes(), &config, &mut stats).unwrap();
+        assert_eq!(rows.len(), 2);
+        assert_eq!(
+            stats.warnings,
+            vec![Warning::UnterminatedQuote { line: 3 }, Warning::TrailingIncompleteRow { line: 3 }]
+        );
+
+        config.repair_unterminated_quote = true;
+        let mut stats = Stats::default();
+        let rows = reconstruct_rows_from_reader(content.as_bytes(), &config, &mut stats).unwrap();
+        let records: Vec<Vec<&str>> = rows.iter().map(|row| row.record.iter().collect()).collect();
+        assert_eq!(
+            records,
+            vec![
+                vec!["ID", "Name", "Amount"],
+                vec!["1", "Tbilisi Waters", "10"],
+                vec!["2", "Gori\nBeverages,20", ""],
+            ]
+        );
+        assert!(rows[2].fixed);
+        assert_eq!(stats.removed_rows, 0);
+        assert_eq!(stats.warnings, vec![Warning::UnterminatedQuote { line: 3 }]);
+    }
+
+    #[test]
+    fn test_literal_quotes_in_unquoted_fields_are_not_unterminated() {
+        let config = ReconstructConfig { repair_unterminated_quote: true, ..ReconstructConfig::default() };
+        let content = "ID,Desc\n1,12\" pipe\n2,ok\n";
+
+        let mut stats = Stats::default();
+        let rows = reconstruct_rows_from_reader(content.as_bytes(), &config, &mut stats).unwrap();
+
+        assert_eq!(&rows[1].record[1], "12\" pipe");
+        assert!(rows.iter().all(|row| !row.fixed));
+        assert_eq!(stats.fixed_rows, 0);
+        assert!(stats.warnings.is_empty());
+    }
+
+    #[test]
+    fn test_unterminated_quote_in_discarded_row_salvages_nothing() {
+        let config = ReconstructConfig { repair_unterminated_quote: true, ..ReconstructConfig::default() };
+        let content = "A,B\n1,2\n3,4,\"5\n";
+
+        let mut stats = Stats::default();
+        let rows = reconstruct_rows_from_reader(content.as_bytes(), &config, &mut stats).unwrap();
+
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[1].record, StringRecord::from(vec!["1", "2"]));
+        assert!(!rows[1].fixed);
+        assert_eq!((stats.fixed_rows, stats.removed_rows), (0, 1));
+        assert_eq!(
+            stats.warnings,
+            vec![Warning::OverLengthRow { line: 3, got: 3, expected: 2 }, Warning::UnterminatedQuote { line: 3 }]
+        );
+    }
+
+    #[test]
+    fn test_leading_column_pattern_realigns_dropped_leading_field() {
+        // Column 0 is always empty; column 1 holds an invoice number
+        let content = "Flag,Invoice,Name,Amount\n,INV-1,Tbilisi Waters,10\nINV-2,Gori,20\n,INV-3,Kutaisi,30\n";
+        let mut config = ReconstructConfig::new(HeaderMode::HasHeaders, Delimiter::Comma);
+        config.leading_column_pattern = Some(Regex::new(r"^INV-\d+$").unwrap());
+
+        let mut stats = Stats::default();
+        let rows = reconstruct_rows_from_reader(content.as_bytes(), &config, &mut stats).unwrap();
+        let records: Vec<Vec<&str>> = rows.iter().map(|row| row.record.iter().collect()).collect();
+        assert_eq!(
+            records,
+            vec![
+                vec!["Flag", "Invoice", "Name", "Amount"],
+                vec!["", "INV-1", "Tbilisi Waters", "10"],
+                vec!["", "INV-2", "Gori", "20"],
+                vec!["", "INV-3", "Kutaisi", "30"],
+            ]
+        );
+        assert!(rows[2].fixed);
+        assert_eq!(stats.fixed_rows, 1);
+    }
+
+    #[test]
+    fn test_normalize_modes_on_output() {
+        let rows = vec![StringRecord::from(vec!["1", "  12  Rustaveli Ave\n  Apt   4 "])];
+        let written = |mode| {
+            let mut buffer = Vec::new();
+            write_output_csv_to_writer(&mut buffer, &rows, Delimiter::Comma, mode).unwrap();
+            String::from_utf8(buffer).unwrap()
+        };
+
+        assert_eq!(written(NormalizeMode::Collapse), "1,12 Rustaveli Ave Apt 4\n");
+        assert_eq!(written(NormalizeMode::PreserveNewlines), "1,\"12 Rustaveli Ave\nApt 4\"\n");
+        assert_eq!(written(NormalizeMode::TrimOnly), "1,\"12  Rustaveli Ave\n  Apt   4\"\n");
+        assert_eq!(NormalizeMode::PreserveNewlines.apply("a \r\n b"), "a\r\nb");
+    }
+
+    #[test]
+    fn test_two_row_header_is_combined() {
+        let lines = [
+            "Supplier,,Totals",
+            "ID,Name,Amount",
+            "1,Tbilisi Waters,10",
+            "2,Gori",
+            "Beverages,20",
+        ];
+        let config = ReconstructConfig { header_rows: 2, ..ReconstructConfig::default() };
+        let mut stats = Stats::default();
+        let rows = reconstruct_from_lines(&lines, &config, &mut stats).unwrap();
+
+        assert_eq!(rows[0].record, StringRecord::from(vec!["Supplier ID", "Name", "Totals Amount"]));
+        assert_eq!(rows[0].source_lines, vec![1, 2]);
+        assert_eq!(rows.len(), 3);
+        assert_eq!(&rows[2].record[1], "Gori\nBeverages");
+        assert_eq!(stats.total_rows, 3);
+    }
+
+    #[test]
+    fn test_stitch_separator() {
+        let lines = ["ID,Word,Amount", "1,hyphen-", "ated,10"];
+        let stitched = |separator: &str, reverse| {
+            let config = ReconstructConfig {
+                stitch_separator: separator.to_string(),
+                reverse,
+                ..ReconstructConfig::default()
+            };
+            let rows = reconstruct_from_lines(&lines, &config, &mut Stats::default()).unwrap();
+            rows[1].record[1].to_string()
+        };
+
+        assert_eq!(stitched("\n", false), "hyphen-\nated");
+        assert_eq!(stitched("", false), "hyphen-ated");
+        assert_eq!(stitched(" ", true), "hyphen- ated");
+    }
+
+    #[test]
+    fn test_custom_delimiter_reads_and_writes() {
+        let input = "ID\x1fName\x1fAmount\n1\x1fTbilisi, Waters\x1f10\n2\x1fGori\nBeverages\x1f20\n";
+        let delimiter = Delimiter::Custom(0x1F);
+        let records = reconstruct_records_from_reader(input.as_bytes(), HeaderMode::HasHeaders, delimiter, &mut Stats::default()).unwrap();
+        assert_eq!(records.len(), 3);
+        assert_eq!(&records[1][1], "Tbilisi, Waters");
+
+        let mut reader = build_csv_reader("a^b\n".as_bytes(), HeaderMode::NoHeaders, Delimiter::Custom(b'^'));
+        assert_eq!(reader.records().next().unwrap().unwrap().len(), 2);
+
+        let output = "test_custom_delimiter.csv";
+        write_output_csv(output, &records, Delimiter::Custom(b'^'), NormalizeMode::Collapse).unwrap();
+        let written = fs::read_to_string(output).unwrap();
+        let _ = fs::remove_file(output);
+        assert_eq!(written, "ID^Name^Amount\n1^Tbilisi, Waters^10\n2^Gori Beverages^20\n");
+    }
+
+    #[test]
+    fn test_convert_tab_to_pipe() {
+        let input = "test_convert_input.tsv";
+        let output = "test_convert_output.csv";
+        fs::write(input, "ID\tName\tNotes\n1\tTbilisi|Waters\tsaid \"hi\"\n2\tGori\nBeverages\ta, b\n").unwrap();
+
+        let mut stats = Stats::default();
+        convert_delimiter(input, output, HeaderMode::HasHeaders, Delimiter::Tab, Delimiter::Pipe, &mut stats).unwrap();
+        let written = fs::read_to_string(output).unwrap();
+        let _ = fs::remove_file(input);
+        let _ = fs::remove_file(output);
+
+        assert_eq!(
+            written,
+            "ID|Name|Notes\n1|\"Tbilisi|Waters\"|\"said \"\"hi\"\"\"\n2|Gori Beverages|a, b\n"
+        );
+        assert_eq!(stats.fixed_rows, 1);
+    }
+
+    #[test]
+    fn test_discarded_records_sidecar() {
+        let input = "test_discarded_input.csv";
+        let rejects = "test_discarded_rejects.csv";
+        fs::write(input, "ID,Name,Amount\n1,Tbilisi Waters,10\n2,x,y,z\n3,Gori\nBeverages,20,extra\n4,Mestia,40\n5,Sarme\n").unwrap();
+
+        let mut stats = Stats::default();
+        let (records, discarded) =
+            reconstruct_records_with_discarded(input, HeaderMode::HasHeaders, Delimiter::Comma, &mut stats).unwrap();
+        let _ = fs::remove_file(input);
+        assert_eq!(records.len(), 3);
+        assert_eq!(discarded.len(), stats.rows_discarded);
+
+        write_discarded_csv(rejects, &discarded, Delimiter::Comma).unwrap();
+        let written = fs::read_to_string(rejects).unwrap();
+        let _ = fs::remove_file(rejects);
+        assert_eq!(written, "2,x,y,z\n3,Gori\nBeverages,20,extra\n5,Sarme\n");
+    }
+
+    #[test]
+    fn test_legacy_input_encoding_is_decoded_before_parsing() {
+        let (cp1251, _, _) = encoding_rs::WINDOWS_1251.encode("ID,Имя,Сумма\n1,Тбилиси\nВодоканал,10\n");
+        let config = ReconstructConfig {
+            input_encoding: InputEncoding::Auto,
+            ..ReconstructConfig::default()
+        };
+        let mut stats = Stats::default();
+        let rows = reconstruct_rows_from_reader(cp1251.as_ref(), &config, &mut stats).unwrap();
+
+        assert_eq!(rows[0].record, StringRecord::from(vec!["ID", "Имя", "Сумма"]));
+        assert_eq!(rows[1].record, StringRecord::from(vec!["1", "Тбилиси\nВодоканал", "10"]));
+        assert_eq!(stats.fixed_rows, 1);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_stats_serde_round_trip() {
+        let content = "ID,Name,Amount\n1,Tbilisi\nWaters,10\n2,Gori,20,extra\n";
+        let config = ReconstructConfig { collect_discarded: true, ..ReconstructConfig::default() };
+        let mut stats = Stats::default();
+        reconstruct_rows_from_reader(content.as_bytes(), &config, &mut stats).unwrap();
+        stats.warnings.push(Warning::AmbiguousDelimiter {
+            chosen: Delimiter::Semicolon,
+            tied: vec![Delimiter::Semicolon, Delimiter::Custom(b'^')],
+        });
+
+        let json = serde_json::to_string(&stats).unwrap();
+        let restored: Stats = serde_json::from_str(&json).unwrap();
+
+        assert_eq!((restored.total_rows, restored.fixed_rows, restored.removed_rows), (3, 1, 1));
+        assert_eq!(restored.discarded_lines, [4]);
+        assert_eq!(restored.warnings, stats.warnings);
+        // Raw records are left out of the report
+        assert_eq!(stats.discarded_records.len(), 1);
+        assert!(restored.discarded_records.is_empty());
+
+        let delimiter: Delimiter = serde_json::from_str(&serde_json::to_string(&Delimiter::Tab).unwrap()).unwrap();
+        assert_eq!(delimiter, Delimiter::Tab);
+        let mode: HeaderMode = serde_json::from_str("\"NoHeaders\"").unwrap();
+        assert_eq!(mode, HeaderMode::NoHeaders);
+    }
+
+    #[test]
+    fn test_stats_track_fixed_and_discarded_lines() {
+        let lines = [
+            "ID,Name,Amount",
+            "1,Tbilisi",
+            "Waters,10",
+            "2,x,y,z",
+            "3,Gori,20",
+            "4,Mestia",
+            "Sarme,40",
+            "5,Batumi",
+        ];
+        let mut stats = Stats::default();
+        reconstruct_from_lines(&lines, &ReconstructConfig::default(), &mut stats).unwrap();
+
+        assert_eq!(stats.fixed_lines, [2, 6]);
+        assert_eq!(stats.discarded_lines, [4, 8]);
+        assert_eq!(stats.discarded_lines.len(), stats.rows_discarded);
+    }
+
+    #[test]
+    fn test_discarded_rows_are_reported_as_warnings() {
+        let content = "ID,Name,Amount\n1,Tbilisi\nWaters,x,10\n2,x,y,z\n3,Gori,20\n4,Mestia\n";
+        let expected = vec![
+            Warning::OverLengthRow { line: 2, got: 4, expected: 3 },
+            Warning::OverLengthRow { line: 4, got: 4, expected: 3 },
+            Warning::TrailingIncompleteRow { line: 6 },
+        ];
+
+        let mut stats = Stats::default();
+        reconstruct_rows_from_reader(content.as_bytes(), &ReconstructConfig::default(), &mut stats).unwrap();
+        let mut streamed = crate::RecordReconstructor::new(content.as_bytes(), &ReconstructConfig::default()).unwrap();
+        streamed.by_ref().for_each(drop);
+
+        assert_eq!(stats.warnings, expected);
+        assert_eq!(streamed.into_stats().warnings, expected);
+        assert_eq!(expected[0].to_string(), "line 2: row has 4 fields instead of 3 and was discarded");
+    }
+
+    #[test]
+    fn test_stitch_overshoot_tolerance_drops_empty_boundary_fields() {
+        let lines = [
+            "ID,Name,Details,Amount",
+            "1,Tbilisi,",
+            "Waters,x,10",
+            "2,Gori",
+            ",Beverages,y,20",
+            "3,Mestia,",
+            ",Valley,z,30",
+        ];
+        let mut stats = Stats::default();
+        let rows = reconstruct_from_lines(&lines, &ReconstructConfig::default(), &mut stats).unwrap();
+        assert_eq!(rows.len(), 1);
+        assert_eq!(stats.removed_rows, 3);
+
+        let config = ReconstructConfig { stitch_overshoot_tolerance: 1, ..ReconstructConfig::default() };
+        let mut stats = Stats::default();
+        let rows = reconstruct_from_lines(&lines, &config, &mut stats).unwrap();
+        let records: Vec<Vec<&str>> = rows.iter().skip(1).map(|row| row.record.iter().collect()).collect();
+
+        assert_eq!(records, [vec!["1", "Tbilisi\nWaters", "x", "10"], vec!["2", "Gori\nBeverages", "y", "20"]]);
+        // Two empty boundary fields overshoot by two
+        assert_eq!((stats.fixed_rows, stats.removed_rows), (2, 1));
+    }
+
+    #[test]
+    fn test_pad_trailing_row_keeps_last_record() {
+        let content = "ID,Name,Amount,Notes\n1,Tbilisi,10,ok\n2,Gori\nBeverages";
+        let mut stats = Stats::default();
+        let records = ReconstructOptions::new().reconstruct(content.as_bytes(), &mut stats).unwrap();
+        assert_eq!(records.len(), 2);
+        assert_eq!(stats.removed_rows, 1);
+
+        let mut stats = Stats::default();
+        let options = ReconstructOptions::new().pad_trailing_row(true);
+        let rows = reconstruct_rows_from_reader(content.as_bytes(), options.config(), &mut stats).unwrap();
+
+        assert_eq!(rows.len(), 3);
+        assert_eq!(rows[2].record, StringRecord::from(vec!["2", "Gori\nBeverages", "", ""]));
+        assert!(rows[2].fixed);
+        assert_eq!(rows[2].source_lines, [3, 4]);
+        assert_eq!((stats.fixed_rows, stats.removed_rows), (1, 0));
+        assert!(stats.warnings.is_empty());
+    }
+
+    #[test]
+    fn test_decimal_comma_repair() {
+        let lines = [
+            "ID,Name,Price,Qty",
+            "1,Tbilisi Waters,12,50,3",
+            "2,Gori,1.234,56,7",
+            "3,Mestia,-4,10,2,20",
+        ];
+        let mut config = ReconstructConfig { repair_decimal_comma: true, ..ReconstructConfig::default() };
+        let mut stats = Stats::default();
+        let rows = reconstruct_from_lines(&lines, &config, &mut stats).unwrap();
+
+        assert_eq!(rows[1].record, StringRecord::from(vec!["1", "Tbilisi Waters", "12,50", "3"]));
+        assert!(rows[1].fixed);
+        assert_eq!(rows[2].record, StringRecord::from(vec!["2", "Gori", "1.234,56", "7"]));
+        assert_eq!(rows[3].record, StringRecord::from(vec!["3", "Mestia", "-4,10", "2,20"]));
+
+        config.decimal_comma_to_point = true;
+        let rows = reconstruct_from_lines(&lines[..3], &config, &mut Stats::default()).unwrap();
+        assert_eq!(&rows[1].record[2], "12.50");
+        assert_eq!(&rows[2].record[2], "1234.56");
+
+        // Either "3,10" or "10,25" could be the decimal: left alone and discarded
+        let ambiguous = ["ID,Name,Price,Qty", "1,Gori,3,10,25"];
+        let rows = reconstruct_from_lines(&ambiguous, &config, &mut Stats::default()).unwrap();
+        assert_eq!(rows.len(), 1);
+    }
+
+    #[test]
+    fn test_gzip_input_and_output_round_trip() {
+        let input = "test_gzip_input.csv.gz";
+        let output = "test_gzip_round_trip.csv.gz";
+        let mut encoder = GzEncoder::new(File::create(input).unwrap(), Compression::default());
+        encoder.write_all(b"ID,Name,Amount\n1,Tbilisi\nWaters,10\n2,Gori,20\n").unwrap();
+        encoder.finish().unwrap();
+
+        let mut stats = Stats::default();
+        let records = reconstruct_records(input, HeaderMode::HasHeaders, Delimiter::Comma, &mut stats).unwrap();
+        write_output_csv(output, &records, Delimiter::Comma, NormalizeMode::Collapse).unwrap();
+        let compressed = fs::read(output).unwrap();
+        let mut again = Stats::default();
+        let read_back = reconstruct_records_with_config(output, &ReconstructConfig::default(), &mut again).unwrap();
+        fs::remove_file(input).unwrap();
+        fs::remove_file(output).unwrap();
+
+        assert_eq!(&records[1], &StringRecord::from(vec!["1", "Tbilisi\nWaters", "10"]));
+        assert_eq!(stats.fixed_rows, 1);
+        assert_eq!(&compressed[..2], &[0x1f, 0x8b]);
+        assert_eq!(&read_back[1], &StringRecord::from(vec!["1", "Tbilisi Waters", "10"]));
+        assert_eq!((read_back.len(), again.fixed_rows), (3, 0));
+    }
+
+    #[test]
+    fn test_reconstruct_records_resolves_auto_delimiter() {
+        let filename = "test_reconstruct_records_auto.csv";
+        fs::write(filename, "ID;Name;Amount\n1;Tbilisi\nWaters;10\n2;Gori;20\n").unwrap();
+
+        let mut stats = Stats::default();
+        let records = reconstruct_records(filename, HeaderMode::HasHeaders, Delimiter::Auto, &mut stats).unwrap();
+        fs::remove_file(filename).unwrap();
+
+        assert_eq!(records.len(), 3);
+        assert_eq!(&records[1], &StringRecord::from(vec!["1", "Tbilisi\nWaters", "10"]));
+        assert_eq!(stats.fixed_rows, 1);
+    }
 }
@@ -0,0 +1,108 @@
+// src/error.rs
+//! Error types
+//!
+//! Every fallible library function returns [`FixerrError`], so callers can
+//! tell a bad file (`Io`, `Csv`) from bad data or settings and match on the
+//! failure instead of inspecting strings.
+
+use std::error::Error;
+use std::fmt;
+use std::io;
+
+/// An error raised by Fixerr
+#[derive(Debug)]
+pub enum FixerrError {
+    /// Reading or writing a file or stream failed
+    Io(io::Error),
+    /// The csv reader or writer failed
+    Csv(csv::Error),
+    /// The expected column count is missing or unusable
+    InvalidColumnCount(String),
+    /// An input needed to complete the operation holds no records
+    EmptyInput,
+    /// A configuration option is inconsistent or refers to something unknown
+    InvalidConfig(String),
+    /// Building a Polars DataFrame failed
+    #[cfg(feature = "polars")]
+    Polars(polars::prelude::PolarsError),
+    /// A retryable IO operation kept failing until the retry budget ran out
+    IoAfterRetries {
+        /// Total number of attempts made
+        attempts: usize,
+        /// The error from the final attempt
+        source: io::Error,
+    },
+    /// The input header differs from the configured `expect_headers`
+    HeaderMismatch {
+        /// The configured column names
+        expected: Vec<String>,
+        /// The column names found in the input
+        found: Vec<String>,
+    },
+    /// A written output file failed the strict (non-flexible) re-read
+    OutputNotStrictlyValid {
+        /// Line of the first record whose width differs from the first record's
+        line: u64,
+        /// Width of that record
+        width: usize,
+    },
+}
+
+impl fmt::Display for FixerrError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FixerrError::Io(e) => write!(f, "{e}"),
+            FixerrError::Csv(e) => write!(f, "{e}"),
+            FixerrError::InvalidColumnCount(message) => write!(f, "invalid column count: {message}"),
+            FixerrError::EmptyInput => write!(f, "input contains no records"),
+            FixerrError::InvalidConfig(message) => write!(f, "invalid configuration: {message}"),
+            #[cfg(feature = "polars")]
+            FixerrError::Polars(e) => write!(f, "{e}"),
+            FixerrError::IoAfterRetries { attempts, source } => {
+                write!(f, "IO operation failed after {attempts} attempts: {source}")
+            }
+            FixerrError::HeaderMismatch { expected, found } => {
+                write!(f, "header mismatch: expected [{}], found [{}]", expected.join(", "), found.join(", "))
+            }
+            FixerrError::OutputNotStrictlyValid { line, width } => {
+                write!(f, "output line {line} has {width} fields, unlike the first record")
+            }
+        }
+    }
+}
+
+impl Error for FixerrError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            FixerrError::Io(e) => Some(e),
+            FixerrError::Csv(e) => Some(e),
+            #[cfg(feature = "polars")]
+            FixerrError::Polars(e) => Some(e),
+            FixerrError::IoAfterRetries { source, .. } => Some(source),
+            FixerrError::InvalidColumnCount(_)
+            | FixerrError::EmptyInput
+            | FixerrError::InvalidConfig(_)
+            | FixerrError::HeaderMismatch { .. }
+            | FixerrError::OutputNotStrictlyValid { .. } => None,
+        }
+    }
+}
+
+impl From<io::Error> for FixerrError {
+    fn from(e: io::Error) -> Self {
+        FixerrError::Io(e)
+    }
+}
+
+impl From<csv::Error> for FixerrError {
+    fn from(e: csv::Error) -> Self {
+        FixerrError::Csv(e)
+    }
+}
+
+#[cfg(feature = "polars")]
+impl From<polars::prelude::PolarsError> for FixerrError {
+    fn from(e: polars::prelude::PolarsError) -> Self {
+        FixerrError::Polars(e)
+    }
+}
@@ -0,0 +1,162 @@
+// src/estimate.rs
+//! Pre-flight cost estimation
+//!
+//! Reconstructs a small sample from the start of a file and extrapolates the
+//! processing time and peak memory of a full run. Nothing is written.
+
+use crate::config::ReconstructConfig;
+use crate::engine::{build_csv_reader, reconstruct_rows_from_reader, HeaderMode, Stats};
+use crate::error::FixerrError;
+use csv::StringRecord;
+use std::fs::{self, File};
+use std::io::Read;
+use std::mem::size_of;
+use std::time::{Duration, Instant};
+
+/// Number of bytes read from the start of the file for sampling
+pub const ESTIMATE_SAMPLE_BYTES: usize = 256 * 1024;
+
+/// An estimated quantity, expressed as a range rather than a point value
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CostRange<T> {
+    /// Optimistic bound
+    pub low: T,
+    /// Pessimistic bound
+    pub high: T,
+}
+
+/// Estimated cost of reconstructing a file
+#[derive(Debug, Clone, PartialEq)]
+pub struct CostEstimate {
+    /// Size of the input file in bytes
+    pub file_size: u64,
+    /// Number of bytes actually sampled
+    pub sampled_bytes: u64,
+    /// Estimated number of logical records
+    pub records: CostRange<u64>,
+    /// Estimated wall-clock processing time
+    pub duration: CostRange<Duration>,
+    /// Estimated peak memory in bytes (records are held in memory)
+    pub peak_memory: CostRange<u64>,
+}
+
+/// Estimate processing time and memory for a file before running
+///
+/// Samples up to [`ESTIMATE_SAMPLE_BYTES`] from the start of the file,
+/// measures reconstruction speed and average record size on that sample and
+/// extrapolates to the full file size. Small samples are dominated by start-up
+/// cost and real files vary in density, so the result is a wide range.
+pub fn estimate_cost(
+    file_path: &str,
+    config: &ReconstructConfig,
+) -> Result<CostEstimate, FixerrError> {
+    let file_size = fs::metadata(file_path)?.len();
+
+    let mut sample = Vec::with_capacity(ESTIMATE_SAMPLE_BYTES);
+    File::open(file_path)?
+        .take(ESTIMATE_SAMPLE_BYTES as u64)
+        .read_to_end(&mut sample)?;
+
+    // Only keep complete physical lines unless the whole file fit
+    if (sample.len() as u64) < file_size {
+        if let Some(last_newline) = sample.iter().rposition(|&b| b == b'\n') {
+            sample.truncate(last_newline + 1);
+        }
+    }
+    let sampled_bytes = sample.len() as u64;
+
+    // Never prompt during a pre-flight check: take the width from the sample
+    let mut sample_config = config.clone();
+    if matches!(config.header_mode, HeaderMode::NoHeaders) && config.expected_columns.is_none() {
+        sample_config.expected_columns = Some(first_record_width(&sample, config));
+    }
+
+    let mut stats = Stats::default();
+    let started = Instant::now();
+    let rows = reconstruct_rows_from_reader(sample.as_slice(), &sample_config, &mut stats)?;
+    let elapsed = started.elapsed();
+
+    let record_count = rows.len().max(1) as u64;
+    let scale = if sampled_bytes == 0 {
+        1.0
+    } else {
+        file_size as f64 / sampled_bytes as f64
+    };
+
+    let records = (record_count as f64 * scale).ceil() as u64;
+    let per_record_memory = rows
+        .iter()
+        .map(|row| record_memory(&row.record))
+        .sum::<u64>()
+        / record_count;
+    let memory = records.saturating_mul(per_record_memory);
+    let duration = elapsed.mul_f64(scale);
+
+    Ok(CostEstimate {
+        file_size,
+        sampled_bytes,
+        records: CostRange {
+            low: records * 3 / 4,
+            high: records * 3 / 2,
+        },
+        duration: CostRange {
+            low: duration / 2,
+            high: duration * 2,
+        },
+        peak_memory: CostRange {
+            low: memory,
+            high: memory.saturating_mul(2),
+        },
+    })
+}
+
+// ============================================
+// Private Helper Functions
+// ============================================
+
+/// Approximate heap + inline size of a `StringRecord`
+fn record_memory(record: &StringRecord) -> u64 {
+    let text = record.as_slice().len();
+    let bounds = record.len() * size_of::<usize>();
+    (size_of::<StringRecord>() + text + bounds) as u64
+}
+
+fn first_record_width(sample: &[u8], config: &ReconstructConfig) -> usize {
+    let mut reader = build_csv_reader(sample, HeaderMode::NoHeaders, config.delimiter);
+    reader
+        .records()
+        .next()
+        .and_then(|r| r.ok())
+        .map_or(0, |r| r.len())
+}
+
+// ============================================
+// Unit Tests
+// ============================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_estimate_cost_extrapolates_from_sample() {
+        let filename = "test_estimate_cost.csv";
+        let mut content = String::from("ID,Organization,Details,Amount\n");
+        for i in 0..20_000 {
+            content.push_str(&format!("{i},Tbilisi Waters,Georgian Product,1722.63\n"));
+        }
+        fs::write(filename, &content).unwrap();
+
+        let config = ReconstructConfig::default();
+        let estimate = estimate_cost(filename, &config).unwrap();
+        let _ = fs::remove_file(filename);
+
+        assert_eq!(estimate.file_size, content.len() as u64);
+        assert!(estimate.sampled_bytes <= ESTIMATE_SAMPLE_BYTES as u64);
+        assert!(estimate.sampled_bytes < estimate.file_size);
+        assert!(estimate.records.low <= 20_001 && 20_001 <= estimate.records.high);
+        assert!(estimate.duration.low <= estimate.duration.high);
+        assert!(estimate.peak_memory.low >= estimate.file_size);
+        assert!(estimate.peak_memory.low <= estimate.peak_memory.high);
+    }
+}
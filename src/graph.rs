@@ -0,0 +1,120 @@
+// src/graph.rs
+//! Stitching graph export
+//!
+//! Describes how physical lines were combined into logical records, as JSON
+//! suitable for rendering in a debugging UI: each logical row is a group of
+//! physical-line nodes together with the action taken and its final width.
+
+use crate::engine::ReconstructedRow;
+use crate::error::FixerrError;
+use std::fmt::Write as _;
+use std::fs;
+
+/// How a logical row came to be
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StitchAction {
+    /// A header row
+    Header,
+    /// Read intact from a single physical row
+    Clean,
+    /// Stitched from several physical rows or split out of one
+    Fixed,
+}
+
+impl StitchAction {
+    /// Name used in the JSON output
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            StitchAction::Header => "header",
+            StitchAction::Clean => "clean",
+            StitchAction::Fixed => "fixed",
+        }
+    }
+}
+
+/// One logical row and the physical lines it was built from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StitchGroup {
+    /// 0-based position of the row in the reconstructed output
+    pub id: usize,
+    /// Start lines of the contributing physical rows
+    pub physical_lines: Vec<u64>,
+    /// Number of fields in the resulting record
+    pub width: usize,
+    /// How the row was produced
+    pub action: StitchAction,
+}
+
+/// Build the stitching graph of a reconstruction
+pub fn stitch_graph(rows: &[ReconstructedRow]) -> Vec<StitchGroup> {
+    rows.iter()
+        .enumerate()
+        .map(|(id, row)| StitchGroup {
+            id,
+            physical_lines: row.source_lines.clone(),
+            width: row.record.len(),
+            action: if row.header {
+                StitchAction::Header
+            } else if row.fixed {
+                StitchAction::Fixed
+            } else {
+                StitchAction::Clean
+            },
+        })
+        .collect()
+}
+
+/// Render the stitching graph as JSON
+///
+/// The format is `{"logical_rows":[{"id":0,"physical_lines":[14,15,16],"width":4,"action":"fixed"}, ...]}`.
+pub fn stitch_graph_json(rows: &[ReconstructedRow]) -> String {
+    let mut json = String::from("{\"logical_rows\":[");
+    for (i, group) in stitch_graph(rows).iter().enumerate() {
+        if i > 0 {
+            json.push(',');
+        }
+        let lines: Vec<String> = group.physical_lines.iter().map(u64::to_string).collect();
+        let _ = write!(
+            json,
+            "{{\"id\":{},\"physical_lines\":[{}],\"width\":{},\"action\":\"{}\"}}",
+            group.id,
+            lines.join(","),
+            group.width,
+            group.action.as_str()
+        );
+    }
+    json.push_str("]}");
+    json
+}
+
+/// Write the stitching graph JSON to `path`
+pub fn write_stitch_graph(path: &str, rows: &[ReconstructedRow]) -> Result<(), FixerrError> {
+    fs::write(path, stitch_graph_json(rows))?;
+    Ok(())
+}
+
+// ============================================
+// Unit Tests
+// ============================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::ReconstructConfig;
+    use crate::engine::{reconstruct_rows_from_reader, Stats};
+
+    #[test]
+    fn test_stitch_graph_matches_reconstruction() {
+        let content = "ID,Name,Amount\n1,Tbilisi\nWaters\n,10\n2,Gori,20\n";
+        let mut stats = Stats::default();
+        let rows = reconstruct_rows_from_reader(content.as_bytes(), &ReconstructConfig::default(), &mut stats).unwrap();
+
+        assert_eq!(
+            stitch_graph_json(&rows),
+            "{\"logical_rows\":[\
+             {\"id\":0,\"physical_lines\":[1],\"width\":3,\"action\":\"header\"},\
+             {\"id\":1,\"physical_lines\":[2,3,4],\"width\":3,\"action\":\"fixed\"},\
+             {\"id\":2,\"physical_lines\":[5],\"width\":3,\"action\":\"clean\"}]}"
+        );
+    }
+}
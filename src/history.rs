@@ -0,0 +1,242 @@
+// src/history.rs
+//! Width history across runs
+//!
+//! Recurring feeds keep the same structure from day to day. Remembering the
+//! column count and the physical row widths seen for an input lets later
+//! runs reuse the count and notice when a file suddenly looks different,
+//! which usually means the upstream export changed.
+
+use crate::config::ReconstructConfig;
+use crate::detect::{rank_delimiters, QUOTE_SAMPLE_BYTES};
+use crate::engine::{
+    build_quoted_reader, open_input, reconstruct_rows_with_config, ColumnCountStrategy, Delimiter, HeaderMode, ReconstructedRow,
+    Stats,
+};
+use crate::error::FixerrError;
+use crate::warning::Warning;
+use csv::StringRecord;
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::{self, Read};
+use std::path::Path;
+
+/// Share of rows whose width would have to change for a run to be reported
+/// as diverging from its history (total variation distance)
+pub const HISTORY_DIVERGENCE_THRESHOLD: f64 = 0.25;
+
+/// First line of a history file
+const HISTORY_FILE_HEADER: &str = "# fixerr width history v1";
+
+/// What earlier runs saw for one input
+#[derive(Debug, Clone, Default, PartialEq)]
+struct Baseline {
+    /// Number of runs recorded
+    runs: usize,
+    /// Column count of the latest run
+    expected_columns: usize,
+    /// Physical data rows per width, summed over all runs
+    widths: BTreeMap<usize, usize>,
+}
+
+/// Reconstruct `file_path`, checking it against and adding it to `history_path`
+///
+/// Inputs are keyed by `config.history_key` or, failing that, their file
+/// name. In `NoHeaders` mode without `expected_columns` the recorded column
+/// count is reused. Warns when the column count or the width distribution
+/// differs from the recorded runs. The input is read a second time to count
+/// physical row widths.
+pub(crate) fn reconstruct_with_history(
+    file_path: &str,
+    history_path: &Path,
+    config: &ReconstructConfig,
+    stats: &mut Stats,
+) -> Result<Vec<ReconstructedRow>, FixerrError> {
+    let key = match &config.history_key {
+        Some(key) => key.clone(),
+        None => Path::new(file_path).file_name().map_or_else(|| file_path.to_string(), |n| n.to_string_lossy().into_owned()),
+    };
+    // Keys are stored one per line, tab-separated from the counts
+    let key = key.replace(['\t', '\n', '\r'], " ");
+    let mut history = load_history(history_path)?;
+    let baseline = history.get(&key).cloned();
+
+    let mut config = ReconstructConfig { history_file: None, ..config.clone() };
+    let remembered = baseline.as_ref().map(|b| b.expected_columns).filter(|&count| count > 0);
+    if matches!(config.header_mode, HeaderMode::NoHeaders)
+        && config.expected_columns.is_none()
+        && config.column_count_strategy == ColumnCountStrategy::Detect
+    {
+        config.expected_columns = remembered;
+    }
+
+    let rows = reconstruct_rows_with_config(file_path, &config, stats)?;
+    let expected_columns = match rows.first() {
+        Some(row) => row.record.len(),
+        None => return Ok(rows),
+    };
+    let widths = count_widths(file_path, &config)?;
+
+    if let Some(baseline) = &baseline {
+        if baseline.expected_columns != expected_columns {
+            stats.warnings.push(Warning::ColumnCountChanged {
+                previous: baseline.expected_columns,
+                current: expected_columns,
+            });
+        }
+        let divergence = width_divergence(&baseline.widths, &widths);
+        if divergence > HISTORY_DIVERGENCE_THRESHOLD {
+            stats.warnings.push(Warning::WidthDistributionDiverged {
+                runs: baseline.runs,
+                divergence_percent: (divergence * 100.0).round() as u32,
+            });
+        }
+    }
+
+    let entry = history.entry(key).or_default();
+    entry.runs += 1;
+    entry.expected_columns = expected_columns;
+    for (width, count) in widths {
+        *entry.widths.entry(width).or_insert(0) += count;
+    }
+    save_history(history_path, &history)?;
+
+    Ok(rows)
+}
+
+// ============================================
+// Private Helper Functions
+// ============================================
+
+/// Physical data rows of `file_path` per width, headers excluded
+fn count_widths(file_path: &str, config: &ReconstructConfig) -> Result<BTreeMap<usize, usize>, FixerrError> {
+    let mut sample = Vec::new();
+    open_input(file_path, config)?.take(QUOTE_SAMPLE_BYTES as u64).read_to_end(&mut sample)?;
+    let delimiter = match config.delimiter {
+        Delimiter::Auto => rank_delimiters(&sample).0,
+        delimiter => delimiter,
+    };
+    let quote = config.quote.resolve(&sample, delimiter);
+
+    let source = open_input(file_path, config)?;
+    let mut reader = build_quoted_reader(source, HeaderMode::NoHeaders, delimiter, quote, config.comment);
+    let mut header_rows = if config.header_mode.as_bool() { config.header_rows } else { 0 };
+    let mut widths = BTreeMap::new();
+    let mut record = StringRecord::new();
+    while reader.read_record(&mut record)? {
+        if header_rows > 0 {
+            header_rows -= 1;
+            continue;
+        }
+        *widths.entry(record.len()).or_insert(0) += 1;
+    }
+    Ok(widths)
+}
+
+/// Total variation distance between two width distributions, in `[0, 1]`
+fn width_divergence(baseline: &BTreeMap<usize, usize>, current: &BTreeMap<usize, usize>) -> f64 {
+    let baseline_total: usize = baseline.values().sum();
+    let current_total: usize = current.values().sum();
+    if baseline_total == 0 || current_total == 0 {
+        return 0.0;
+    }
+
+    let share = |counts: &BTreeMap<usize, usize>, total: usize, width: &usize| {
+        counts.get(width).copied().unwrap_or(0) as f64 / total as f64
+    };
+    let distance: f64 = baseline
+        .keys()
+        .chain(current.keys().filter(|width| !baseline.contains_key(width)))
+        .map(|width| (share(baseline, baseline_total, width) - share(current, current_total, width)).abs())
+        .sum();
+    distance / 2.0
+}
+
+/// Read a history file; a missing file is an empty history
+///
+/// Each entry is one tab-separated line: key, runs, column count and the
+/// width counts as `width:count` pairs joined by commas.
+fn load_history(path: &Path) -> Result<BTreeMap<String, Baseline>, FixerrError> {
+    let content = match fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(BTreeMap::new()),
+        Err(e) => return Err(e.into()),
+    };
+
+    let invalid = |line: &str| FixerrError::InvalidConfig(format!("malformed history entry in {}: '{line}'", path.display()));
+    let mut history = BTreeMap::new();
+    for line in content.lines().filter(|line| !line.is_empty() && !line.starts_with('#')) {
+        let parts: Vec<&str> = line.split('\t').collect();
+        if parts.len() != 4 {
+            return Err(invalid(line));
+        }
+        let mut baseline = Baseline {
+            runs: parts[1].parse().map_err(|_| invalid(line))?,
+            expected_columns: parts[2].parse().map_err(|_| invalid(line))?,
+            widths: BTreeMap::new(),
+        };
+        for pair in parts[3].split(',').filter(|pair| !pair.is_empty()) {
+            let (width, count) = pair.split_once(':').ok_or_else(|| invalid(line))?;
+            baseline
+                .widths
+                .insert(width.parse().map_err(|_| invalid(line))?, count.parse().map_err(|_| invalid(line))?);
+        }
+        history.insert(parts[0].to_string(), baseline);
+    }
+    Ok(history)
+}
+
+/// Write `history` in the format read by [`load_history`]
+fn save_history(path: &Path, history: &BTreeMap<String, Baseline>) -> Result<(), FixerrError> {
+    let mut content = format!("{HISTORY_FILE_HEADER}\n");
+    for (key, baseline) in history {
+        let widths: Vec<String> = baseline.widths.iter().map(|(width, count)| format!("{width}:{count}")).collect();
+        content.push_str(&format!(
+            "{}\t{}\t{}\t{}\n",
+            key,
+            baseline.runs,
+            baseline.expected_columns,
+            widths.join(",")
+        ));
+    }
+    fs::write(path, content)?;
+    Ok(())
+}
+
+// ============================================
+// Unit Tests
+// ============================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_history_warns_when_widths_diverge() {
+        let history = "test_history_state.tsv";
+        let input = "test_history_feed.csv";
+        let _ = fs::remove_file(history);
+        let config = ReconstructConfig {
+            history_file: Some(PathBuf::from(history)),
+            ..ReconstructConfig::default()
+        };
+        let run = |content: &str| -> Stats {
+            fs::write(input, content).unwrap();
+            let mut stats = Stats::default();
+            reconstruct_rows_with_config(input, &config, &mut stats).unwrap();
+            stats
+        };
+
+        let first = run("ID,Name,Amount\n1,Tbilisi,10\n2,Gori,20\n3,Mestia,30\n4,Batumi,40\n");
+        let same = run("ID,Name,Amount\n5,Kutaisi,50\n6,Telavi,60\n");
+        let diverged = run("ID,Name,Amount\n7,Zugdidi\n,70\n8,Poti\n,80\n9,Rustavi,90\n");
+        let saved = fs::read_to_string(history).unwrap();
+        fs::remove_file(history).unwrap();
+        fs::remove_file(input).unwrap();
+
+        assert!(first.warnings.is_empty());
+        assert!(same.warnings.is_empty());
+        assert_eq!(diverged.warnings, vec![Warning::WidthDistributionDiverged { runs: 2, divergence_percent: 80 }]);
+        assert_eq!(saved, format!("{HISTORY_FILE_HEADER}\ntest_history_feed.csv\t3\t3\t2:4,3:7\n"));
+    }
+}
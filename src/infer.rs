@@ -0,0 +1,166 @@
+// src/infer.rs
+//! Column type inference
+//!
+//! Classifies each column of a set of reconstructed records into a coarse
+//! data type by sampling its values. Used to describe headerless files.
+
+use crate::engine::clean_and_normalize_field;
+use csv::StringRecord;
+
+/// Default number of records sampled when inferring column types
+pub const DEFAULT_INFERENCE_SAMPLE: usize = 1000;
+
+/// Coarse data type of a column
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ColumnType {
+    /// Whole numbers, optionally signed
+    Integer,
+    /// Numbers with a decimal point
+    Decimal,
+    /// Calendar dates (`YYYY-MM-DD`, `DD.MM.YYYY` or `DD/MM/YYYY`)
+    Date,
+    /// Anything else
+    Text,
+}
+
+impl ColumnType {
+    /// Short name used when generating placeholder headers
+    pub fn prefix(&self) -> &'static str {
+        match self {
+            ColumnType::Integer => "int",
+            ColumnType::Decimal => "decimal",
+            ColumnType::Date => "date",
+            ColumnType::Text => "text",
+        }
+    }
+
+    /// Classify a single (already normalized) value
+    pub fn of_value(value: &str) -> ColumnType {
+        if is_integer(value) {
+            ColumnType::Integer
+        } else if is_decimal(value) {
+            ColumnType::Decimal
+        } else if is_date(value) {
+            ColumnType::Date
+        } else {
+            ColumnType::Text
+        }
+    }
+
+    /// Narrowest type able to hold values of both `self` and `other`
+    fn widen(self, other: ColumnType) -> ColumnType {
+        use ColumnType::*;
+        match (self, other) {
+            (a, b) if a == b => a,
+            (Integer, Decimal) | (Decimal, Integer) => Decimal,
+            _ => Text,
+        }
+    }
+}
+
+/// Infer the type of every column from the first `sample_size` records
+///
+/// Empty values are ignored; a column with no non-empty values is `Text`.
+pub fn infer_column_types(rows: &[StringRecord], sample_size: usize) -> Vec<ColumnType> {
+    let width = rows.iter().map(|r| r.len()).max().unwrap_or(0);
+    let mut types: Vec<Option<ColumnType>> = vec![None; width];
+
+    for record in rows.iter().take(sample_size) {
+        for (i, raw) in record.iter().enumerate() {
+            let value = clean_and_normalize_field(raw);
+            if value.is_empty() {
+                continue;
+            }
+            let observed = ColumnType::of_value(&value);
+            types[i] = Some(match types[i] {
+                Some(current) => current.widen(observed),
+                None => observed,
+            });
+        }
+    }
+
+    types
+        .into_iter()
+        .map(|t| t.unwrap_or(ColumnType::Text))
+        .collect()
+}
+
+/// Build a header row such as `int_col_0,text_col_1,date_col_2`
+pub fn typed_header(types: &[ColumnType]) -> StringRecord {
+    types
+        .iter()
+        .enumerate()
+        .map(|(i, t)| format!("{}_col_{i}", t.prefix()))
+        .collect()
+}
+
+// ============================================
+// Private Helper Functions
+// ============================================
+
+fn is_integer(value: &str) -> bool {
+    let digits = value.strip_prefix(['-', '+']).unwrap_or(value);
+    !digits.is_empty() && digits.bytes().all(|b| b.is_ascii_digit())
+}
+
+fn is_decimal(value: &str) -> bool {
+    let unsigned = value.strip_prefix(['-', '+']).unwrap_or(value);
+    match unsigned.split_once('.') {
+        Some((int, frac)) => {
+            !(int.is_empty() && frac.is_empty())
+                && int.bytes().all(|b| b.is_ascii_digit())
+                && frac.bytes().all(|b| b.is_ascii_digit())
+        }
+        None => false,
+    }
+}
+
+fn is_date(value: &str) -> bool {
+    let parts: Vec<&str> = value.split(['-', '.', '/']).collect();
+    if parts.len() != 3 || !parts.iter().all(|p| p.bytes().all(|b| b.is_ascii_digit())) {
+        return false;
+    }
+
+    let lens: Vec<usize> = parts.iter().map(|p| p.len()).collect();
+    let (year, month, day) = match lens.as_slice() {
+        [4, 2, 2] if value.contains('-') => (parts[0], parts[1], parts[2]),
+        [2, 2, 4] if !value.contains('-') => (parts[2], parts[1], parts[0]),
+        _ => return false,
+    };
+
+    let month: u32 = month.parse().unwrap_or(0);
+    let day: u32 = day.parse().unwrap_or(0);
+    !year.is_empty() && (1..=12).contains(&month) && (1..=31).contains(&day)
+}
+
+// ============================================
+// Unit Tests
+// ============================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_value_classification() {
+        assert_eq!(ColumnType::of_value("9413154"), ColumnType::Integer);
+        assert_eq!(ColumnType::of_value("-12"), ColumnType::Integer);
+        assert_eq!(ColumnType::of_value("1722.63"), ColumnType::Decimal);
+        assert_eq!(ColumnType::of_value("2024-03-15"), ColumnType::Date);
+        assert_eq!(ColumnType::of_value("15.03.2024"), ColumnType::Date);
+        assert_eq!(ColumnType::of_value("15/03/2024"), ColumnType::Date);
+        assert_eq!(ColumnType::of_value("2024-13-01"), ColumnType::Text);
+        assert_eq!(ColumnType::of_value("Tbilisi Waters"), ColumnType::Text);
+    }
+
+    #[test]
+    fn test_integer_and_decimal_widen_to_decimal() {
+        let rows = vec![
+            StringRecord::from(vec!["1", "a"]),
+            StringRecord::from(vec!["2.5", ""]),
+        ];
+        let types = infer_column_types(&rows, DEFAULT_INFERENCE_SAMPLE);
+        assert_eq!(types, vec![ColumnType::Decimal, ColumnType::Text]);
+    }
+}
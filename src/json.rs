@@ -0,0 +1,183 @@
+// src/json.rs
+//! JSON output
+//!
+//! Writes reconstructed records as a JSON array for consumers that do not
+//! read CSV. Fields are cleaned exactly as for CSV output.
+
+use crate::engine::{clean_and_normalize_field, HeaderMode};
+use crate::error::FixerrError;
+use csv::StringRecord;
+use std::collections::HashSet;
+use std::fmt::Write as _;
+use std::fs;
+
+/// Render `rows` as a JSON array, one record per line
+///
+/// With `HasHeaders` the first row supplies the keys and every later row
+/// becomes an object; repeated header names get a `_2`, `_3`, ... suffix so
+/// no key is lost. Fields beyond the header width are dropped. With
+/// `NoHeaders` every row becomes an array of strings.
+///
+/// Values are JSON strings unless `coerce_numbers` is set, in which case
+/// fields that are valid JSON numbers (e.g. `-12.5`, `3e4`, but not `007`)
+/// are emitted as numbers.
+pub fn rows_to_json(rows: &[StringRecord], header_mode: HeaderMode, coerce_numbers: bool) -> String {
+    let (keys, data) = match (header_mode, rows.split_first()) {
+        (HeaderMode::HasHeaders, Some((header, data))) => {
+            (Some(unique_keys(header.iter().map(clean_and_normalize_field))), data)
+        }
+        _ => (None, rows),
+    };
+
+    let mut json = String::from("[");
+    for (i, record) in data.iter().enumerate() {
+        json.push_str(if i > 0 { ",\n" } else { "\n" });
+        let values = record.iter().map(|field| json_value(&clean_and_normalize_field(field), coerce_numbers));
+        match &keys {
+            Some(keys) => {
+                let members: Vec<String> =
+                    keys.iter().zip(values).map(|(key, value)| format!("{}:{}", json_string(key), value)).collect();
+                let _ = write!(json, "{{{}}}", members.join(","));
+            }
+            None => {
+                let values: Vec<String> = values.collect();
+                let _ = write!(json, "[{}]", values.join(","));
+            }
+        }
+    }
+    json.push_str(if data.is_empty() { "]\n" } else { "\n]\n" });
+    json
+}
+
+/// Write `rows` to `output_path` as rendered by [`rows_to_json`]
+pub fn write_output_json(
+    output_path: &str,
+    rows: &[StringRecord],
+    header_mode: HeaderMode,
+    coerce_numbers: bool,
+) -> Result<(), FixerrError> {
+    fs::write(output_path, rows_to_json(rows, header_mode, coerce_numbers))?;
+    Ok(())
+}
+
+// ============================================
+// Private Helper Functions
+// ============================================
+
+/// Header names with repeats suffixed `_2`, `_3`, ... until unique
+fn unique_keys(names: impl Iterator<Item = String>) -> Vec<String> {
+    let names: Vec<String> = names.collect();
+    let mut taken: HashSet<String> = HashSet::new();
+    let mut keys = Vec::with_capacity(names.len());
+    for name in names {
+        let mut key = name.clone();
+        let mut n = 1;
+        while taken.contains(&key) {
+            n += 1;
+            key = format!("{name}_{n}");
+        }
+        taken.insert(key.clone());
+        keys.push(key);
+    }
+    keys
+}
+
+/// A field as a JSON number when requested and valid, otherwise a JSON string
+fn json_value(field: &str, coerce_numbers: bool) -> String {
+    if coerce_numbers && is_json_number(field) {
+        field.to_string()
+    } else {
+        json_string(field)
+    }
+}
+
+/// Quote and escape `value` as a JSON string
+fn json_string(value: &str) -> String {
+    let mut quoted = String::with_capacity(value.len() + 2);
+    quoted.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            '\t' => quoted.push_str("\\t"),
+            c if (c as u32) < 0x20 => {
+                let _ = write!(quoted, "\\u{:04x}", c as u32);
+            }
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+/// True if `value` matches the JSON number grammar
+fn is_json_number(value: &str) -> bool {
+    let bytes = value.as_bytes();
+    let mut i = 0;
+    let digits = |i: &mut usize| {
+        let start = *i;
+        while *i < bytes.len() && bytes[*i].is_ascii_digit() {
+            *i += 1;
+        }
+        *i - start
+    };
+
+    if bytes.first() == Some(&b'-') {
+        i += 1;
+    }
+    match digits(&mut i) {
+        0 => return false,
+        n if n > 1 && bytes[i - n] == b'0' => return false,
+        _ => {}
+    }
+    if bytes.get(i) == Some(&b'.') {
+        i += 1;
+        if digits(&mut i) == 0 {
+            return false;
+        }
+    }
+    if matches!(bytes.get(i), Some(b'e' | b'E')) {
+        i += 1;
+        if matches!(bytes.get(i), Some(b'+' | b'-')) {
+            i += 1;
+        }
+        if digits(&mut i) == 0 {
+            return false;
+        }
+    }
+    i == bytes.len()
+}
+
+// ============================================
+// Unit Tests
+// ============================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rows_to_json_objects_and_arrays() {
+        let rows = vec![
+            StringRecord::from(vec!["ID", "Name", "Name", "Amount"]),
+            StringRecord::from(vec!["007", "Tbilisi \"Waters\"", "Gori\nBranch", "-12.50"]),
+        ];
+
+        assert_eq!(
+            rows_to_json(&rows, HeaderMode::HasHeaders, false),
+            "[\n{\"ID\":\"007\",\"Name\":\"Tbilisi \\\"Waters\\\"\",\"Name_2\":\"Gori Branch\",\"Amount\":\"-12.50\"}\n]\n"
+        );
+        assert_eq!(
+            rows_to_json(&rows, HeaderMode::HasHeaders, true),
+            "[\n{\"ID\":\"007\",\"Name\":\"Tbilisi \\\"Waters\\\"\",\"Name_2\":\"Gori Branch\",\"Amount\":-12.50}\n]\n"
+        );
+        assert_eq!(
+            rows_to_json(&rows[1..], HeaderMode::NoHeaders, true),
+            "[\n[\"007\",\"Tbilisi \\\"Waters\\\"\",\"Gori Branch\",-12.50]\n]\n"
+        );
+        assert_eq!(rows_to_json(&rows[..1], HeaderMode::HasHeaders, false), "[]\n");
+        assert_eq!(unique_keys(["a", "a", "a_2"].iter().map(|s| s.to_string())), ["a", "a_2", "a_2_2"]);
+    }
+}
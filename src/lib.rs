@@ -20,7 +20,7 @@
 //! # Example
 //!
 //! ```no_run
-//! use fixerr::{reconstruct_records, write_output_csv, HeaderMode, Delimiter, Stats};
+//! use fixerr::{reconstruct_records, write_output_csv, HeaderMode, Delimiter, NormalizeMode, Stats};
 //!
 //! let mut stats = Stats::default();
 //! let records = reconstruct_records(
@@ -30,17 +30,140 @@
 //!     &mut stats
 //! ).unwrap();
 //!
-//! write_output_csv("output.csv", &records, Delimiter::Comma).unwrap();
+//! write_output_csv("output.csv", &records, Delimiter::Comma, NormalizeMode::Collapse).unwrap();
 //! println!("Processed {} rows, fixed {} rows", stats.total_rows, stats.fixed_rows);
 //! ```
+mod aggregate;
+mod audit;
+mod batch;
+mod collapse;
+mod compare;
+mod concat;
+mod config;
+#[cfg(feature = "polars")]
+mod dataframe;
+mod detect;
+mod encoding;
 mod engine;
+mod error;
+mod estimate;
+mod graph;
+mod history;
+mod infer;
+mod json;
+mod mode;
+mod normalizer;
+mod numeric;
+mod options;
+mod output;
+mod pipeline;
+mod profile;
+mod progress;
+mod reconcile;
+mod redact;
+mod retry;
+mod reverse;
+mod sample;
+mod scrub;
+mod sort;
+mod stitch;
+mod stream;
+mod validate;
+mod warning;
 
 // Re-export public API
+pub use aggregate::group_rows;
+pub use audit::{collect_audit_entries, write_audit_log, AuditEntry, ChangeConfidence, AUDIT_LOG_HEADER};
+pub use batch::{process_directory, process_directory_with_policy, DirectoryReport, ErrorPolicy, FileReport};
+#[cfg(feature = "parallel")]
+pub use batch::{process_directory_parallel, process_directory_parallel_with_policy};
+pub use compare::{compare_runs, RunComparison, RunCounts};
+pub use concat::reconstruct_concatenated;
+pub use config::{ReconstructConfig, RunawayFieldPolicy, SectionOutput, TypeHintPolicy};
+#[cfg(feature = "polars")]
+pub use dataframe::{reconstruct_to_dataframe, reconstruct_to_dataframe_with_schema};
+pub use detect::{
+    detect_delimiter,
+    detect_quote,
+    vote_column_count,
+    ColumnCountVote,
+    COLUMN_VOTE_SAMPLE_ROWS,
+    DELIMITER_SAMPLE_LINES,
+    QUOTE_SAMPLE_BYTES,
+};
+pub use encoding::{
+    encoding_for_label,
+    DecodingReader,
+    InputEncoding,
+    TranscodingWriter,
+    UnmappableAction,
+    AUTO_FALLBACK_ENCODING,
+    ENCODING_SNIFF_BYTES,
+};
 pub use engine::{
     reconstruct_records,
+    reconstruct_records_with_config,
+    reconstruct_records_with_columns,
+    reconstruct_records_with_discarded,
+    reconstruct_records_from_reader,
+    reconstruct_rows_with_config,
+    reconstruct_rows_from_reader,
+    reconstruct_from_lines,
+    open_input,
     write_output_csv,
+    write_output_csv_to_writer,
+    write_discarded_csv,
+    convert_delimiter,
     build_csv_reader,
     HeaderMode,
     Delimiter,
+    OverflowStrategy,
+    ColumnCountStrategy,
+    LineEnding,
+    LineTerminator,
+    NormalizeMode,
+    QuoteChar,
+    QuoteStyle,
+    ReconstructedRow,
     Stats,
+    StatsSummary,
+};
+pub use error::FixerrError;
+pub use estimate::{estimate_cost, CostEstimate, CostRange, ESTIMATE_SAMPLE_BYTES};
+pub use graph::{stitch_graph, stitch_graph_json, write_stitch_graph, StitchAction, StitchGroup};
+pub use history::HISTORY_DIVERGENCE_THRESHOLD;
+pub use infer::{infer_column_types, typed_header, ColumnType};
+pub use json::{rows_to_json, write_output_json};
+pub use mode::{reconstruct_file, ProcessingMode};
+pub use normalizer::{ColumnNormalizer, NormalizerFn, NormalizerRegistry};
+pub use numeric::canonicalize_number;
+pub use options::ReconstructOptions;
+pub use output::{
+    escape_special_chars,
+    part_file_path,
+    suffixed_path,
+    verify_output_strict,
+    write_output_csv_with_config,
+    write_output_rows_with_config,
+};
+pub use pipeline::reconstruct_pipeline;
+pub use profile::{profile_cardinality, CardinalityReport, CARDINALITY_CAP};
+pub use progress::{ProgressEvent, ProgressFn, ProgressHook, PROGRESS_INTERVAL_ROWS};
+pub use reconcile::ReconciliationReport;
+pub use redact::{RedactMode, REDACTION_MASK};
+pub use retry::{is_retryable, RetryPolicy, RetryingReader, RetryingWriter};
+pub use sample::reservoir_sample;
+pub use sort::{sort_rows, SortComparison, SortOrder};
+pub use stream::{RecordReconstructor, Reconstructor};
+pub use validate::{
+    check_sequence,
+    check_type_hints,
+    default_type_hint_suffixes,
+    validate_output,
+    validate_written_output,
+    RecordAction,
+    RecordValidator,
+    RecordValidatorFn,
+    ValidationError,
 };
+pub use warning::Warning;
@@ -3,13 +3,25 @@
 //! 
 //! Main entry point with interactive menu system.
 
-use fixerr::{reconstruct_records, write_output_csv, HeaderMode, Delimiter, Stats};
+use fixerr::{
+    compare_runs, detect_delimiter, open_input, process_directory, profile_cardinality, reconstruct_records_with_config, validate_written_output, vote_column_count, write_output_csv,
+    ColumnCountStrategy, HeaderMode, Delimiter, NormalizeMode, OverflowStrategy, ProgressHook, ReconstructConfig,
+    Stats, Warning,
+};
 use std::error::Error;
 use std::path::Path;
 use std::time::Instant;
 
+mod cli;
+mod repl;
 mod ui;
 
+/// Share of sampled rows that must agree on a column count to use it unasked
+const MIN_COLUMN_VOTE_CONFIDENCE: f64 = 0.5;
+
+/// Sample values listed per column in the cardinality profile
+const PROFILE_SAMPLE_VALUES: usize = 4;
+
 /// Application configuration
 #[derive(Debug, Clone)]
 pub struct Config {
@@ -17,6 +29,8 @@ pub struct Config {
     pub header_mode: HeaderMode,
     pub input_file: String,
     pub output_file: String,
+    /// Column names used to spot a header row in a file processed as headerless
+    pub known_header_names: Vec<String>,
 }
 
 impl Default for Config {
@@ -26,27 +40,87 @@ impl Default for Config {
             header_mode: HeaderMode::HasHeaders,
             input_file: "data.csv".to_string(),
             output_file: "output.csv".to_string(),
+            known_header_names: Vec::new(),
         }
     }
 }
 
 fn main() -> Result<(), Box<dyn Error>> {
     let mut config = Config::default();
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    
+    // The REPL is a separate tool and bypasses the menus entirely
+    if args.iter().any(|arg| arg == "--repl") {
+        return repl::run(&config);
+    }
+    
+    // Any other arguments select the non-interactive mode
+    if !args.is_empty() {
+        let code = match cli::parse(args) {
+            Ok(cli::Command::Help) => {
+                println!("{}", cli::USAGE);
+                0
+            }
+            Ok(cli::Command::Run(run_args)) => match cli::run(&run_args) {
+                Ok(()) => 0,
+                Err(e) => {
+                    eprintln!("fixerr: {e}");
+                    1
+                }
+            },
+            Ok(cli::Command::Batch(batch_args)) => match cli::run_batch(&batch_args) {
+                Ok(()) => 0,
+                Err(e) => {
+                    eprintln!("fixerr: {e}");
+                    1
+                }
+            },
+            Err(e) => {
+                eprintln!("fixerr: {e}\n\n{}", cli::USAGE);
+                2
+            }
+        };
+        std::process::exit(code);
+    }
     
+    match run_menu(&mut config) {
+        // stdin was closed (e.g. launched without a TTY) - exit cleanly
+        Err(e) if ui::is_input_closed(e.as_ref()) => {
+            println!("\n\nInput closed. Exiting Fixerr.");
+            Ok(())
+        }
+        result => result,
+    }
+}
+
+/// Run the interactive main menu until the user exits
+fn run_menu(config: &mut Config) -> Result<(), Box<dyn Error>> {
     loop {
         ui::display_welcome();
         
-        let choice = ui::get_menu_choice(1, 3, "\nEnter your choice (1-3): ")?;
+        let choice = ui::get_menu_choice(1, 5, "\nEnter your choice (1-5): ")?;
         
         match choice {
             1 => {
-                if let Err(e) = process_csv(&config) {
+                if let Err(e) = process_csv(config) {
                     ui::show_error_message(&format!("Processing failed: {e}"));
                 }
                 ui::pause();
             }
-            2 => configure_settings(&mut config)?,
+            2 => configure_settings(config)?,
             3 => {
+                if let Err(e) = compare_configs(config) {
+                    ui::show_error_message(&format!("Comparison failed: {e}"));
+                }
+                ui::pause();
+            }
+            4 => {
+                if let Err(e) = process_csv_directory(config) {
+                    ui::show_error_message(&format!("Batch processing failed: {e}"));
+                }
+                ui::pause();
+            }
+            5 => {
                 ui::clear_screen();
                 println!("\n✨ Thank you for using Fixerr! Goodbye.\n");
                 break;
@@ -70,16 +144,29 @@ fn process_csv(config: &Config) -> Result<(), Box<dyn Error>> {
     let mut stats = Stats::default();
     let total_start = Instant::now();
     
+    let mut engine_config = build_engine_config(config)?;
+    let delimiter = engine_config.delimiter;
+    engine_config.progress = Some(ProgressHook::new(Box::new(ui::display_progress)));
+    
     // Phase 1: Reconstruct records
     println!("🔄 Phase 1: Analyzing and reconstructing records...");
     let process_start = Instant::now();
     
-    let records = reconstruct_records(
-        &config.input_file,
-        config.header_mode,
-        config.delimiter,
-        &mut stats,
-    )?;
+    let mut records = reconstruct_records_with_config(&config.input_file, &engine_config, &mut stats)?;
+    ui::clear_progress();
+    
+    // The first row looks like a header although the file is set as headerless
+    if stats.warnings.iter().any(|w| matches!(w, Warning::PossibleHeaderRow { .. })) {
+        ui::show_warning_message("The first row matches the known header names - the file may have headers.");
+        
+        if ui::get_confirmation("Re-process the file with headers?")? {
+            engine_config.header_mode = HeaderMode::HasHeaders;
+            engine_config.expected_columns = None;
+            stats = Stats::default();
+            records = reconstruct_records_with_config(&config.input_file, &engine_config, &mut stats)?;
+            ui::clear_progress();
+        }
+    }
     
     ui::print_elapsed("   Processing Time", process_start);
     
@@ -87,10 +174,16 @@ fn process_csv(config: &Config) -> Result<(), Box<dyn Error>> {
     println!("\n💾 Phase 2: Writing cleaned CSV...");
     let write_start = Instant::now();
     
-    write_output_csv(&config.output_file, &records, config.delimiter)?;
+    write_output_csv(&config.output_file, &records, delimiter, NormalizeMode::Collapse)?;
     
     ui::print_elapsed("   Writing Time", write_start);
     
+    // Optional check that the written file parses back to the same records
+    if ui::get_confirmation("Validate the written file?")? {
+        let expected_columns = records.first().map_or(0, |record| record.len());
+        ui::display_validation(&validate_written_output(&config.output_file, delimiter, &records, expected_columns));
+    }
+    
     // Total time
     println!();
     ui::print_elapsed("   Total Time", total_start);
@@ -98,30 +191,122 @@ fn process_csv(config: &Config) -> Result<(), Box<dyn Error>> {
     // Summary statistics
     ui::display_summary(&stats, records.len(), &config.output_file);
     
+    if ui::get_confirmation("Show distinct values per column?")? {
+        let (header, data) = match (engine_config.output_has_header(), records.split_first()) {
+            (true, Some((header, data))) => (Some(header), data),
+            _ => (None, records.as_slice()),
+        };
+        ui::display_cardinality(&profile_cardinality(data, header, PROFILE_SAMPLE_VALUES));
+    }
+    
     Ok(())
 }
 
+/// Process every CSV file of a directory with the current settings
+fn process_csv_directory(config: &Config) -> Result<(), Box<dyn Error>> {
+    ui::display_directory_screen();
+    let input_dir = ui::get_string_input("Input directory: ")?;
+    if !Path::new(&input_dir).is_dir() {
+        return Err(format!("Input directory '{input_dir}' not found").into());
+    }
+    let output_dir = ui::get_string_input("Output directory: ")?;
+    if output_dir.is_empty() {
+        return Err("Output directory cannot be empty".into());
+    }
+    
+    println!("\n🔄 Processing directory...");
+    let start = Instant::now();
+    let report = process_directory(&input_dir, &output_dir, config.header_mode, config.delimiter)?;
+    ui::print_elapsed("   Processing Time", start);
+    
+    ui::display_directory_summary(&report, &output_dir);
+    
+    Ok(())
+}
+
+/// Compare the current settings against one alternative on the input file
+fn compare_configs(config: &Config) -> Result<(), Box<dyn Error>> {
+    if !Path::new(&config.input_file).exists() {
+        return Err(format!("Input file '{}' not found", config.input_file).into());
+    }
+    
+    ui::display_compare_menu();
+    let choice = ui::get_menu_choice(1, 4, "Select alternative (1-4): ")?;
+    
+    let current = build_engine_config(config)?;
+    let mut alternative = current.clone();
+    match choice {
+        1 => alternative.overflow_strategy = OverflowStrategy::SplitAtExpected,
+        2 => alternative.overflow_strategy = OverflowStrategy::SmartMerge,
+        3 => alternative.column_count_strategy = ColumnCountStrategy::MajorityVote,
+        4 => alternative.reverse = true,
+        _ => unreachable!(), // Validation prevents this
+    }
+    
+    let comparison = compare_runs(&current, &alternative, &config.input_file)?;
+    ui::display_comparison(&comparison);
+    
+    Ok(())
+}
+
+/// Engine configuration for the current settings, with the delimiter resolved
+fn build_engine_config(config: &Config) -> Result<ReconstructConfig, Box<dyn Error>> {
+    // Detect up front so the output uses the input's delimiter as well
+    let delimiter = match config.delimiter {
+        Delimiter::Auto => {
+            let detected = detect_delimiter(open_input(&config.input_file, &ReconstructConfig::default())?)?;
+            println!("🔍 Detected delimiter: {detected:?}\n");
+            detected
+        }
+        delimiter => delimiter,
+    };
+    
+    let mut engine_config = ReconstructConfig::new(config.header_mode, delimiter);
+    engine_config.known_header_names = config.known_header_names.clone();
+    if matches!(config.header_mode, HeaderMode::NoHeaders) {
+        engine_config.expected_columns = Some(infer_column_count(config, delimiter)?);
+    }
+    Ok(engine_config)
+}
+
+/// Column count of a headerless input, asking only when the vote is unclear
+fn infer_column_count(config: &Config, delimiter: Delimiter) -> Result<usize, Box<dyn Error>> {
+    let vote = vote_column_count(open_input(&config.input_file, &ReconstructConfig::default())?, HeaderMode::NoHeaders, delimiter)?;
+    let percent = vote.confidence * 100.0;
+    if vote.confidence >= MIN_COLUMN_VOTE_CONFIDENCE {
+        println!("🔍 Detected {} columns ({percent:.0}% of sampled rows)\n", vote.columns);
+        return Ok(vote.columns);
+    }
+    
+    ui::show_warning_message(&format!(
+        "Column count is ambiguous: {} columns matched only {percent:.0}% of sampled rows.",
+        vote.columns
+    ));
+    Ok(ui::get_column_count()?)
+}
+
 /// Configure application settings with submenu
 fn configure_settings(config: &mut Config) -> Result<(), Box<dyn Error>> {
     loop {
         ui::display_settings_menu(config);
         
-        let choice = ui::get_menu_choice(1, 6, "\nEnter your choice (1-6): ")?;
+        let choice = ui::get_menu_choice(1, 7, "\nEnter your choice (1-7): ")?;
         
         match choice {
             1 => change_delimiter(config)?,
             2 => change_header_mode(config)?,
             3 => change_input_file(config)?,
             4 => change_output_file(config)?,
-            5 => {
+            5 => change_known_header_names(config)?,
+            6 => {
                 *config = Config::default();
                 ui::show_success_message("Settings reset to defaults!");
             }
-            6 => break, // Back to main menu
+            7 => break, // Back to main menu
             _ => unreachable!(), // Validation prevents this
         }
         
-        if choice != 6 {
+        if choice != 7 {
             ui::pause();
         }
     }
@@ -134,13 +319,24 @@ fn change_delimiter(config: &mut Config) -> Result<(), Box<dyn Error>> {
     let current = format!("{:?}", config.delimiter);
     ui::display_delimiter_menu(&current);
     
-    let choice = ui::get_menu_choice(1, 4, "Select delimiter (1-4): ")?;
+    let choice = ui::get_menu_choice(1, 6, "Select delimiter (1-6): ")?;
     
     config.delimiter = match choice {
         1 => Delimiter::Comma,
         2 => Delimiter::Semicolon,
         3 => Delimiter::Tab,
         4 => Delimiter::Pipe,
+        5 => Delimiter::Auto,
+        6 => {
+            let input = ui::get_string_input("Enter the delimiter character or byte (e.g. ^ or 0x1F): ")?;
+            match input.parse() {
+                Ok(delimiter) => delimiter,
+                Err(e) => {
+                    ui::show_error_message(&format!("Delimiter not changed: {e}"));
+                    return Ok(());
+                }
+            }
+        }
         _ => unreachable!(), // Validation prevents this
     };
     
@@ -224,3 +420,24 @@ fn change_output_file(config: &mut Config) -> Result<(), Box<dyn Error>> {
     
     Ok(())
 }
+
+/// Change the list of known header names
+fn change_known_header_names(config: &mut Config) -> Result<(), Box<dyn Error>> {
+    ui::display_known_headers_screen(&config.known_header_names);
+    
+    let input = ui::get_string_input("Enter comma-separated header names (or press Enter to clear): ")?;
+    
+    config.known_header_names = input
+        .split(',')
+        .map(|name| name.trim().to_string())
+        .filter(|name| !name.is_empty())
+        .collect();
+    
+    if config.known_header_names.is_empty() {
+        ui::show_success_message("Known header names cleared.");
+    } else {
+        ui::show_success_message(&format!("Known header names set to: {}", config.known_header_names.join(", ")));
+    }
+    
+    Ok(())
+}
@@ -0,0 +1,252 @@
+// src/mode.rs
+//! Automatic choice between in-memory and streamed processing
+//!
+//! The in-memory path supports every option but holds the whole file; the
+//! streamed path writes records as they complete. [`reconstruct_file`] picks
+//! one by input size, so callers get a single entry point that stays within
+//! memory on very large files.
+
+use crate::config::ReconstructConfig;
+use crate::engine::{is_gzip_input, open_input, reconstruct_rows_with_config, ColumnCountStrategy, Stats};
+use crate::error::FixerrError;
+use crate::output::{write_output_rows_with_config, write_rejects, write_row_stream, write_sample_stream};
+use crate::stream::Reconstructor;
+use std::fs;
+
+/// How a file was processed by [`reconstruct_file`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ProcessingMode {
+    /// Reconstructed in memory, with every option applied
+    InMemory,
+    /// Streamed, each record written as soon as it was complete
+    Streaming,
+}
+
+/// Reconstruct `input_path` and write the result to `output_path`
+///
+/// Inputs larger than `config.auto_mode_threshold` bytes are streamed, as
+/// are inputs whose size is unknown in advance, such as gzip files. Smaller
+/// inputs, and every input when no threshold is set, are reconstructed in
+/// memory and written with [`write_output_rows_with_config`].
+///
+/// Streaming writes a single file and skips options that need the whole
+/// input or every row at once (sorting, grouping, part files, sampling, ...);
+/// when any of those is set the input is reconstructed in memory whatever
+/// its size. With `sample_only`, a streamed input is sampled as it is read,
+/// holding no more than the sample. The chosen mode is recorded in
+/// `stats.processing_mode`.
+///
+/// Returns the paths of all record files written.
+pub fn reconstruct_file(
+    input_path: &str,
+    output_path: &str,
+    config: &ReconstructConfig,
+    stats: &mut Stats,
+) -> Result<Vec<String>, FixerrError> {
+    let mode = match config.auto_mode_threshold {
+        None => ProcessingMode::InMemory,
+        Some(_) if unsupported_when_streaming(config).is_some() => ProcessingMode::InMemory,
+        Some(threshold) => {
+            let size = if is_gzip_input(input_path, config) {
+                None
+            } else {
+                fs::metadata(input_path).ok().map(|m| m.len())
+            };
+            match size {
+                Some(size) if size <= threshold => ProcessingMode::InMemory,
+                _ => ProcessingMode::Streaming,
+            }
+        }
+    };
+    stats.processing_mode = Some(mode);
+
+    match mode {
+        ProcessingMode::InMemory => {
+            let rows = reconstruct_rows_with_config(input_path, config, stats)?;
+            write_output_rows_with_config(output_path, &rows, config, stats)
+        }
+        ProcessingMode::Streaming => {
+            let rows = Reconstructor::new(open_input(input_path, config)?, config)?;
+            let streamed = rows.stats();
+            let written = if sampling_only(config) {
+                write_sample_stream(rows, config, stats)
+            } else {
+                write_row_stream(output_path, rows, config, stats).map(|()| vec![output_path.to_string()])
+            };
+            stats.absorb(std::mem::take(&mut *streamed.borrow_mut()));
+            let written = written?;
+            if let Some(rejects_path) = &config.rejects_path {
+                write_rejects(rejects_path, config, stats)?;
+            }
+            Ok(written)
+        }
+    }
+}
+
+// ============================================
+// Private Helper Functions
+// ============================================
+
+/// First option set in `config` that the streamed path cannot honour
+fn unsupported_when_streaming(config: &ReconstructConfig) -> Option<&'static str> {
+    let options = [
+        // Passes over the whole input, and sidecar files built from every row
+        ("reverse", config.reverse),
+        ("collapse_consecutive_delimiters", config.collapse_consecutive_delimiters),
+        ("per_row_delimiter_detection", config.per_row_delimiter_detection),
+        ("column_count_strategy", matches!(config.column_count_strategy, ColumnCountStrategy::Quantile(_))),
+        ("generate_typed_headers", config.generate_typed_headers),
+        ("header_type_hints", config.header_type_hints),
+        ("sequence_column", config.sequence_column.is_some()),
+        ("group_by", config.group_by.is_some()),
+        ("sort_by", config.sort_by.is_some()),
+        ("history_file", config.history_file.is_some()),
+        ("audit_log_path", config.audit_log_path.is_some()),
+        ("stitch_graph_path", config.stitch_graph_path.is_some()),
+    ];
+    // Layouts of the full output, which a sample-only run does not write at all
+    let layouts = [
+        ("max_rows_per_file", config.max_rows_per_file.map_or(false, |limit| limit > 0)),
+        ("split_changed", config.split_changed),
+        ("preserve_quoting", config.preserve_quoting),
+        ("sample_output", config.sample_output.is_some()),
+        ("multi_section", config.multi_section),
+        ("column_delimiters", config.column_delimiters.is_some()),
+    ];
+    let layouts: &[(&'static str, bool)] = if sampling_only(config) { &[] } else { &layouts };
+    options.iter().chain(layouts).find(|(_, set)| *set).map(|(option, _)| *option)
+}
+
+/// Whether only the `sample_output` sample is written
+fn sampling_only(config: &ReconstructConfig) -> bool {
+    config.sample_only && config.sample_output.is_some()
+}
+
+// ============================================
+// Unit Tests
+// ============================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+
+    #[test]
+    fn test_auto_mode_threshold_picks_mode_by_size() {
+        let input = "test_auto_mode_input.csv";
+        let output = "test_auto_mode_output.csv";
+        let content = "ID,Name,Amount\n1,Tbilisi\nWaters,10\n2,Gori,20\n";
+        fs::write(input, content).unwrap();
+        let run = |threshold: u64| {
+            let config = ReconstructConfig { auto_mode_threshold: Some(threshold), ..ReconstructConfig::default() };
+            let mut stats = Stats::default();
+            reconstruct_file(input, output, &config, &mut stats).unwrap();
+            (stats, fs::read_to_string(output).unwrap())
+        };
+
+        let (below, in_memory) = run(content.len() as u64);
+        let (above, streamed) = run(content.len() as u64 - 1);
+        fs::remove_file(input).unwrap();
+        fs::remove_file(output).unwrap();
+
+        assert_eq!(below.processing_mode, Some(ProcessingMode::InMemory));
+        assert_eq!(above.processing_mode, Some(ProcessingMode::Streaming));
+        assert_eq!(in_memory, "ID,Name,Amount\n1,Tbilisi Waters,10\n2,Gori,20\n");
+        assert_eq!(streamed, in_memory);
+        assert_eq!((above.total_rows, above.fixed_rows), (below.total_rows, below.fixed_rows));
+        assert!(above.reconcile().is_balanced());
+    }
+
+    #[test]
+    fn test_streaming_honours_or_falls_back_on_every_option() {
+        use crate::engine::OverflowStrategy;
+        use crate::sort::SortOrder;
+
+        let input = "test_auto_mode_options_input.csv";
+        let output = "test_auto_mode_options_output.csv";
+        let rejects = "test_auto_mode_options_rejects.csv";
+        let content = "ID,Name,Amount\n3,Tbilisi\nWaters,10\n2,Gori,20,1,Mestia,40\n4,x,y,z,w\n5,\"Sairme\n";
+        fs::write(input, content).unwrap();
+        let base = ReconstructConfig {
+            overflow_strategy: OverflowStrategy::SplitAtExpected,
+            repair_unterminated_quote: true,
+            rejects_path: Some(rejects.to_string()),
+            ..ReconstructConfig::default()
+        };
+        let run = |config: &ReconstructConfig, threshold: Option<u64>| {
+            let config = ReconstructConfig { auto_mode_threshold: threshold, ..config.clone() };
+            let mut stats = Stats::default();
+            reconstruct_file(input, output, &config, &mut stats).unwrap();
+            (stats, fs::read_to_string(output).unwrap(), fs::read_to_string(rejects).unwrap())
+        };
+
+        let (in_memory, expected, expected_rejects) = run(&base, None);
+        let (streamed, written, written_rejects) = run(&base, Some(1));
+        let sorted = ReconstructConfig { sort_by: Some((0, SortOrder::Ascending)), ..base.clone() };
+        let (fallback, sorted_output, _) = run(&sorted, Some(1));
+        let (_, expected_sorted, _) = run(&sorted, None);
+        for path in [input, output, rejects] {
+            fs::remove_file(path).unwrap();
+        }
+
+        assert_eq!(streamed.processing_mode, Some(ProcessingMode::Streaming));
+        assert_eq!(expected, "ID,Name,Amount\n3,Tbilisi Waters,10\n2,Gori,20\n1,Mestia,40\n5,Sairme,\n");
+        assert_eq!(written, expected);
+        assert_eq!(written_rejects, expected_rejects);
+        assert_eq!(expected_rejects, "4,x,y,z,w\n");
+        assert_eq!(streamed.warnings, in_memory.warnings);
+        assert_eq!((streamed.fixed_rows, streamed.removed_rows), (in_memory.fixed_rows, in_memory.removed_rows));
+
+        assert_eq!(fallback.processing_mode, Some(ProcessingMode::InMemory));
+        assert_eq!(sorted_output, expected_sorted);
+    }
+
+    #[test]
+    fn test_sample_output_writes_exactly_n_rows_in_either_mode() {
+        let input = "test_auto_mode_sample_input.csv";
+        let output = "test_auto_mode_sample_output.csv";
+        let sample = "test_auto_mode_sample.csv";
+        let content: String = std::iter::once("ID,Name\n".to_string())
+            .chain((0..50).map(|i| if i % 10 == 0 { format!("{i},Split\nName\n") } else { format!("{i},x\n") }))
+            .collect();
+        fs::write(input, &content).unwrap();
+        let run = |threshold: Option<u64>, sample_only: bool| {
+            let config = ReconstructConfig {
+                sample_output: Some((sample.to_string(), 7)),
+                sample_seed: Some(42),
+                sample_only,
+                auto_mode_threshold: threshold,
+                ..ReconstructConfig::default()
+            };
+            let mut stats = Stats::default();
+            let written = reconstruct_file(input, output, &config, &mut stats).unwrap();
+            (stats.processing_mode, written, fs::read_to_string(sample).unwrap())
+        };
+
+        let (full_mode, full_written, full_sample) = run(None, false);
+        let full_output = fs::read_to_string(output).unwrap();
+        fs::remove_file(output).unwrap();
+        let (streamed_mode, streamed_written, streamed_sample) = run(Some(1), true);
+        let output_written = Path::new(output).exists();
+        for path in [input, sample] {
+            fs::remove_file(path).unwrap();
+        }
+
+        assert_eq!(full_mode, Some(ProcessingMode::InMemory));
+        assert_eq!(full_written, [output]);
+        assert_eq!(full_output.lines().count(), 51);
+        assert_eq!(streamed_mode, Some(ProcessingMode::Streaming));
+        assert_eq!(streamed_written, [sample]);
+        assert!(!output_written, "sample_only must not write the full output");
+
+        for sampled in [&full_sample, &streamed_sample] {
+            let lines: Vec<&str> = sampled.lines().collect();
+            assert_eq!(lines.len(), 8, "header plus 7 rows in {sampled:?}");
+            assert_eq!(lines[0], "ID,Name");
+            assert!(lines[1..].iter().all(|line| full_output.lines().skip(1).any(|row| row == *line)));
+        }
+        // The same seed draws the same rows whichever way the input was read
+        assert_eq!(streamed_sample, full_sample);
+    }
+}
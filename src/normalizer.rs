@@ -0,0 +1,129 @@
+// src/normalizer.rs
+//! Named field normalizers
+//!
+//! Reusable value transforms (casing, digit extraction, ...) registered under
+//! a name, so configuration can refer to them per column without holding
+//! closures itself.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Arc;
+
+/// Signature of a field normalizer
+pub type NormalizerFn = dyn Fn(&str) -> String + Send + Sync;
+
+/// A normalizer applied to one column
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ColumnNormalizer {
+    /// 0-based column index
+    pub column: usize,
+    /// Name of a normalizer in the [`NormalizerRegistry`]
+    pub normalizer: String,
+}
+
+/// Normalizers available by name
+///
+/// Starts with the built-ins `title_case`, `upper`, `lower` and
+/// `digits_only`. Cloning is cheap; the functions are shared.
+#[derive(Clone)]
+pub struct NormalizerRegistry {
+    normalizers: HashMap<String, Arc<NormalizerFn>>,
+}
+
+impl NormalizerRegistry {
+    /// An empty registry without the built-ins
+    pub fn empty() -> Self {
+        Self {
+            normalizers: HashMap::new(),
+        }
+    }
+
+    /// Register `f` under `name`, replacing any normalizer of that name
+    pub fn register_normalizer(&mut self, name: &str, f: Box<NormalizerFn>) {
+        self.normalizers.insert(name.to_string(), Arc::from(f));
+    }
+
+    /// Look up a normalizer by name
+    pub fn get(&self, name: &str) -> Option<&NormalizerFn> {
+        self.normalizers.get(name).map(|f| f.as_ref())
+    }
+
+    /// Registered names in alphabetical order
+    pub fn names(&self) -> Vec<&str> {
+        let mut names: Vec<&str> = self.normalizers.keys().map(String::as_str).collect();
+        names.sort_unstable();
+        names
+    }
+}
+
+impl Default for NormalizerRegistry {
+    fn default() -> Self {
+        let mut registry = Self::empty();
+        registry.register_normalizer("title_case", Box::new(title_case));
+        registry.register_normalizer("upper", Box::new(|s| s.to_uppercase()));
+        registry.register_normalizer("lower", Box::new(|s| s.to_lowercase()));
+        registry.register_normalizer("digits_only", Box::new(|s| s.chars().filter(char::is_ascii_digit).collect()));
+        registry
+    }
+}
+
+impl fmt::Debug for NormalizerRegistry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(self.names()).finish()
+    }
+}
+
+// ============================================
+// Private Helper Functions
+// ============================================
+
+/// Capitalize the first letter of every word and lowercase the rest
+fn title_case(value: &str) -> String {
+    let mut result = String::with_capacity(value.len());
+    let mut word_start = true;
+    for c in value.chars() {
+        if c.is_whitespace() {
+            word_start = true;
+            result.push(c);
+        } else if word_start {
+            word_start = false;
+            result.extend(c.to_uppercase());
+        } else {
+            result.extend(c.to_lowercase());
+        }
+    }
+    result
+}
+
+// ============================================
+// Unit Tests
+// ============================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::ReconstructConfig;
+    use crate::engine::{reconstruct_rows_from_reader, Stats};
+
+    #[test]
+    fn test_custom_normalizer_applied_via_config() {
+        let mut config = ReconstructConfig::default();
+        config.normalizers.register_normalizer(
+            "phone",
+            Box::new(|s| s.chars().filter(char::is_ascii_digit).collect::<String>().replacen("995", "+995 ", 1)),
+        );
+        config.column_normalizers = vec![
+            ColumnNormalizer { column: 1, normalizer: "title_case".to_string() },
+            ColumnNormalizer { column: 2, normalizer: "phone".to_string() },
+        ];
+
+        let content = "ID,Name,Phone\n1,tbilisi WATERS,(995) 322-000\n";
+        let mut stats = Stats::default();
+        let rows = reconstruct_rows_from_reader(content.as_bytes(), &config, &mut stats).unwrap();
+        assert_eq!(rows[1].record.iter().collect::<Vec<_>>(), vec!["1", "Tbilisi Waters", "+995 322000"]);
+        assert_eq!(&rows[0].record[1], "Name");
+
+        config.column_normalizers[0].normalizer = "missing".to_string();
+        assert!(reconstruct_rows_from_reader(content.as_bytes(), &config, &mut stats).is_err());
+    }
+}
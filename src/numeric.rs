@@ -0,0 +1,87 @@
+// src/numeric.rs
+//! Locale-agnostic number canonicalization
+//!
+//! European exports write `1.234,56` where others write `1,234.56`, and some
+//! files mix both. Values are rewritten to the canonical `1234.56`.
+
+/// Canonicalize a number written with either decimal convention
+///
+/// When both `.` and `,` appear, the last one is the decimal separator. A
+/// single separator followed by exactly three digits is treated as grouping,
+/// any other single separator as the decimal point, and a separator repeated
+/// several times as grouping. Returns `None` for values that are not numbers
+/// under these rules; canonical values are returned unchanged.
+pub fn canonicalize_number(value: &str) -> Option<String> {
+    let compact: String = value.chars().filter(|c| !c.is_whitespace()).collect();
+    let (sign, digits) = match compact.strip_prefix('-') {
+        Some(rest) => ("-", rest),
+        None => ("", compact.strip_prefix('+').unwrap_or(&compact)),
+    };
+    if digits.is_empty() || !digits.chars().all(|c| c.is_ascii_digit() || c == '.' || c == ',') {
+        return None;
+    }
+
+    let decimal_at = match (digits.rfind('.'), digits.rfind(',')) {
+        (Some(dot), Some(comma)) => {
+            // The decimal separator may only appear once
+            let pos = dot.max(comma);
+            if digits.bytes().filter(|&b| b == digits.as_bytes()[pos]).count() > 1 {
+                return None;
+            }
+            Some(pos)
+        }
+        (Some(pos), None) | (None, Some(pos)) => {
+            let separator = digits.as_bytes()[pos];
+            let repeated = digits.bytes().filter(|&b| b == separator).count() > 1;
+            let grouping = repeated || digits.len() - pos - 1 == 3;
+            if grouping {
+                None
+            } else {
+                Some(pos)
+            }
+        }
+        (None, None) => None,
+    };
+
+    let (integer, fraction) = match decimal_at {
+        Some(pos) => (&digits[..pos], Some(&digits[pos + 1..])),
+        None => (digits, None),
+    };
+    let mut integer: String = integer.chars().filter(char::is_ascii_digit).collect();
+    let fraction = fraction.unwrap_or("");
+    if integer.is_empty() && fraction.is_empty() {
+        return None;
+    }
+    if integer.is_empty() {
+        integer.push('0');
+    }
+
+    if fraction.is_empty() {
+        Some(format!("{sign}{integer}"))
+    } else {
+        Some(format!("{sign}{integer}.{fraction}"))
+    }
+}
+
+// ============================================
+// Unit Tests
+// ============================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_canonicalize_number_formats() {
+        assert_eq!(canonicalize_number("1.234,56").as_deref(), Some("1234.56"));
+        assert_eq!(canonicalize_number("1,234.56").as_deref(), Some("1234.56"));
+        assert_eq!(canonicalize_number("1234").as_deref(), Some("1234"));
+        assert_eq!(canonicalize_number("1234.5").as_deref(), Some("1234.5"));
+        assert_eq!(canonicalize_number("1234,5").as_deref(), Some("1234.5"));
+        assert_eq!(canonicalize_number("1.234.567").as_deref(), Some("1234567"));
+        assert_eq!(canonicalize_number("-1 234,56").as_deref(), Some("-1234.56"));
+        assert_eq!(canonicalize_number("1234.56").as_deref(), Some("1234.56"));
+        assert_eq!(canonicalize_number("n/a"), None);
+        assert_eq!(canonicalize_number("1,2.3,4"), None);
+    }
+}
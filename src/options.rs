@@ -0,0 +1,143 @@
+// src/options.rs
+//! Builder for reconstruction options
+//!
+//! A chained alternative to filling in a [`ReconstructConfig`] by hand. New
+//! settings become new builder methods, so code using the builder keeps
+//! compiling as options are added.
+
+use crate::config::ReconstructConfig;
+use crate::encoding::InputEncoding;
+use crate::engine::{reconstruct_rows_from_reader, Delimiter, HeaderMode, NormalizeMode, QuoteChar, Stats};
+use crate::error::FixerrError;
+use crate::progress::{ProgressEvent, ProgressHook};
+use csv::StringRecord;
+use std::io::Read;
+
+/// Reconstruction options, set up by chaining methods on [`ReconstructOptions::new`]
+///
+/// ```
+/// use fixerr::{Delimiter, ReconstructOptions, Stats};
+///
+/// let mut stats = Stats::default();
+/// let records = ReconstructOptions::new()
+///     .delimiter(Delimiter::Semicolon)
+///     .reconstruct("ID;Name;Amount\n1;Tbilisi\nWaters;10\n".as_bytes(), &mut stats)
+///     .unwrap();
+/// assert_eq!(&records[1][1], "Tbilisi\nWaters");
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct ReconstructOptions {
+    config: ReconstructConfig,
+}
+
+impl ReconstructOptions {
+    /// Start from the default configuration
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Whether the first row is a header
+    pub fn header_mode(mut self, header_mode: HeaderMode) -> Self {
+        self.config.header_mode = header_mode;
+        self
+    }
+
+    /// Field separator of the input
+    pub fn delimiter(mut self, delimiter: Delimiter) -> Self {
+        self.config.delimiter = delimiter;
+        self
+    }
+
+    /// Character enclosing quoted fields in the input
+    pub fn quote(mut self, quote: QuoteChar) -> Self {
+        self.config.quote = quote;
+        self
+    }
+
+    /// Column count of headerless input
+    pub fn expected_columns(mut self, columns: usize) -> Self {
+        self.config.expected_columns = Some(columns);
+        self
+    }
+
+    /// Whitespace clean-up applied to each field on output
+    pub fn normalize_mode(mut self, normalize_mode: NormalizeMode) -> Self {
+        self.config.normalize_mode = normalize_mode;
+        self
+    }
+
+    /// Text inserted where the pieces of a split field are joined
+    pub fn stitch_separator(mut self, separator: impl Into<String>) -> Self {
+        self.config.stitch_separator = separator.into();
+        self
+    }
+
+    /// Character encoding of the input
+    pub fn input_encoding(mut self, encoding: InputEncoding) -> Self {
+        self.config.input_encoding = encoding;
+        self
+    }
+
+    /// Pad a record left incomplete at the end of the input instead of
+    /// discarding it
+    pub fn pad_trailing_row(mut self, pad: bool) -> Self {
+        self.config.pad_trailing_row = pad;
+        self
+    }
+
+    /// Call `f` every [`PROGRESS_INTERVAL_ROWS`](crate::PROGRESS_INTERVAL_ROWS)
+    /// physical rows
+    pub fn progress(mut self, f: impl FnMut(&ProgressEvent) + Send + 'static) -> Self {
+        self.config.progress = Some(ProgressHook::new(Box::new(f)));
+        self
+    }
+
+    /// Change any other setting of the underlying configuration
+    pub fn with_config(mut self, change: impl FnOnce(&mut ReconstructConfig)) -> Self {
+        change(&mut self.config);
+        self
+    }
+
+    /// The configuration built so far
+    pub fn config(&self) -> &ReconstructConfig {
+        &self.config
+    }
+
+    /// Finish building, for the functions taking a [`ReconstructConfig`]
+    pub fn build(self) -> ReconstructConfig {
+        self.config
+    }
+
+    /// Reconstruct the records of `reader` with these options
+    pub fn reconstruct<R: Read>(&self, reader: R, stats: &mut Stats) -> Result<Vec<StringRecord>, FixerrError> {
+        let rows = reconstruct_rows_from_reader(reader, &self.config, stats)?;
+        Ok(rows.into_iter().map(|row| row.record).collect())
+    }
+}
+
+// ============================================
+// Unit Tests
+// ============================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_options_builder_reconstructs_with_chained_settings() {
+        let options = ReconstructOptions::new()
+            .header_mode(HeaderMode::NoHeaders)
+            .delimiter(Delimiter::Pipe)
+            .expected_columns(3)
+            .stitch_separator(" ")
+            .with_config(|config| config.collect_discarded = true);
+
+        let mut stats = Stats::default();
+        let records = options.reconstruct("1|Tbilisi\nWaters|10\n2|Gori|20|x\n".as_bytes(), &mut stats).unwrap();
+
+        assert_eq!(records, vec![StringRecord::from(vec!["1", "Tbilisi Waters", "10"])]);
+        assert_eq!(stats.discarded_records.len(), 1);
+        let config = options.build();
+        assert_eq!((config.delimiter, config.expected_columns), (Delimiter::Pipe, Some(3)));
+    }
+}
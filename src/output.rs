@@ -0,0 +1,1127 @@
+// src/output.rs
+//! Output generation
+//!
+//! Writes reconstructed records according to a [`ReconstructConfig`],
+//! including optional splitting of large outputs into numbered part files.
+
+use crate::audit::write_audit_log;
+use crate::config::{ReconstructConfig, SectionOutput};
+use crate::encoding::TranscodingWriter;
+use crate::engine::{LineTerminator, NormalizeMode, ReconstructedRow, Stats};
+use crate::graph::write_stitch_graph;
+use crate::sample::{reservoir_sample, time_seed};
+use crate::error::FixerrError;
+use crate::retry::{RetryingReader, RetryingWriter};
+use csv::{ByteRecord, ReaderBuilder, StringRecord, Terminator, Writer, WriterBuilder};
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use std::fs::File;
+use std::io::{self, Read, Write};
+use std::path::Path;
+
+// ============================================
+// Public API Functions
+// ============================================
+
+/// Write cleaned CSV records using a full [`ReconstructConfig`]
+///
+/// When `max_rows_per_file` is set, the output is split into numbered part
+/// files (`output_part_001.csv`, ...) each holding at most that many data
+/// rows, with the header repeated at the top of every part. Output is
+/// transcoded to `output_encoding`; replaced characters are counted in
+/// `stats.unmappable_chars`.
+///
+/// Returns the paths of all files written.
+pub fn write_output_csv_with_config(
+    output_path: &str,
+    rows: &[StringRecord],
+    config: &ReconstructConfig,
+    stats: &mut Stats,
+) -> Result<Vec<String>, FixerrError> {
+    check_output_config(config)?;
+    let (header, data) = match (config.output_has_header(), rows.split_first()) {
+        (true, Some((header, data))) => (Some(header), data),
+        _ => (None, rows),
+    };
+
+    let limit = match config.max_rows_per_file {
+        Some(limit) if limit > 0 => limit,
+        _ => {
+            write_part(output_path, header, data, config, stats)?;
+            return Ok(vec![output_path.to_string()]);
+        }
+    };
+
+    // An empty input still produces one (header-only) part
+    let chunks: Vec<&[StringRecord]> = if data.is_empty() {
+        vec![data]
+    } else {
+        data.chunks(limit).collect()
+    };
+
+    let mut written = Vec::with_capacity(chunks.len());
+    for (index, chunk) in chunks.into_iter().enumerate() {
+        let part_path = part_file_path(output_path, index + 1);
+        write_part(&part_path, header, chunk, config, stats)?;
+        written.push(part_path);
+    }
+
+    Ok(written)
+}
+
+/// Write reconstructed rows, honouring options that need per-row metadata
+///
+/// With `multi_section` enabled, each section is written according to
+/// `section_output`. With `split_changed` enabled, records that required
+/// repair are written to `<output>_changed.csv` and untouched records to
+/// `<output>_unchanged.csv` (both with the header). Otherwise this behaves like
+/// [`write_output_csv_with_config`]. When `audit_log_path` is set, the
+/// value-change audit log is written as well, and likewise the stitching
+/// graph for `stitch_graph_path` and the raw discarded rows for
+/// `rejects_path`. With `sample_output` set, a random sample of data rows is
+/// written too, or instead of the full output with `sample_only`.
+///
+/// Returns the paths of all record files written (the audit log, graph and
+/// rejects excluded).
+pub fn write_output_rows_with_config(
+    output_path: &str,
+    rows: &[ReconstructedRow],
+    config: &ReconstructConfig,
+    stats: &mut Stats,
+) -> Result<Vec<String>, FixerrError> {
+    check_output_config(config)?;
+    if config.preserve_quoting {
+        let unsupported = [
+            ("max_rows_per_file", config.max_rows_per_file.map_or(false, |limit| limit > 0)),
+            ("split_changed", config.split_changed),
+            ("column_delimiters", config.column_delimiters.is_some()),
+            ("multi_section", config.multi_section),
+        ];
+        if let Some((option, _)) = unsupported.iter().find(|(_, set)| *set) {
+            return Err(FixerrError::InvalidConfig(format!("preserve_quoting cannot be combined with {option}")));
+        }
+    }
+    let records = |rows: &[ReconstructedRow]| -> Vec<StringRecord> {
+        rows.iter().map(|row| row.record.clone()).collect()
+    };
+
+    if let Some(audit_path) = &config.audit_log_path {
+        write_audit_log(audit_path, rows, config)?;
+    }
+
+    if let Some(graph_path) = &config.stitch_graph_path {
+        write_stitch_graph(graph_path, rows)?;
+    }
+
+    if let Some(rejects_path) = &config.rejects_path {
+        write_rejects(rejects_path, config, stats)?;
+    }
+
+    if let Some((sample_path, n)) = &config.sample_output {
+        let header = rows.first().filter(|row| row.header).map(|row| &row.record);
+        let data = rows.iter().filter(|row| !row.header).map(|row| row.record.clone());
+        let seed = config.sample_seed.unwrap_or_else(time_seed);
+        let sample: Vec<StringRecord> = reservoir_sample(data, *n, seed).into_iter().map(|(_, r)| r).collect();
+        write_part(sample_path, header, &sample, config, stats)?;
+        if config.sample_only {
+            return Ok(vec![sample_path.clone()]);
+        }
+    }
+
+    if config.multi_section {
+        return write_sections(output_path, rows, config, stats);
+    }
+
+    if config.preserve_quoting {
+        write_with_original_quoting(output_path, rows, config, stats)?;
+        if config.strict_verify_output {
+            verify_output_strict(output_path, config)?;
+        }
+        return Ok(vec![output_path.to_string()]);
+    }
+
+    if !config.split_changed {
+        return write_output_csv_with_config(output_path, &records(rows), config, stats);
+    }
+
+    let (header, data) = match (config.output_has_header(), rows.split_first()) {
+        (true, Some((header, data))) => (Some(header), data),
+        _ => (None, rows),
+    };
+
+    let mut changed: Vec<ReconstructedRow> = header.into_iter().cloned().collect();
+    let mut unchanged = changed.clone();
+    for row in data {
+        if row.fixed {
+            changed.push(row.clone());
+        } else {
+            unchanged.push(row.clone());
+        }
+    }
+
+    let mut written = write_output_csv_with_config(
+        &suffixed_path(output_path, "changed"),
+        &records(&changed),
+        config,
+        stats,
+    )?;
+    written.extend(write_output_csv_with_config(
+        &suffixed_path(output_path, "unchanged"),
+        &records(&unchanged),
+        config,
+        stats,
+    )?);
+
+    Ok(written)
+}
+
+/// Replace backslashes, tabs, carriage returns and newlines with their
+/// backslash-escape sequences (`\\`, `\t`, `\r`, `\n`)
+pub fn escape_special_chars(field: &str) -> String {
+    let mut escaped = String::with_capacity(field.len());
+    for c in field.chars() {
+        match c {
+            '\\' => escaped.push_str("\\\\"),
+            '\t' => escaped.push_str("\\t"),
+            '\r' => escaped.push_str("\\r"),
+            '\n' => escaped.push_str("\\n"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Re-read a written file strictly, requiring every record to be equally wide
+///
+/// Catches ragged output that a flexible reader would accept silently. Gzip
+/// output (`compress` or a `.gz` path) is decompressed first; records are
+/// read as bytes, so any `output_encoding` works.
+pub fn verify_output_strict(path: &str, config: &ReconstructConfig) -> Result<(), FixerrError> {
+    let file = config.retry.run_unwrapped(|| File::open(path))?;
+    let file = RetryingReader::new(file, config.retry);
+    let source: Box<dyn Read> = if config.compress || path.ends_with(".gz") {
+        Box::new(GzDecoder::new(file))
+    } else {
+        Box::new(file)
+    };
+
+    let mut reader = ReaderBuilder::new()
+        .has_headers(false)
+        .flexible(false)
+        .delimiter(config.delimiter.as_byte())
+        .quote(config.output_quote.output_byte())
+        .from_reader(source);
+    let mut record = ByteRecord::new();
+    loop {
+        match reader.read_byte_record(&mut record) {
+            Ok(true) => {}
+            Ok(false) => return Ok(()),
+            Err(e) => {
+                return match e.kind() {
+                    csv::ErrorKind::UnequalLengths { pos, len, .. } => Err(FixerrError::OutputNotStrictlyValid {
+                        line: pos.as_ref().map_or(0, |p| p.line()),
+                        width: *len as usize,
+                    }),
+                    _ => Err(e.into()),
+                };
+            }
+        }
+    }
+}
+
+/// Build the path of the `n`-th part file, e.g. `out.csv` -> `out_part_002.csv`
+pub fn part_file_path(output_path: &str, n: usize) -> String {
+    suffixed_path(output_path, &format!("part_{n:03}"))
+}
+
+/// Insert `_<suffix>` before the extension, e.g. `out.csv` -> `out_changed.csv`
+pub fn suffixed_path(output_path: &str, suffix: &str) -> String {
+    let path = Path::new(output_path);
+    let stem = path.file_stem().and_then(|s| s.to_str()).unwrap_or("output");
+    let file_name = match path.extension().and_then(|e| e.to_str()) {
+        Some(ext) => format!("{stem}_{suffix}.{ext}"),
+        None => format!("{stem}_{suffix}"),
+    };
+    path.with_file_name(file_name).to_string_lossy().into_owned()
+}
+
+// ============================================
+// Private Helper Functions
+// ============================================
+
+/// Reject output options that cannot be honoured together
+fn check_output_config(config: &ReconstructConfig) -> Result<(), FixerrError> {
+    if config.escape_special_chars && config.normalize_mode == NormalizeMode::PreserveNewlines {
+        // Escaping writes line breaks as `\n`, so there are none left to preserve
+        return Err(FixerrError::InvalidConfig(
+            "escape_special_chars cannot be combined with NormalizeMode::PreserveNewlines".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// Write rows quoting the fields that were quoted in the input
+///
+/// Records are assembled by hand since the csv writer applies one quoting
+/// style to every field. Fields are also quoted when they contain the
+/// delimiter, the output quote or a line break, as the csv writer would.
+fn write_with_original_quoting(
+    path: &str,
+    rows: &[ReconstructedRow],
+    config: &ReconstructConfig,
+    stats: &mut Stats,
+) -> Result<(), FixerrError> {
+    let delimiter = config.delimiter.as_byte();
+    let quote = config.output_quote.output_byte();
+    let terminator = config.line_ending.resolve(stats);
+    let mut sink = open_sink(path, config)?;
+    let mut line = String::new();
+
+    for (index, row) in rows.iter().enumerate() {
+        line.clear();
+        for (i, field) in prepare_record(&row.record, row.header, config).iter().enumerate() {
+            if i > 0 {
+                line.push(char::from(delimiter));
+            }
+            let was_quoted = row.quoted.get(i).copied().unwrap_or(false);
+            let needs = was_quoted || field.bytes().any(|b| matches!(b, b'\r' | b'\n') || b == delimiter || b == quote);
+            if config.quote_style.quotes(field, needs) {
+                push_quoted(&mut line, field, quote);
+            } else {
+                line.push_str(field);
+            }
+        }
+        if index + 1 < rows.len() || config.final_newline {
+            line.push_str(terminator.as_str());
+        }
+        sink.write_all(line.as_bytes())?;
+    }
+
+    finish_sink(sink, stats)
+}
+
+/// Write a multi-section reconstruction as separate files or one tagged file
+fn write_sections(
+    output_path: &str,
+    rows: &[ReconstructedRow],
+    config: &ReconstructConfig,
+    stats: &mut Stats,
+) -> Result<Vec<String>, FixerrError> {
+    match config.section_output {
+        SectionOutput::SeparateFiles => {
+            let section_count = rows.last().map_or(0, |row| row.section + 1);
+            let mut written = Vec::with_capacity(section_count);
+            for section in 0..section_count {
+                let records: Vec<StringRecord> = rows
+                    .iter()
+                    .filter(|row| row.section == section)
+                    .map(|row| row.record.clone())
+                    .collect();
+                let path = suffixed_path(output_path, &format!("section_{}", section + 1));
+                written.extend(write_output_csv_with_config(&path, &records, config, stats)?);
+            }
+            Ok(written)
+        }
+        SectionOutput::SectionColumn => {
+            // Sections have different widths, so the combined file is ragged
+            let mut writer = csv_writer_builder(config, stats)
+                .flexible(true)
+                .from_writer(open_sink(output_path, config)?);
+            for row in rows {
+                let tag = if row.header {
+                    "section".to_string()
+                } else {
+                    (row.section + 1).to_string()
+                };
+                let fields = prepare_record(&row.record, row.header, config);
+                writer.write_record(std::iter::once(tag).chain(fields))?;
+            }
+            finish_writer(writer, stats)?;
+            Ok(vec![output_path.to_string()])
+        }
+    }
+}
+
+/// Apply the configured field transformation before writing
+pub(crate) fn prepare_field(field: &str, config: &ReconstructConfig) -> String {
+    if config.escape_special_chars {
+        // Escaping replaces whitespace collapsing: embedded tabs and newlines
+        // are kept as escape sequences instead of being flattened to spaces.
+        escape_special_chars(field.trim())
+    } else {
+        config.normalize_mode.apply(field)
+    }
+}
+
+/// Clean every field of `record` and redact the configured data columns
+///
+/// Headers are passed through untouched with `verbatim_header`.
+pub(crate) fn prepare_record(record: &StringRecord, is_header: bool, config: &ReconstructConfig) -> Vec<String> {
+    if is_header && config.verbatim_header {
+        return record.iter().map(str::to_string).collect();
+    }
+    record
+        .iter()
+        .enumerate()
+        .map(|(column, field)| {
+            let field = prepare_field(field, config);
+            match config.redact_columns.get(&column) {
+                Some(mode) if !is_header => mode.apply(&field, &config.redact_salt),
+                _ => field,
+            }
+        })
+        .collect()
+}
+
+/// Append `field` enclosed in `quote`, doubling every `quote` inside it
+fn push_quoted(line: &mut String, field: &str, quote: u8) {
+    let quote = char::from(quote);
+    line.push(quote);
+    line.push_str(&field.replace(quote, &format!("{quote}{quote}")));
+    line.push(quote);
+}
+
+/// csv writer settings shared by all record files: delimiter, quote and
+/// line ending
+fn csv_writer_builder(config: &ReconstructConfig, stats: &Stats) -> WriterBuilder {
+    let terminator = match config.line_ending.resolve(stats) {
+        LineTerminator::CrLf => Terminator::CRLF,
+        other => Terminator::Any(other.as_str().as_bytes()[0]),
+    };
+    let mut builder = WriterBuilder::new();
+    builder
+        .delimiter(config.delimiter.as_byte())
+        .quote(config.output_quote.output_byte())
+        .quote_style(config.quote_style.as_csv())
+        .terminator(terminator);
+    builder
+}
+
+/// Output file with retrying writes, optional compression and encoding conversion
+type Sink = TranscodingWriter<OutputFile>;
+
+/// The file under a [`Sink`], gzip-compressed or not
+enum OutputFile {
+    Plain(RetryingWriter<File>),
+    Gzip(GzEncoder<RetryingWriter<File>>),
+}
+
+impl OutputFile {
+    /// Complete the file; for gzip this writes the stream trailer
+    fn finish(self) -> io::Result<()> {
+        match self {
+            OutputFile::Plain(mut file) => file.flush(),
+            OutputFile::Gzip(encoder) => encoder.finish()?.flush(),
+        }
+    }
+}
+
+impl Write for OutputFile {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match self {
+            OutputFile::Plain(file) => file.write(buf),
+            OutputFile::Gzip(encoder) => encoder.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match self {
+            OutputFile::Plain(file) => file.flush(),
+            OutputFile::Gzip(encoder) => encoder.flush(),
+        }
+    }
+}
+
+/// Create an output file wrapped in the configured compression and encoding
+fn open_sink(path: &str, config: &ReconstructConfig) -> Result<Sink, FixerrError> {
+    let file = config.retry.run_unwrapped(|| File::create(path))?;
+    let file = RetryingWriter::new(file, config.retry);
+    let file = if config.compress || path.ends_with(".gz") {
+        OutputFile::Gzip(GzEncoder::new(file, Compression::default()))
+    } else {
+        OutputFile::Plain(file)
+    };
+    Ok(TranscodingWriter::new(file, config.output_encoding, config.unmappable_action))
+}
+
+/// Flush a writer and record how many characters had to be replaced
+fn finish_writer(writer: Writer<Sink>, stats: &mut Stats) -> Result<(), FixerrError> {
+    let sink = writer.into_inner().map_err(|e| e.into_error())?;
+    finish_sink(sink, stats)
+}
+
+fn finish_sink(sink: Sink, stats: &mut Stats) -> Result<(), FixerrError> {
+    let (file, unmappable) = sink.into_parts()?;
+    file.finish()?;
+    stats.unmappable_chars += unmappable;
+    Ok(())
+}
+
+fn write_part(
+    path: &str,
+    header: Option<&StringRecord>,
+    rows: &[StringRecord],
+    config: &ReconstructConfig,
+    stats: &mut Stats,
+) -> Result<(), FixerrError> {
+    if let Some(delimiters) = &config.column_delimiters {
+        return write_mixed_delimiters(path, header, rows, delimiters, config, stats);
+    }
+
+    let mut writer = csv_writer_builder(config, stats).from_writer(open_sink(path, config)?);
+    let records: Vec<&StringRecord> = header.into_iter().chain(rows).collect();
+    let (body, last) = match records.split_last() {
+        Some((last, body)) if !config.final_newline => (body, Some(*last)),
+        _ => (records.as_slice(), None),
+    };
+    for (index, record) in body.iter().enumerate() {
+        writer.write_record(prepare_record(record, index == 0 && header.is_some(), config))?;
+    }
+    let mut sink = writer.into_inner().map_err(|e| e.into_error())?;
+    if let Some(record) = last {
+        write_unterminated(&mut sink, record, body.is_empty() && header.is_some(), config, stats)?;
+    }
+    finish_sink(sink, stats)?;
+
+    if config.strict_verify_output {
+        verify_output_strict(path, config)?;
+    }
+    Ok(())
+}
+
+/// Write rows to a single file as `rows` yields them, for streamed input
+///
+/// Each record is written as soon as the next one arrives, so only one is
+/// held in memory. `line_ending` is resolved against `stats` before the
+/// first row, which for `Auto` means the input's line endings are not yet
+/// known and `\n` is used.
+pub(crate) fn write_row_stream<I>(
+    path: &str,
+    rows: I,
+    config: &ReconstructConfig,
+    stats: &mut Stats,
+) -> Result<(), FixerrError>
+where
+    I: Iterator<Item = Result<ReconstructedRow, FixerrError>>,
+{
+    check_output_config(config)?;
+    let mut writer = csv_writer_builder(config, stats).from_writer(open_sink(path, config)?);
+    let mut pending: Option<ReconstructedRow> = None;
+    for row in rows {
+        if let Some(previous) = pending.replace(row?) {
+            writer.write_record(prepare_record(&previous.record, previous.header, config))?;
+        }
+    }
+    if config.final_newline {
+        if let Some(last) = pending.take() {
+            writer.write_record(prepare_record(&last.record, last.header, config))?;
+        }
+    }
+    let mut sink = writer.into_inner().map_err(|e| e.into_error())?;
+    if let Some(last) = pending {
+        write_unterminated(&mut sink, &last.record, last.header, config, stats)?;
+    }
+    finish_sink(sink, stats)?;
+
+    if config.strict_verify_output {
+        verify_output_strict(path, config)?;
+    }
+    Ok(())
+}
+
+/// Write only the `sample_output` sample of `rows`, for streamed input
+///
+/// Rows are fed to the reservoir as `rows` yields them, so at most the
+/// sample is held in memory. Does nothing without `sample_output`.
+///
+/// Returns the path of the sample.
+pub(crate) fn write_sample_stream<I>(
+    rows: I,
+    config: &ReconstructConfig,
+    stats: &mut Stats,
+) -> Result<Vec<String>, FixerrError>
+where
+    I: Iterator<Item = Result<ReconstructedRow, FixerrError>>,
+{
+    check_output_config(config)?;
+    let (sample_path, n) = match &config.sample_output {
+        Some(sample) => sample,
+        None => return Ok(Vec::new()),
+    };
+
+    let mut header = None;
+    let mut failure = None;
+    let data = rows
+        .map_while(|row| row.map_err(|e| failure = Some(e)).ok())
+        .enumerate()
+        .filter_map(|(index, row)| match row.header {
+            false => Some(row.record),
+            true => {
+                if index == 0 {
+                    header = Some(row.record);
+                }
+                None
+            }
+        });
+    let seed = config.sample_seed.unwrap_or_else(time_seed);
+    let sample: Vec<StringRecord> = reservoir_sample(data, *n, seed).into_iter().map(|(_, r)| r).collect();
+    if let Some(e) = failure {
+        return Err(e);
+    }
+    write_part(sample_path, header.as_ref(), &sample, config, stats)?;
+    Ok(vec![sample_path.clone()])
+}
+
+/// Write the raw input bytes of the discarded rows collected in `stats`
+///
+/// The bytes are written exactly as read: no transcoding, quoting or
+/// compression.
+pub(crate) fn write_rejects(path: &str, config: &ReconstructConfig, stats: &Stats) -> Result<(), FixerrError> {
+    let mut file = RetryingWriter::new(config.retry.run_unwrapped(|| File::create(path))?, config.retry);
+    file.write_all(&stats.rejected_raw)?;
+    file.flush()?;
+    Ok(())
+}
+
+/// Write `record` to `sink` without a line terminator
+///
+/// The csv writer always terminates a record, so the record is encoded
+/// separately and its terminator cut off.
+fn write_unterminated(
+    sink: &mut Sink,
+    record: &StringRecord,
+    is_header: bool,
+    config: &ReconstructConfig,
+    stats: &Stats,
+) -> Result<(), FixerrError> {
+    let mut encoder = csv_writer_builder(config, stats).from_writer(Vec::new());
+    encoder.write_record(prepare_record(record, is_header, config))?;
+    let mut bytes = encoder.into_inner().map_err(|e| e.into_error())?;
+    bytes.truncate(bytes.len() - config.line_ending.resolve(stats).as_str().len());
+    sink.write_all(&bytes)?;
+    Ok(())
+}
+
+/// Write records with a separate delimiter before each column
+///
+/// The csv writer only knows a single delimiter, so records are assembled by
+/// hand. Each field is quoted only when it contains one of the delimiters
+/// around it, the output quote or a line break, so a tab-separated last
+/// column may hold commas freely.
+fn write_mixed_delimiters(
+    path: &str,
+    header: Option<&StringRecord>,
+    rows: &[StringRecord],
+    delimiters: &[u8],
+    config: &ReconstructConfig,
+    stats: &mut Stats,
+) -> Result<(), FixerrError> {
+    let quote = config.output_quote.output_byte();
+    let terminator = config.line_ending.resolve(stats);
+    let mut sink = open_sink(path, config)?;
+    let mut line = String::new();
+
+    let mut records = header.into_iter().chain(rows).enumerate().peekable();
+    while let Some((index, record)) = records.next() {
+        if record.len() != delimiters.len() {
+            return Err(FixerrError::InvalidConfig(format!(
+                "column_delimiters has {} entries but a record has {} columns",
+                delimiters.len(),
+                record.len()
+            )));
+        }
+
+        line.clear();
+        for (i, field) in prepare_record(record, index == 0 && header.is_some(), config).iter().enumerate() {
+            if i > 0 {
+                line.push(char::from(delimiters[i]));
+            }
+            let before = if i > 0 { Some(delimiters[i]) } else { None };
+            let after = delimiters.get(i + 1).copied();
+            let needs_quotes = field
+                .bytes()
+                .any(|b| Some(b) == before || Some(b) == after || b == quote || matches!(b, b'\r' | b'\n'));
+            if config.quote_style.quotes(field, needs_quotes) {
+                push_quoted(&mut line, field, quote);
+            } else {
+                line.push_str(field);
+            }
+        }
+        if records.peek().is_some() || config.final_newline {
+            line.push_str(terminator.as_str());
+        }
+        sink.write_all(line.as_bytes())?;
+    }
+
+    finish_sink(sink, stats)
+}
+
+// ============================================
+// Unit Tests
+// ============================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::{reconstruct_rows_with_config, Delimiter, HeaderMode, Stats};
+    use crate::redact::RedactMode;
+    use std::fs;
+
+    #[test]
+    fn test_split_output_by_row_count() {
+        let mut rows = vec![StringRecord::from(vec!["ID", "Amount"])];
+        for i in 0..25 {
+            rows.push(StringRecord::from(vec![i.to_string(), "1.00".to_string()]));
+        }
+
+        let config = ReconstructConfig {
+            max_rows_per_file: Some(10),
+            ..ReconstructConfig::new(HeaderMode::HasHeaders, Delimiter::Comma)
+        };
+        let written = write_output_csv_with_config("test_split.csv", &rows, &config, &mut Stats::default()).unwrap();
+
+        let counts: Vec<usize> = written
+            .iter()
+            .map(|path| {
+                let content = fs::read_to_string(path).unwrap();
+                let _ = fs::remove_file(path);
+                assert!(content.starts_with("ID,Amount\n"));
+                content.lines().count() - 1
+            })
+            .collect();
+
+        assert_eq!(
+            written,
+            vec!["test_split_part_001.csv", "test_split_part_002.csv", "test_split_part_003.csv"]
+        );
+        assert_eq!(counts, vec![10, 10, 5]);
+    }
+
+    #[test]
+    fn test_split_output_exact_multiple_has_no_empty_part() {
+        let mut rows = vec![StringRecord::from(vec!["ID"])];
+        rows.extend((0..20).map(|i| StringRecord::from(vec![i.to_string()])));
+
+        let config = ReconstructConfig {
+            max_rows_per_file: Some(10),
+            ..ReconstructConfig::default()
+        };
+        let written = write_output_csv_with_config("test_split_exact.csv", &rows, &config, &mut Stats::default()).unwrap();
+        for path in &written {
+            let _ = fs::remove_file(path);
+        }
+
+        assert_eq!(written.len(), 2);
+    }
+
+    #[test]
+    fn test_split_changed_partitions_by_repair_flag() {
+        let filename = "test_split_changed_input.csv";
+        let content = "ID,Details,Amount\n1,Intact,10\n2,Split\nacross,20\n3,Also intact,30";
+        fs::write(filename, content).unwrap();
+
+        let config = ReconstructConfig {
+            split_changed: true,
+            ..ReconstructConfig::new(HeaderMode::HasHeaders, Delimiter::Comma)
+        };
+        let mut stats = Stats::default();
+        let rows = reconstruct_rows_with_config(filename, &config, &mut stats).unwrap();
+        let _ = fs::remove_file(filename);
+
+        let written = write_output_rows_with_config("test_split_changed.csv", &rows, &config, &mut stats).unwrap();
+        let contents: Vec<String> = written
+            .iter()
+            .map(|path| {
+                let content = fs::read_to_string(path).unwrap();
+                let _ = fs::remove_file(path);
+                content
+            })
+            .collect();
+
+        assert_eq!(
+            written,
+            vec!["test_split_changed_changed.csv", "test_split_changed_unchanged.csv"]
+        );
+        assert_eq!(contents[0], "ID,Details,Amount\n2,Split across,20\n");
+        assert_eq!(contents[1], "ID,Details,Amount\n1,Intact,10\n3,Also intact,30\n");
+    }
+
+    #[test]
+    fn test_rejects_keep_original_bytes() {
+        let rejected = "2,\"Gori\"\"s\" ,  20 ,\"x\"\r\n";
+        let content = format!("ID,Name,Amount\n1,Tbilisi,10\n\n{rejected}3,Batumi,30\n4, 'odd\n");
+        let filename = "test_rejects_input.csv";
+        fs::write(filename, &content).unwrap();
+
+        let config = ReconstructConfig {
+            rejects_path: Some("test_rejects.csv".to_string()),
+            ..ReconstructConfig::default()
+        };
+        let mut stats = Stats::default();
+        let rows = reconstruct_rows_with_config(filename, &config, &mut stats).unwrap();
+        let _ = fs::remove_file(filename);
+        let written = write_output_rows_with_config("test_rejects_output.csv", &rows, &config, &mut stats).unwrap();
+        for path in &written {
+            let _ = fs::remove_file(path);
+        }
+        let rejects = fs::read("test_rejects.csv").unwrap();
+        let _ = fs::remove_file("test_rejects.csv");
+
+        assert_eq!(rows.len(), 3);
+        assert_eq!(String::from_utf8(rejects).unwrap(), format!("{rejected}4, 'odd\n"));
+    }
+
+    #[test]
+    fn test_strict_verification_catches_ragged_output() {
+        let config = ReconstructConfig {
+            strict_verify_output: true,
+            ..ReconstructConfig::default()
+        };
+        let rows = vec![
+            StringRecord::from(vec!["ID", "Name"]),
+            StringRecord::from(vec!["1", "Tbilisi, Waters"]),
+        ];
+        let path = "test_strict_verify.csv";
+        write_output_csv_with_config(path, &rows, &config, &mut Stats::default()).unwrap();
+
+        // A writer bug that drops a field would slip past a flexible reader
+        fs::write(path, "ID,Name\n1,\"Tbilisi, Waters\"\n2\n").unwrap();
+        let err = verify_output_strict(path, &config).unwrap_err();
+        let _ = fs::remove_file(path);
+
+        match err {
+            FixerrError::OutputNotStrictlyValid { line, width } => assert_eq!((line, width), (3, 1)),
+            other => panic!("unexpected error: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_gzip_output_round_trips() {
+        let path = "test_gzip_output.csv.gz";
+        let rows = vec![
+            StringRecord::from(vec!["ID", "Name"]),
+            StringRecord::from(vec!["1", "Tbilisi Waters"]),
+            StringRecord::from(vec!["2", "Gori"]),
+        ];
+        write_output_csv_with_config(path, &rows, &ReconstructConfig::default(), &mut Stats::default()).unwrap();
+
+        let compressed = fs::read(path).unwrap();
+        let _ = fs::remove_file(path);
+        let mut reader = csv::ReaderBuilder::new()
+            .has_headers(false)
+            .from_reader(flate2::read::GzDecoder::new(compressed.as_slice()));
+        let read_back: Vec<StringRecord> = reader.records().map(Result::unwrap).collect();
+
+        assert_eq!(&compressed[..2], &[0x1f, 0x8b]);
+        assert_eq!(read_back, rows);
+    }
+
+    #[test]
+    fn test_escape_special_chars_in_output() {
+        let rows = vec![
+            StringRecord::from(vec!["ID", "Notes"]),
+            StringRecord::from(vec!["1", " tab\there\nnext line C:\\dir "]),
+        ];
+
+        let config = ReconstructConfig {
+            escape_special_chars: true,
+            ..ReconstructConfig::new(HeaderMode::HasHeaders, Delimiter::Comma)
+        };
+        let written = write_output_csv_with_config("test_escape.csv", &rows, &config, &mut Stats::default()).unwrap();
+        let content = fs::read_to_string(&written[0]).unwrap();
+        let _ = fs::remove_file(&written[0]);
+
+        assert_eq!(content, "ID,Notes\n1,tab\\there\\nnext line C:\\\\dir\n");
+
+        let conflicting = ReconstructConfig { normalize_mode: NormalizeMode::PreserveNewlines, ..config };
+        let result = write_output_csv_with_config("test_conflict.csv", &rows, &conflicting, &mut Stats::default());
+        assert!(matches!(result, Err(FixerrError::InvalidConfig(_))));
+        assert!(!Path::new("test_conflict.csv").exists());
+    }
+
+    #[test]
+    fn test_redacted_columns_in_output() {
+        let rows = vec![
+            StringRecord::from(vec!["ID", "Name", "Card", "TaxId"]),
+            StringRecord::from(vec!["1", "Tbilisi", "4111 1111 1111 1234", "  205 123 456 "]),
+            StringRecord::from(vec!["2", "Gori", "", "205 123 456"]),
+        ];
+
+        let config = ReconstructConfig {
+            redact_columns: [(1, RedactMode::Mask), (2, RedactMode::Partial), (3, RedactMode::Hash)].into_iter().collect(),
+            redact_salt: "pepper".to_string(),
+            ..ReconstructConfig::new(HeaderMode::HasHeaders, Delimiter::Comma)
+        };
+        let written = write_output_csv_with_config("test_redact.csv", &rows, &config, &mut Stats::default()).unwrap();
+        let content = fs::read_to_string(&written[0]).unwrap();
+        let _ = fs::remove_file(&written[0]);
+
+        let hash = RedactMode::Hash.apply("205 123 456", "pepper");
+        assert_eq!(hash.len(), 16);
+        assert_ne!(hash, RedactMode::Hash.apply("205 123 456", "salt"));
+        assert_eq!(content, format!("ID,Name,Card,TaxId\n1,****,****1234,{hash}\n2,****,,{hash}\n"));
+    }
+
+    #[test]
+    fn test_multi_section_reconstruction() {
+        let filename = "test_multi_section_input.csv";
+        let content = "ID,Name,Amount\n1,Tbilisi\nWaters,10\n2,Gori,20\n\nCode,Region\nGE-TB,Tbilisi\nGE-IM,Imereti";
+        fs::write(filename, content).unwrap();
+
+        let mut config = ReconstructConfig {
+            multi_section: true,
+            ..ReconstructConfig::new(HeaderMode::HasHeaders, Delimiter::Comma)
+        };
+        let mut stats = Stats::default();
+        let rows = reconstruct_rows_with_config(filename, &config, &mut stats).unwrap();
+        let _ = fs::remove_file(filename);
+
+        assert_eq!(rows.len(), 6);
+        assert_eq!(stats.removed_rows, 0);
+        assert_eq!(stats.fixed_rows, 1);
+        let sections: Vec<usize> = rows.iter().map(|r| r.section).collect();
+        assert_eq!(sections, vec![0, 0, 0, 1, 1, 1]);
+        assert!(rows[3].header);
+
+        let written = write_output_rows_with_config("test_multi_section.csv", &rows, &config, &mut stats).unwrap();
+        let contents: Vec<String> = written
+            .iter()
+            .map(|path| {
+                let content = fs::read_to_string(path).unwrap();
+                let _ = fs::remove_file(path);
+                content
+            })
+            .collect();
+        assert_eq!(
+            written,
+            vec!["test_multi_section_section_1.csv", "test_multi_section_section_2.csv"]
+        );
+        assert_eq!(contents[0], "ID,Name,Amount\n1,Tbilisi Waters,10\n2,Gori,20\n");
+        assert_eq!(contents[1], "Code,Region\nGE-TB,Tbilisi\nGE-IM,Imereti\n");
+
+        config.section_output = SectionOutput::SectionColumn;
+        let written = write_output_rows_with_config("test_multi_section.csv", &rows, &config, &mut stats).unwrap();
+        let combined = fs::read_to_string(&written[0]).unwrap();
+        let _ = fs::remove_file(&written[0]);
+        assert_eq!(
+            combined,
+            "section,ID,Name,Amount\n1,1,Tbilisi Waters,10\n1,2,Gori,20\n\
+             section,Code,Region\n2,GE-TB,Tbilisi\n2,GE-IM,Imereti\n"
+        );
+    }
+
+    #[test]
+    fn test_output_encoding_windows_1252() {
+        let rows = vec![
+            StringRecord::from(vec!["Name", "City"]),
+            StringRecord::from(vec!["Café", "თბილისი"]),
+        ];
+
+        let config = ReconstructConfig {
+            output_encoding: encoding_rs::WINDOWS_1252,
+            ..ReconstructConfig::default()
+        };
+        let mut stats = Stats::default();
+        let written = write_output_csv_with_config("test_encoding.csv", &rows, &config, &mut stats).unwrap();
+        let bytes = fs::read(&written[0]).unwrap();
+        let _ = fs::remove_file(&written[0]);
+
+        assert_eq!(bytes, b"Name,City\nCaf\xE9,???????\n".to_vec());
+        assert_eq!(stats.unmappable_chars, 7);
+    }
+
+    #[test]
+    fn test_column_delimiters_hybrid_format_round_trips() {
+        let rows = vec![
+            StringRecord::from(vec!["ID", "Name", "Amount", "Notes"]),
+            StringRecord::from(vec!["1", "Tbilisi Waters", "10", "paid, in full"]),
+            StringRecord::from(vec!["2", "Gori, Ltd", "20", "tab\there"]),
+        ];
+        let mut config = ReconstructConfig {
+            column_delimiters: Some(vec![b',', b',', b',', b'\t']),
+            escape_special_chars: true,
+            ..ReconstructConfig::default()
+        };
+
+        let output = "test_column_delimiters.csv";
+        let mut stats = Stats::default();
+        write_output_csv_with_config(output, &rows, &config, &mut stats).unwrap();
+        let written = fs::read_to_string(output).unwrap();
+        let _ = fs::remove_file(output);
+
+        assert_eq!(
+            written,
+            "ID,Name,Amount\tNotes\n1,Tbilisi Waters,10\tpaid, in full\n2,\"Gori, Ltd\",20\ttab\\there\n"
+        );
+
+        // Reading back: split off the notes at the tab, then parse the rest as CSV
+        let mut outer = csv::ReaderBuilder::new().delimiter(b'\t').has_headers(false).from_reader(written.as_bytes());
+        let second = outer.records().nth(1).unwrap().unwrap();
+        assert_eq!(&second[1], "paid, in full");
+        let mut inner = csv::ReaderBuilder::new().has_headers(false).from_reader(second[0].as_bytes());
+        let leading = inner.records().next().unwrap().unwrap();
+        assert_eq!(leading.iter().collect::<Vec<_>>(), vec!["1", "Tbilisi Waters", "10"]);
+
+        config.column_delimiters = Some(vec![b',', b'\t']);
+        assert!(write_output_csv_with_config(output, &rows, &config, &mut stats).is_err());
+        let _ = fs::remove_file(output);
+    }
+
+    #[test]
+    fn test_final_newline_setting() {
+        let rows = vec![
+            StringRecord::from(vec!["ID", "Name"]),
+            StringRecord::from(vec!["1", "Gori, Ltd"]),
+        ];
+        let output = "test_final_newline.csv";
+
+        let mut config = ReconstructConfig::default();
+        write_output_csv_with_config(output, &rows, &config, &mut Stats::default()).unwrap();
+        assert_eq!(fs::read(output).unwrap(), b"ID,Name\n1,\"Gori, Ltd\"\n");
+
+        config.final_newline = false;
+        write_output_csv_with_config(output, &rows, &config, &mut Stats::default()).unwrap();
+        assert_eq!(fs::read(output).unwrap(), b"ID,Name\n1,\"Gori, Ltd\"");
+
+        config.column_delimiters = Some(vec![b',', b'\t']);
+        write_output_csv_with_config(output, &rows, &config, &mut Stats::default()).unwrap();
+        let written = fs::read(output).unwrap();
+        let _ = fs::remove_file(output);
+        assert_eq!(written, b"ID\tName\n1\tGori, Ltd");
+    }
+
+    #[test]
+    fn test_preserve_quoting_round_trips_clean_rows() {
+        let input = "\"ID\",Name,\"Amount\"\n1,\"Tbilisi Waters\",10\n\"2\",Gori,\"said \"\"hi\"\"\"\n3,\"Sarme\nBeverages\",30\n4,Mestia\nValley,40\n";
+        let config = ReconstructConfig {
+            preserve_quoting: true,
+            normalize_mode: crate::NormalizeMode::PreserveNewlines,
+            ..ReconstructConfig::default()
+        };
+        let mut stats = Stats::default();
+        let rows = crate::reconstruct_rows_from_reader(input.as_bytes(), &config, &mut stats).unwrap();
+
+        let output = "test_preserve_quoting.csv";
+        write_output_rows_with_config(output, &rows, &config, &mut stats).unwrap();
+        let written = fs::read_to_string(output).unwrap();
+        let _ = fs::remove_file(output);
+
+        // Intact rows keep their quoting; the stitched row is quoted for its newline only
+        assert_eq!(
+            written,
+            "\"ID\",Name,\"Amount\"\n1,\"Tbilisi Waters\",10\n\"2\",Gori,\"said \"\"hi\"\"\"\n3,\"Sarme\nBeverages\",30\n4,\"Mestia\nValley\",40\n"
+        );
+    }
+
+    #[test]
+    fn test_preserve_quoting_verifies_and_rejects_unsupported_options() {
+        let rows = vec![
+            ReconstructedRow::clean(StringRecord::from(vec!["ID", "Name"]), vec![1]),
+            ReconstructedRow::clean(StringRecord::from(vec!["1"]), vec![2]),
+        ];
+        let output = "test_preserve_quoting_options.csv";
+        let quoting = ReconstructConfig { preserve_quoting: true, ..ReconstructConfig::default() };
+
+        let strict = ReconstructConfig { strict_verify_output: true, ..quoting.clone() };
+        let verified = write_output_rows_with_config(output, &rows, &strict, &mut Stats::default());
+        let _ = fs::remove_file(output);
+        assert!(matches!(verified, Err(FixerrError::OutputNotStrictlyValid { line: 2, width: 1 })));
+
+        let conflicting = [
+            ReconstructConfig { max_rows_per_file: Some(1), ..quoting.clone() },
+            ReconstructConfig { split_changed: true, ..quoting.clone() },
+            ReconstructConfig { column_delimiters: Some(vec![b',', b',']), ..quoting.clone() },
+            ReconstructConfig { multi_section: true, ..quoting },
+        ];
+        for config in &conflicting {
+            let result = write_output_rows_with_config(output, &rows, config, &mut Stats::default());
+            assert!(matches!(result, Err(FixerrError::InvalidConfig(_))), "{result:?}");
+            assert!(!Path::new(output).exists());
+        }
+    }
+
+    #[test]
+    fn test_line_ending_auto_mirrors_crlf_input() {
+        let input = "ID,Name,Amount\r\n1,Tbilisi\r\nWaters,10\r\n2,Gori,20\r\n";
+        let mut config = ReconstructConfig::default();
+        let mut stats = Stats::default();
+        let rows = crate::reconstruct_rows_from_reader(input.as_bytes(), &config, &mut stats).unwrap();
+        assert_eq!(stats.dominant_line_ending(), crate::LineTerminator::CrLf);
+
+        let output = "test_line_ending.csv";
+        write_output_rows_with_config(output, &rows, &config, &mut stats).unwrap();
+        let default_written = fs::read_to_string(output).unwrap();
+
+        config.line_ending = crate::LineEnding::Auto;
+        write_output_rows_with_config(output, &rows, &config, &mut stats).unwrap();
+        let auto_written = fs::read_to_string(output).unwrap();
+        let _ = fs::remove_file(output);
+
+        assert_eq!(default_written, "ID,Name,Amount\n1,Tbilisi Waters,10\n2,Gori,20\n");
+        assert_eq!(auto_written, "ID,Name,Amount\r\n1,Tbilisi Waters,10\r\n2,Gori,20\r\n");
+    }
+
+    #[test]
+    fn test_output_quote_differs_from_input_quote() {
+        let input = "ID,Name\n1,'Gori, \"Ltd\"'\n2,O'Neil\n";
+        let mut config = ReconstructConfig { quote: crate::QuoteChar::Single, ..ReconstructConfig::default() };
+        let rows = crate::reconstruct_rows_from_reader(input.as_bytes(), &config, &mut Stats::default()).unwrap();
+        let records: Vec<StringRecord> = rows.into_iter().map(|row| row.record).collect();
+
+        let output = "test_output_quote.csv";
+        write_output_csv_with_config(output, &records, &config, &mut Stats::default()).unwrap();
+        let double = fs::read_to_string(output).unwrap();
+
+        config.output_quote = crate::QuoteChar::Custom(b'\'');
+        write_output_csv_with_config(output, &records, &config, &mut Stats::default()).unwrap();
+        let single = fs::read_to_string(output).unwrap();
+        let verified = verify_output_strict(output, &config);
+        let _ = fs::remove_file(output);
+
+        assert_eq!(double, "ID,Name\n1,\"Gori, \"\"Ltd\"\"\"\n2,O'Neil\n");
+        assert_eq!(single, "ID,Name\n1,'Gori, \"Ltd\"'\n2,'O''Neil'\n");
+        assert!(verified.is_ok());
+    }
+
+    #[test]
+    fn test_quote_style_setting() {
+        let rows = vec![
+            StringRecord::from(vec!["ID", "Name"]),
+            StringRecord::from(vec!["1", "Gori, Ltd"]),
+        ];
+        let output = "test_quote_style.csv";
+        let mut written = Vec::new();
+        for style in [crate::QuoteStyle::Always, crate::QuoteStyle::NonNumeric, crate::QuoteStyle::Never] {
+            let config = ReconstructConfig { quote_style: style, ..ReconstructConfig::default() };
+            write_output_csv_with_config(output, &rows, &config, &mut Stats::default()).unwrap();
+            written.push(fs::read_to_string(output).unwrap());
+        }
+        let _ = fs::remove_file(output);
+
+        assert_eq!(written[0], "\"ID\",\"Name\"\n\"1\",\"Gori, Ltd\"\n");
+        assert_eq!(written[1], "\"ID\",\"Name\"\n1,\"Gori, Ltd\"\n");
+        // Never is the caller's risk: the comma now splits the field
+        assert_eq!(written[2], "ID,Name\n1,Gori, Ltd\n");
+    }
+
+    #[test]
+    fn test_verbatim_header_keeps_intentional_spacing() {
+        let rows = vec![
+            StringRecord::from(vec!["Customer  ID", " Name "]),
+            StringRecord::from(vec!["1", "  Gori   Ltd "]),
+        ];
+        let output = "test_verbatim_header.csv";
+        let mut config = ReconstructConfig::default();
+        write_output_csv_with_config(output, &rows, &config, &mut Stats::default()).unwrap();
+        let normalized = fs::read_to_string(output).unwrap();
+
+        config.verbatim_header = true;
+        write_output_csv_with_config(output, &rows, &config, &mut Stats::default()).unwrap();
+        let verbatim = fs::read_to_string(output).unwrap();
+        let _ = fs::remove_file(output);
+
+        assert_eq!(normalized, "Customer ID,Name\n1,Gori Ltd\n");
+        assert_eq!(verbatim, "Customer  ID, Name \n1,Gori Ltd\n");
+    }
+}
@@ -0,0 +1,104 @@
+// src/pipeline.rs
+//! Multi-stage reconstruction
+//!
+//! Some files need several passes with different settings, e.g. stitching
+//! fragments with one delimiter and then normalizing with another. Each
+//! stage's output is handed to the next stage in memory.
+
+use crate::config::ReconstructConfig;
+use crate::engine::{reconstruct_rows_from_reader, reconstruct_rows_with_config, ReconstructedRow, Stats};
+use crate::error::FixerrError;
+use crate::output::prepare_field;
+use csv::WriterBuilder;
+
+/// Reconstruct `file_path` through a chain of configurations
+///
+/// The first stage reads the file; every later stage reads the previous
+/// stage's records, serialized as CSV with the later stage's delimiter and
+/// with the earlier stage's field cleaning applied. Intermediate results are
+/// never written to disk; to inspect one, run the pipeline up to that stage.
+///
+/// `stats` receives the sum of every stage's counters and all warnings, so
+/// physical-row figures count rows once per stage they passed through.
+/// Output options of the stages are ignored; write the returned rows with
+/// the final stage's config.
+pub fn reconstruct_pipeline(
+    file_path: &str,
+    stages: &[ReconstructConfig],
+    stats: &mut Stats,
+) -> Result<Vec<ReconstructedRow>, FixerrError> {
+    let (first, rest) = match stages.split_first() {
+        Some(split) => split,
+        None => return Err(FixerrError::InvalidConfig("pipeline needs at least one stage".to_string())),
+    };
+
+    let mut stage_stats = Stats::default();
+    let mut rows = reconstruct_rows_with_config(file_path, first, &mut stage_stats)?;
+    stats.absorb(stage_stats);
+
+    let mut previous = first;
+    for stage in rest {
+        let intermediate = serialize_rows(&rows, previous, stage)?;
+        let mut stage_stats = Stats::default();
+        rows = reconstruct_rows_from_reader(intermediate.as_slice(), stage, &mut stage_stats)?;
+        stats.absorb(stage_stats);
+        previous = stage;
+    }
+
+    Ok(rows)
+}
+
+// ============================================
+// Private Helper Functions
+// ============================================
+
+/// Write `rows` as cleaned by `from`, delimited for `to`
+fn serialize_rows(
+    rows: &[ReconstructedRow],
+    from: &ReconstructConfig,
+    to: &ReconstructConfig,
+) -> Result<Vec<u8>, FixerrError> {
+    let mut writer = WriterBuilder::new()
+        .delimiter(to.delimiter.as_byte())
+        .flexible(true)
+        .from_writer(Vec::new());
+    for row in rows {
+        writer.write_record(row.record.iter().map(|field| prepare_field(field, from)))?;
+    }
+    Ok(writer.into_inner().map_err(|e| e.into_error())?)
+}
+
+// ============================================
+// Unit Tests
+// ============================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ColumnNormalizer, Delimiter, HeaderMode};
+    use std::fs;
+
+    #[test]
+    fn test_two_stage_pipeline_composes_stages() {
+        let path = "test_pipeline_input.csv";
+        fs::write(path, "ID;Name;Amount\n1;tbilisi\nwaters;10\n2;gori;20\n").unwrap();
+
+        let stitch = ReconstructConfig::new(HeaderMode::HasHeaders, Delimiter::Semicolon);
+        let mut shout = ReconstructConfig::new(HeaderMode::HasHeaders, Delimiter::Comma);
+        shout.column_normalizers = vec![ColumnNormalizer { column: 1, normalizer: "upper".to_string() }];
+        let names = |rows: &[ReconstructedRow]| -> Vec<String> {
+            rows.iter().map(|row| row.record.get(1).unwrap_or("-").to_string()).collect()
+        };
+
+        let mut stats = Stats::default();
+        let composed = reconstruct_pipeline(path, &[stitch.clone(), shout.clone()], &mut stats).unwrap();
+        let first_only = reconstruct_pipeline(path, &[stitch], &mut Stats::default()).unwrap();
+        let second_only = reconstruct_pipeline(path, &[shout], &mut Stats::default()).unwrap();
+        fs::remove_file(path).unwrap();
+
+        assert_eq!(names(&composed), ["Name", "TBILISI WATERS", "GORI"]);
+        assert_eq!(names(&first_only), ["Name", "tbilisi\nwaters", "gori"]);
+        assert_ne!(names(&second_only), names(&composed));
+        assert_eq!(stats.fixed_rows, 1);
+    }
+}
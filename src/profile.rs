@@ -0,0 +1,118 @@
+// src/profile.rs
+//! Column cardinality profiling
+//!
+//! Counts the distinct values of each column after a repair. A column that
+//! should be categorical but holds thousands of distinct values usually
+//! means record fragments leaked into it.
+
+use crate::engine::clean_and_normalize_field;
+use csv::StringRecord;
+use std::collections::HashSet;
+use std::fmt;
+
+/// Distinct values tracked per column before counting stops
+pub const CARDINALITY_CAP: usize = 10_000;
+
+/// Distinct values and samples of one column
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CardinalityReport {
+    /// 0-based column index
+    pub column: usize,
+    /// Header name, if a header was given
+    pub name: Option<String>,
+    /// Number of distinct non-empty values, at most [`CARDINALITY_CAP`]
+    pub distinct: usize,
+    /// True if counting stopped at [`CARDINALITY_CAP`]
+    pub capped: bool,
+    /// The first distinct values in record order
+    pub samples: Vec<String>,
+}
+
+impl fmt::Display for CardinalityReport {
+    /// Render as e.g. `Column 3 'Status': 4 distinct — ACTIVE, INACTIVE, PENDING, VOID`
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Column {}", self.column)?;
+        if let Some(name) = &self.name {
+            write!(f, " '{name}'")?;
+        }
+        write!(f, ": {}{} distinct", self.distinct, if self.capped { "+" } else { "" })?;
+        if !self.samples.is_empty() {
+            write!(f, " — {}", self.samples.join(", "))?;
+        }
+        Ok(())
+    }
+}
+
+/// Count the distinct values of every column of `rows`
+///
+/// `rows` are data records only; pass the header separately to name the
+/// columns. Values are compared after the usual field clean-up, and empty
+/// values are ignored. Up to `sample_size` distinct values are kept per
+/// column as samples. The report covers the widest record's columns.
+pub fn profile_cardinality(
+    rows: &[StringRecord],
+    header: Option<&StringRecord>,
+    sample_size: usize,
+) -> Vec<CardinalityReport> {
+    let width = rows.iter().map(StringRecord::len).max().unwrap_or(0);
+    let mut seen: Vec<HashSet<String>> = vec![HashSet::new(); width];
+    let mut reports: Vec<CardinalityReport> = (0..width)
+        .map(|column| CardinalityReport {
+            column,
+            name: header.and_then(|h| h.get(column)).map(clean_and_normalize_field),
+            distinct: 0,
+            capped: false,
+            samples: Vec::new(),
+        })
+        .collect();
+
+    for record in rows {
+        for ((field, values), report) in record.iter().zip(seen.iter_mut()).zip(reports.iter_mut()) {
+            let value = clean_and_normalize_field(field);
+            if value.is_empty() || report.capped || values.contains(&value) {
+                continue;
+            }
+            if values.len() == CARDINALITY_CAP {
+                report.capped = true;
+                continue;
+            }
+            if report.samples.len() < sample_size {
+                report.samples.push(value.clone());
+            }
+            values.insert(value);
+            report.distinct = values.len();
+        }
+    }
+    reports
+}
+
+// ============================================
+// Unit Tests
+// ============================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_profile_cardinality_counts_distinct_values() {
+        let header = StringRecord::from(vec!["ID", "Status", "Notes"]);
+        let rows: Vec<StringRecord> = [
+            ["1", "ACTIVE", ""],
+            ["2", " ACTIVE ", ""],
+            ["3", "VOID", "late"],
+            ["4", "PENDING", ""],
+            ["5", "ACTIVE", "late"],
+        ]
+        .iter()
+        .map(|fields| StringRecord::from(fields.to_vec()))
+        .collect();
+
+        let reports = profile_cardinality(&rows, Some(&header), 2);
+
+        assert_eq!(reports.iter().map(|r| r.distinct).collect::<Vec<_>>(), [5, 3, 1]);
+        assert_eq!(reports[1].samples, ["ACTIVE", "VOID"]);
+        assert_eq!(reports[1].to_string(), "Column 1 'Status': 3 distinct — ACTIVE, VOID");
+        assert!(!reports[0].capped);
+    }
+}
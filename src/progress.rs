@@ -0,0 +1,151 @@
+// src/progress.rs
+//! Progress reporting for long runs
+//!
+//! The engine periodically emits a [`ProgressEvent`] to the hook set in
+//! [`ReconstructConfig::progress`](crate::ReconstructConfig). Percentages and
+//! estimates are derived from the byte position the csv reader reports.
+
+use std::fmt;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// Physical rows read between two progress events
+pub const PROGRESS_INTERVAL_ROWS: usize = 10_000;
+
+/// A snapshot of how far reconstruction has come
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ProgressEvent {
+    /// Physical rows read so far
+    pub rows: usize,
+    /// Input bytes consumed so far
+    pub bytes_processed: u64,
+    /// Size of the whole input, when known (files, in-memory data)
+    pub total_bytes: Option<u64>,
+    /// Time since reconstruction started
+    pub elapsed: Duration,
+}
+
+impl ProgressEvent {
+    /// Share of the input processed, in `0.0..=1.0`
+    pub fn fraction(&self) -> Option<f64> {
+        match self.total_bytes {
+            Some(total) if total > 0 => Some((self.bytes_processed as f64 / total as f64).min(1.0)),
+            _ => None,
+        }
+    }
+
+    /// Time remaining at the throughput so far
+    pub fn eta(&self) -> Option<Duration> {
+        let total = self.total_bytes?;
+        if self.bytes_processed == 0 {
+            return None;
+        }
+        let remaining = total.saturating_sub(self.bytes_processed) as f64;
+        Some(self.elapsed.mul_f64(remaining / self.bytes_processed as f64))
+    }
+}
+
+impl fmt::Display for ProgressEvent {
+    /// `45% — ~1m20s remaining`, or the row count for inputs of unknown size
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (self.fraction(), self.eta()) {
+            (Some(fraction), Some(eta)) => {
+                write!(f, "{:.0}% — ~{} remaining", fraction * 100.0, format_duration(eta))
+            }
+            (Some(fraction), None) => write!(f, "{:.0}%", fraction * 100.0),
+            _ => write!(f, "{} rows", self.rows),
+        }
+    }
+}
+
+/// Signature of a progress callback
+pub type ProgressFn = dyn FnMut(&ProgressEvent) + Send;
+
+/// A shareable progress callback
+///
+/// Clones share the same closure, so state it keeps carries across them.
+#[derive(Clone)]
+pub struct ProgressHook(Arc<Mutex<Box<ProgressFn>>>);
+
+impl ProgressHook {
+    /// Wrap `f` as a hook
+    pub fn new(f: Box<ProgressFn>) -> Self {
+        Self(Arc::new(Mutex::new(f)))
+    }
+
+    /// Pass `event` to the callback
+    pub fn report(&self, event: &ProgressEvent) {
+        let mut f = self.0.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        f(event)
+    }
+}
+
+impl fmt::Debug for ProgressHook {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("ProgressHook")
+    }
+}
+
+// ============================================
+// Private Helper Functions
+// ============================================
+
+fn format_duration(duration: Duration) -> String {
+    let secs = duration.as_secs();
+    match (secs / 3600, secs / 60 % 60, secs % 60) {
+        (0, 0, s) => format!("{s}s"),
+        (0, m, s) => format!("{m}m{s:02}s"),
+        (h, m, _) => format!("{h}h{m:02}m"),
+    }
+}
+
+// ============================================
+// Unit Tests
+// ============================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{reconstruct_rows_with_config, ReconstructConfig, Stats};
+    use std::fs;
+    use std::sync::mpsc;
+
+    #[test]
+    fn test_progress_percentage_and_eta() {
+        let event = ProgressEvent {
+            rows: 4_500,
+            bytes_processed: 450,
+            total_bytes: Some(1_000),
+            elapsed: Duration::from_secs(65),
+        };
+        assert_eq!(event.to_string(), "45% — ~1m19s remaining");
+
+        let unknown = ProgressEvent { total_bytes: None, ..event };
+        assert_eq!(unknown.to_string(), "4500 rows");
+
+        // A plain closure keeping its own count
+        let (sender, receiver) = mpsc::channel();
+        let mut calls = 0;
+        let config = ReconstructConfig {
+            progress: Some(ProgressHook::new(Box::new(move |e: &ProgressEvent| {
+                calls += 1;
+                sender.send((calls, *e)).unwrap();
+            }))),
+            ..ReconstructConfig::default()
+        };
+        let input: String = std::iter::once("ID,Name\n".to_string())
+            .chain((0..PROGRESS_INTERVAL_ROWS).map(|i| format!("{i},x\n")))
+            .collect();
+        let filename = "test_progress_input.csv";
+        fs::write(filename, &input).unwrap();
+        reconstruct_rows_with_config(filename, &config, &mut Stats::default()).unwrap();
+        let _ = fs::remove_file(filename);
+
+        drop(config);
+        let events: Vec<(usize, ProgressEvent)> = receiver.iter().collect();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].0, 1);
+        assert_eq!(events[0].1.rows, PROGRESS_INTERVAL_ROWS);
+        assert_eq!(events[0].1.total_bytes, Some(input.len() as u64));
+    }
+}
@@ -0,0 +1,124 @@
+// src/reconcile.rs
+//! Row accounting reconciliation
+//!
+//! Proves that every physical row read was either written or discarded, so
+//! an audit can rely on the counts in [`Stats`].
+
+use crate::engine::Stats;
+use std::fmt;
+
+/// Balance of physical rows read against rows written and discarded
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ReconciliationReport {
+    /// Physical rows read (a detected leading header excluded)
+    pub rows_read: usize,
+    /// Blank lines skipped between records (not rows, listed for reference)
+    pub blank_lines: usize,
+    /// Physical rows that ended up in the output
+    pub rows_in_output: usize,
+    /// Physical rows belonging to discarded records
+    pub rows_discarded: usize,
+    /// Logical records discarded
+    pub records_discarded: usize,
+}
+
+impl ReconciliationReport {
+    /// Rows read minus rows accounted for; anything but 0 is an engine bug
+    pub fn discrepancy(&self) -> i64 {
+        self.rows_read as i64 - (self.rows_in_output + self.rows_discarded) as i64
+    }
+
+    /// True if every row read is accounted for
+    pub fn is_balanced(&self) -> bool {
+        self.discrepancy() == 0
+    }
+}
+
+impl Stats {
+    /// Check that the physical rows read balance against output and discards
+    pub fn reconcile(&self) -> ReconciliationReport {
+        ReconciliationReport {
+            rows_read: self.total_rows,
+            blank_lines: self.blank_lines,
+            rows_in_output: self.rows_in_output,
+            rows_discarded: self.rows_discarded,
+            records_discarded: self.removed_rows,
+        }
+    }
+}
+
+impl fmt::Display for ReconciliationReport {
+    /// Render as a balance sheet
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Physical rows read        {:>10}", self.rows_read)?;
+        writeln!(f, "  in output records      {:>10}", self.rows_in_output)?;
+        writeln!(f, "  in discarded records   {:>10}  ({} records)", self.rows_discarded, self.records_discarded)?;
+        writeln!(f, "Blank lines skipped       {:>10}", self.blank_lines)?;
+        if self.is_balanced() {
+            writeln!(f, "Balance                   {:>10}", "OK")
+        } else {
+            writeln!(f, "Balance                   {:>10}  (ENGINE BUG: rows unaccounted for)", self.discrepancy())
+        }
+    }
+}
+
+// ============================================
+// Unit Tests
+// ============================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::ReconstructConfig;
+    use crate::engine::{reconstruct_rows_from_reader, Delimiter, HeaderMode, OverflowStrategy};
+
+    fn reconcile(content: &str, config: &ReconstructConfig) -> ReconciliationReport {
+        let mut stats = Stats::default();
+        reconstruct_rows_from_reader(content.as_bytes(), config, &mut stats).unwrap();
+        stats.reconcile()
+    }
+
+    #[test]
+    fn test_reconciliation_balances_across_fixtures() {
+        let headed = ReconstructConfig::default();
+        let mut headerless = ReconstructConfig::new(HeaderMode::NoHeaders, Delimiter::Comma);
+        headerless.expected_columns = Some(3);
+        let mut split = headed.clone();
+        split.overflow_strategy = OverflowStrategy::SplitAtExpected;
+        let mut sections = headed.clone();
+        sections.multi_section = true;
+
+        let fixtures = [
+            ("ID,Name,Amount\n1,Tbilisi\nWaters,10\n\n2,Gori,20\n", &headed),
+            ("ID,Name,Amount\n1,a,b,c,d\n2,Gori\n3,x,y,z\n4,Kutaisi,40\n5,dangling\n", &headed),
+            ("1,Tbilisi\nWaters,10\n2,Gori,20\n", &headerless),
+            ("ID,Name,Amount\n1,a,10,2,b,20\n3,c,30\n", &split),
+            ("ID,Name,Amount\n1,a,10\n2,partial\n\nCode,Label\nX,y\n", &sections),
+        ];
+
+        for (content, config) in fixtures {
+            let report = reconcile(content, config);
+            assert!(report.is_balanced(), "unbalanced for {content:?}: {report:?}");
+        }
+
+        let report = reconcile(fixtures[1].0, fixtures[1].1);
+        assert_eq!(report.rows_read, 5);
+        assert_eq!(report.rows_in_output, 0);
+        assert_eq!(report.rows_discarded, 5);
+        assert_eq!(report.records_discarded, 4);
+    }
+
+    #[test]
+    fn test_inconsistent_counts_are_flagged() {
+        let stats = Stats {
+            total_rows: 10,
+            rows_in_output: 7,
+            rows_discarded: 2,
+            ..Stats::default()
+        };
+        let report = stats.reconcile();
+        assert!(!report.is_balanced());
+        assert_eq!(report.discrepancy(), 1);
+        assert!(report.to_string().contains("ENGINE BUG"));
+    }
+}
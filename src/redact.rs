@@ -0,0 +1,64 @@
+// src/redact.rs
+//! Column redaction
+//!
+//! Hides sensitive values on output while keeping the file's structure, so
+//! cleaned data can be shared with parties who must not see some columns.
+
+/// Token written in place of masked values
+pub const REDACTION_MASK: &str = "****";
+
+/// Number of trailing characters `RedactMode::Partial` leaves visible
+const PARTIAL_VISIBLE_CHARS: usize = 4;
+
+/// How a redacted column's values are replaced
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RedactMode {
+    /// Replace the value with [`REDACTION_MASK`]
+    Mask,
+    /// Replace the value with a 16-digit hex hash of the salt and value, so
+    /// equal values still match across files redacted with the same salt
+    ///
+    /// The hash (64-bit FNV-1a) is stable across runs and platforms but not
+    /// cryptographic; without a secret salt, short values can be recovered by
+    /// hashing candidates.
+    Hash,
+    /// Keep the last four characters behind [`REDACTION_MASK`], e.g.
+    /// `****1234`; values of four characters or fewer are masked completely
+    Partial,
+}
+
+impl RedactMode {
+    /// Redact one cleaned value; empty values stay empty
+    pub fn apply(&self, value: &str, salt: &str) -> String {
+        if value.is_empty() {
+            return String::new();
+        }
+        match self {
+            RedactMode::Mask => REDACTION_MASK.to_string(),
+            RedactMode::Hash => format!("{:016x}", salted_hash(value, salt)),
+            RedactMode::Partial => {
+                let length = value.chars().count();
+                if length <= PARTIAL_VISIBLE_CHARS {
+                    REDACTION_MASK.to_string()
+                } else {
+                    let tail: String = value.chars().skip(length - PARTIAL_VISIBLE_CHARS).collect();
+                    format!("{REDACTION_MASK}{tail}")
+                }
+            }
+        }
+    }
+}
+
+// ============================================
+// Private Helper Functions
+// ============================================
+
+/// 64-bit FNV-1a of `salt`, a separator byte and `value`
+fn salted_hash(value: &str, salt: &str) -> u64 {
+    const OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+    const PRIME: u64 = 0x0000_0100_0000_01b3;
+
+    // 0xFF never occurs in UTF-8, so ("ab", "c") and ("a", "bc") differ
+    let bytes = salt.bytes().chain(std::iter::once(0xFF)).chain(value.bytes());
+    bytes.fold(OFFSET_BASIS, |hash, byte| (hash ^ u64::from(byte)).wrapping_mul(PRIME))
+}
@@ -0,0 +1,121 @@
+// src/repl.rs
+//! Interactive "fix one record" mode
+//!
+//! Reads a few raw CSV lines from stdin, reconstructs them with the current
+//! settings and shows what happened to every line. Nothing touches the disk.
+
+use crate::{ui, Config};
+use fixerr::{reconstruct_from_lines, HeaderMode, ReconstructConfig, ReconstructedRow, Stats};
+use std::error::Error;
+use std::io::{self, BufRead, Write};
+
+/// Run the REPL until stdin is closed
+pub fn run(config: &Config) -> Result<(), Box<dyn Error>> {
+    println!("Fixerr REPL - paste CSV lines, finish with a blank line (Ctrl-D to quit)");
+    println!("Delimiter: {:?}, Header Mode: {:?}\n", config.delimiter, config.header_mode);
+
+    let mut engine_config = ReconstructConfig::new(config.header_mode, config.delimiter);
+    engine_config.known_header_names = config.known_header_names.clone();
+    if matches!(config.header_mode, HeaderMode::NoHeaders) {
+        engine_config.expected_columns = Some(ui::get_column_count()?);
+    }
+
+    let stdin = io::stdin();
+    let mut input = stdin.lock();
+    loop {
+        let lines = read_snippet(&mut input)?;
+        if lines.is_empty() {
+            return Ok(());
+        }
+
+        let mut stats = Stats::default();
+        match reconstruct_from_lines(&lines, &engine_config, &mut stats) {
+            Ok(rows) => {
+                println!("\nRecords:");
+                for (index, row) in rows.iter().enumerate() {
+                    let fields: Vec<&str> = row.record.iter().collect();
+                    println!("  {}: {:?}", index + 1, fields);
+                }
+                println!("\nDecisions:");
+                for (line, decision) in lines.iter().zip(describe_lines(&rows, lines.len())) {
+                    println!("  {line:<40} -> {decision}");
+                }
+                for warning in &stats.warnings {
+                    println!("  ⚠️  {warning}");
+                }
+            }
+            Err(e) => println!("❌ {e}"),
+        }
+        println!();
+    }
+}
+
+/// Read lines up to a blank line; empty result means stdin was closed
+fn read_snippet<R: BufRead>(input: &mut R) -> io::Result<Vec<String>> {
+    let mut lines = Vec::new();
+    loop {
+        print!("{}", if lines.is_empty() { "csv> " } else { "...> " });
+        io::stdout().flush()?;
+
+        let mut line = String::new();
+        if input.read_line(&mut line)? == 0 {
+            return Ok(lines);
+        }
+        let line = line.trim_end_matches(['\r', '\n']);
+        if line.is_empty() {
+            if lines.is_empty() {
+                continue;
+            }
+            return Ok(lines);
+        }
+        lines.push(line.to_string());
+    }
+}
+
+/// What became of each input line (1-based line numbers)
+fn describe_lines(rows: &[ReconstructedRow], line_count: usize) -> Vec<String> {
+    (1..=line_count as u64)
+        .map(|line| {
+            let found = rows
+                .iter()
+                .enumerate()
+                .find_map(|(index, row)| row.source_lines.iter().position(|&l| l == line).map(|part| (index, row, part)));
+            match found {
+                Some((_, row, _)) if row.header => "header".to_string(),
+                Some((index, row, part)) if row.source_lines.len() > 1 => {
+                    format!("stitched into record {} (part {} of {})", index + 1, part + 1, row.source_lines.len())
+                }
+                Some((index, row, _)) if row.fixed => format!("repaired into record {}", index + 1),
+                Some((index, _, _)) => format!("record {}", index + 1),
+                None => "discarded, or inside a quoted field".to_string(),
+            }
+        })
+        .collect()
+}
+
+// ============================================
+// Unit Tests
+// ============================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_describe_lines_reports_each_decision() {
+        let lines = ["ID,Name,Amount", "1,Tbilisi", "Waters,10", "2,a,b,c", "3,Gori,30"];
+        let mut stats = Stats::default();
+        let rows = reconstruct_from_lines(&lines, &ReconstructConfig::default(), &mut stats).unwrap();
+
+        assert_eq!(
+            describe_lines(&rows, lines.len()),
+            vec![
+                "header",
+                "stitched into record 2 (part 1 of 2)",
+                "stitched into record 2 (part 2 of 2)",
+                "discarded, or inside a quoted field",
+                "record 3",
+            ]
+        );
+    }
+}
@@ -0,0 +1,197 @@
+// src/retry.rs
+//! Retry with exponential backoff for transient IO errors
+//!
+//! Networked filesystems (NFS, S3-fuse) occasionally fail a read or write
+//! that succeeds when repeated. Permanent conditions such as a missing file
+//! or denied permission are never retried.
+
+use crate::error::FixerrError;
+use std::io::{self, Read, Write};
+use std::thread;
+use std::time::Duration;
+
+/// How often and how patiently to retry a failing IO operation
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// Retries after the first failure; 0 disables retrying (default)
+    pub max_retries: usize,
+    /// Delay before the first retry; doubled before every further retry
+    pub backoff: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: 0,
+            backoff: Duration::from_millis(100),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Run `op`, retrying retryable failures according to the policy
+    pub fn run<T>(&self, mut op: impl FnMut() -> io::Result<T>) -> Result<T, FixerrError> {
+        let mut attempt = 0;
+        loop {
+            match op() {
+                Ok(value) => return Ok(value),
+                Err(e) if is_retryable(&e) && attempt < self.max_retries => {
+                    thread::sleep(self.delay(attempt));
+                    attempt += 1;
+                }
+                Err(source) => {
+                    return Err(FixerrError::IoAfterRetries {
+                        attempts: attempt + 1,
+                        source,
+                    })
+                }
+            }
+        }
+    }
+
+    /// Delay before retry number `attempt` (0-based)
+    fn delay(&self, attempt: usize) -> Duration {
+        let factor = 1u32.checked_shl(attempt as u32).unwrap_or(u32::MAX);
+        self.backoff.saturating_mul(factor)
+    }
+
+    /// Like [`run`](Self::run), but a failure that was never retried is
+    /// reported as a plain [`FixerrError::Io`]
+    pub(crate) fn run_unwrapped<T>(&self, op: impl FnMut() -> io::Result<T>) -> Result<T, FixerrError> {
+        self.run(op).map_err(|e| match e {
+            FixerrError::IoAfterRetries { attempts: 1, source } => FixerrError::Io(source),
+            e => e,
+        })
+    }
+
+    /// Like [`run`](Self::run), but reporting failures as `io::Error`
+    fn run_io<T>(&self, op: impl FnMut() -> io::Result<T>) -> io::Result<T> {
+        self.run(op).map_err(|e| match e {
+            FixerrError::IoAfterRetries { attempts: 1, source } => source,
+            e => io::Error::new(io::ErrorKind::Other, e),
+        })
+    }
+}
+
+/// Whether an IO error may be transient and worth retrying
+pub fn is_retryable(error: &io::Error) -> bool {
+    !matches!(
+        error.kind(),
+        io::ErrorKind::NotFound
+            | io::ErrorKind::PermissionDenied
+            | io::ErrorKind::AlreadyExists
+            | io::ErrorKind::InvalidInput
+            | io::ErrorKind::InvalidData
+            | io::ErrorKind::UnexpectedEof
+            | io::ErrorKind::Unsupported
+            | io::ErrorKind::OutOfMemory
+    )
+}
+
+/// A `Read` adapter that retries transient read errors
+pub struct RetryingReader<R: Read> {
+    inner: R,
+    policy: RetryPolicy,
+}
+
+impl<R: Read> RetryingReader<R> {
+    /// Wrap `inner` with the given retry policy
+    pub fn new(inner: R, policy: RetryPolicy) -> Self {
+        Self { inner, policy }
+    }
+}
+
+impl<R: Read> Read for RetryingReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let inner = &mut self.inner;
+        self.policy.run_io(|| inner.read(buf))
+    }
+}
+
+/// A `Write` adapter that retries transient write errors
+pub struct RetryingWriter<W: Write> {
+    inner: W,
+    policy: RetryPolicy,
+}
+
+impl<W: Write> RetryingWriter<W> {
+    /// Wrap `inner` with the given retry policy
+    pub fn new(inner: W, policy: RetryPolicy) -> Self {
+        Self { inner, policy }
+    }
+}
+
+impl<W: Write> Write for RetryingWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let inner = &mut self.inner;
+        self.policy.run_io(|| inner.write(buf))
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        let inner = &mut self.inner;
+        self.policy.run_io(|| inner.flush())
+    }
+}
+
+// ============================================
+// Unit Tests
+// ============================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Reader that fails with a transient error a fixed number of times
+    struct FlakyReader {
+        failures_left: usize,
+        data: &'static [u8],
+    }
+
+    impl Read for FlakyReader {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            if self.failures_left > 0 {
+                self.failures_left -= 1;
+                return Err(io::Error::new(io::ErrorKind::TimedOut, "transient"));
+            }
+            self.data.read(buf)
+        }
+    }
+
+    fn policy(max_retries: usize) -> RetryPolicy {
+        RetryPolicy {
+            max_retries,
+            backoff: Duration::from_millis(1),
+        }
+    }
+
+    #[test]
+    fn test_retrying_reader_recovers_from_transient_failures() {
+        let flaky = FlakyReader { failures_left: 2, data: b"ID,Amount\n1,10\n" };
+        let mut content = String::new();
+        RetryingReader::new(flaky, policy(3)).read_to_string(&mut content).unwrap();
+        assert_eq!(content, "ID,Amount\n1,10\n");
+    }
+
+    #[test]
+    fn test_retries_exhausted_reports_attempts() {
+        let mut calls = 0;
+        let err = policy(2)
+            .run(|| -> io::Result<()> {
+                calls += 1;
+                Err(io::Error::new(io::ErrorKind::TimedOut, "transient"))
+            })
+            .unwrap_err();
+        assert_eq!(calls, 3);
+        assert!(matches!(err, FixerrError::IoAfterRetries { attempts: 3, .. }));
+    }
+
+    #[test]
+    fn test_permanent_errors_are_not_retried() {
+        let mut calls = 0;
+        let _ = policy(5).run(|| -> io::Result<()> {
+            calls += 1;
+            Err(io::Error::new(io::ErrorKind::NotFound, "missing"))
+        });
+        assert_eq!(calls, 1);
+    }
+}
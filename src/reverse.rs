@@ -0,0 +1,94 @@
+// src/reverse.rs
+//! Reverse-order reconstruction
+//!
+//! Some exports reliably end every record cleanly but may start with orphaned
+//! fragments. Stitching from the last physical row backwards anchors each
+//! record on its end, so leading junk is left over instead of being glued to
+//! the first real record.
+
+use crate::engine::{physical_start_line, skip_blank_record, ReconstructedRow, Stats};
+use crate::error::FixerrError;
+use crate::warning::Warning;
+use csv::{Reader, StringRecord};
+use std::io::Read;
+
+/// Reconstruct the remaining rows of `reader` by stitching from the end
+///
+/// Reads every physical row into memory first, so this cannot stream. Rows
+/// wider than `expected_columns` are discarded, as is any incomplete group
+/// left at the start of the input. Fragments are joined with `separator`.
+/// Records are returned in file order.
+pub(crate) fn reconstruct_reversed<R: Read>(
+    reader: &mut Reader<R>,
+    expected_columns: usize,
+    separator: &str,
+    stats: &mut Stats,
+) -> Result<Vec<ReconstructedRow>, FixerrError> {
+    let mut physical: Vec<(StringRecord, u64)> = Vec::new();
+    let mut next = StringRecord::new();
+    while reader.read_record(&mut next)? {
+        if skip_blank_record(&next, reader.position().line(), stats) {
+            continue;
+        }
+        let record = std::mem::take(&mut next);
+        let parse_line = record.position().map_or(0, |p| p.line());
+        let line = physical_start_line(&record, parse_line, reader.position().line());
+        stats.blank_lines += (line - parse_line) as usize;
+        physical.push((record, line));
+    }
+    stats.total_rows += physical.len();
+
+    let mut logical_rows: Vec<ReconstructedRow> = Vec::new();
+    // Fields and start lines of the group being built, both in file order
+    let mut buffer: Vec<String> = Vec::new();
+    let mut buffer_lines: Vec<u64> = Vec::new();
+
+    for (record, line) in physical.into_iter().rev() {
+        if record.len() > expected_columns {
+            stats.warnings.push(Warning::OverLengthRow { line, got: record.len(), expected: expected_columns });
+            stats.removed_rows += 1;
+            stats.discard_lines(&[line]);
+            continue;
+        }
+
+        if buffer.is_empty() && record.len() == expected_columns {
+            logical_rows.push(ReconstructedRow::clean(record, vec![line]));
+            continue;
+        }
+
+        // Prepend the earlier fragment, joining its last field to our first
+        let mut fields: Vec<String> = record.iter().map(str::to_string).collect();
+        if let (Some(last), false) = (fields.last_mut(), buffer.is_empty()) {
+            let continuation = buffer.remove(0);
+            if !last.is_empty() {
+                last.push_str(separator);
+            }
+            last.push_str(&continuation);
+        }
+        fields.append(&mut buffer);
+        buffer = fields;
+        buffer_lines.insert(0, line);
+
+        if buffer.len() == expected_columns {
+            let lines = std::mem::take(&mut buffer_lines);
+            logical_rows.push(ReconstructedRow::fixed(StringRecord::from(std::mem::take(&mut buffer)), lines));
+            stats.fixed_rows += 1;
+        } else if buffer.len() > expected_columns {
+            stats.warnings.push(Warning::OverLengthRow { line: buffer_lines[0], got: buffer.len(), expected: expected_columns });
+            stats.removed_rows += 1;
+            stats.discard_lines(&buffer_lines);
+            buffer.clear();
+            buffer_lines.clear();
+        }
+    }
+
+    // Leading fragments that never completed a record
+    if !buffer.is_empty() {
+        stats.warnings.push(Warning::TrailingIncompleteRow { line: buffer_lines[0] });
+        stats.removed_rows += 1;
+        stats.discard_lines(&buffer_lines);
+    }
+
+    logical_rows.reverse();
+    Ok(logical_rows)
+}
@@ -0,0 +1,85 @@
+// src/sample.rs
+//! Uniform random sampling of reconstructed records
+//!
+//! Spot-checking a huge file does not require reading every record: a
+//! reservoir sample gives a representative slice in a single pass.
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Pick `n` items uniformly at random from `items` (reservoir sampling)
+///
+/// Consumes the iterator in one pass while holding at most `n` items, so the
+/// input never has to be collected. Items are returned with their 0-based
+/// position, ordered by position. The same `seed` gives the same sample.
+pub fn reservoir_sample<T, I>(items: I, n: usize, seed: u64) -> Vec<(usize, T)>
+where
+    I: IntoIterator<Item = T>,
+{
+    let mut rng = SplitMix64(seed);
+    let mut reservoir: Vec<(usize, T)> = Vec::with_capacity(n);
+
+    for (index, item) in items.into_iter().enumerate() {
+        if reservoir.len() < n {
+            reservoir.push((index, item));
+        } else if n > 0 {
+            let slot = rng.below(index as u64 + 1) as usize;
+            if slot < n {
+                reservoir[slot] = (index, item);
+            }
+        }
+    }
+
+    reservoir.sort_by_key(|(index, _)| *index);
+    reservoir
+}
+
+/// A seed derived from the current time
+pub(crate) fn time_seed() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_nanos() as u64)
+}
+
+// ============================================
+// Private Helper Functions
+// ============================================
+
+/// Small, dependency-free PRNG; statistical quality is ample for sampling
+struct SplitMix64(u64);
+
+impl SplitMix64 {
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Uniform value in `0..bound`
+    fn below(&mut self, bound: u64) -> u64 {
+        ((self.next() as u128 * bound as u128) >> 64) as u64
+    }
+}
+
+// ============================================
+// Unit Tests
+// ============================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reservoir_sample_takes_exactly_n_in_one_pass() {
+        // A lazy iterator: the full input is never materialized
+        let sample = reservoir_sample((0..100_000).map(|i| i * 2), 10, 42);
+
+        assert_eq!(sample.len(), 10);
+        assert!(sample.windows(2).all(|w| w[0].0 < w[1].0));
+        assert!(sample.iter().all(|(index, value)| *value == index * 2));
+        assert_eq!(sample, reservoir_sample((0..100_000).map(|i| i * 2), 10, 42));
+
+        assert_eq!(reservoir_sample(0..3, 10, 7).len(), 3);
+    }
+}
@@ -0,0 +1,106 @@
+// src/scrub.rs
+//! Removal of junk bytes before the first record
+//!
+//! Some exports start with a byte-order mark, a truncated one, a stray NUL or
+//! a terminal escape sequence ahead of the real header. Left in place they
+//! become part of the first header cell.
+
+use std::io::{self, Chain, Cursor, Read};
+
+/// Most leading bytes ever considered junk; anything longer is treated as data
+const MAX_SCRUBBED_BYTES: usize = 16;
+
+const UTF8_BOM: &[u8] = b"\xEF\xBB\xBF";
+
+/// The remaining sample bytes followed by the rest of the source
+pub(crate) type Scrubbed<R> = Chain<Cursor<Vec<u8>>, R>;
+
+/// Strip junk bytes from the start of `source`
+///
+/// Returns the number of bytes removed together with a reader over the rest.
+/// Only byte-order marks, non-whitespace control bytes, ANSI escape sequences
+/// and bytes that cannot start valid UTF-8 are removed, and at most
+/// [`MAX_SCRUBBED_BYTES`] of them; printable text is never touched.
+pub(crate) fn scrub_leading_junk<R: Read>(mut source: R) -> io::Result<(usize, Scrubbed<R>)> {
+    let mut prefix = Vec::with_capacity(MAX_SCRUBBED_BYTES + UTF8_BOM.len());
+    (&mut source)
+        .take((MAX_SCRUBBED_BYTES + UTF8_BOM.len()) as u64)
+        .read_to_end(&mut prefix)?;
+
+    let stripped = junk_length(&prefix);
+    let mut rest = Cursor::new(prefix);
+    rest.set_position(stripped as u64);
+    Ok((stripped, rest.chain(source)))
+}
+
+// ============================================
+// Private Helper Functions
+// ============================================
+
+fn junk_length(bytes: &[u8]) -> usize {
+    let mut pos = 0;
+    while pos < MAX_SCRUBBED_BYTES && pos < bytes.len() {
+        let rest = &bytes[pos..];
+        let skip = if rest.starts_with(UTF8_BOM) {
+            UTF8_BOM.len()
+        } else if let Some(len) = escape_sequence_length(rest) {
+            len
+        } else if is_junk_byte(rest) {
+            1
+        } else {
+            break;
+        };
+        if pos + skip > MAX_SCRUBBED_BYTES {
+            break;
+        }
+        pos += skip;
+    }
+    pos
+}
+
+/// Length of an ANSI CSI sequence such as `ESC [ 0 m`, if `bytes` starts with one
+fn escape_sequence_length(bytes: &[u8]) -> Option<usize> {
+    if !bytes.starts_with(b"\x1B[") {
+        return None;
+    }
+    let params = bytes[2..]
+        .iter()
+        .take_while(|b| b.is_ascii_digit() || **b == b';')
+        .count();
+    match bytes.get(2 + params) {
+        Some(b) if b.is_ascii_alphabetic() => Some(3 + params),
+        _ => None,
+    }
+}
+
+/// Control bytes other than whitespace, and bytes that don't begin valid UTF-8
+fn is_junk_byte(bytes: &[u8]) -> bool {
+    let first = bytes[0];
+    if first.is_ascii() {
+        return first.is_ascii_control() && !matches!(first, b'\t' | b'\n' | b'\r');
+    }
+    // A multi-byte character cut off by the end of the sample is kept
+    match std::str::from_utf8(&bytes[..bytes.len().min(4)]) {
+        Ok(_) => false,
+        Err(e) => e.valid_up_to() == 0 && e.error_len().is_some(),
+    }
+}
+
+// ============================================
+// Unit Tests
+// ============================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_junk_length_is_conservative() {
+        assert_eq!(junk_length(b"\x00ID,Name"), 1);
+        assert_eq!(junk_length(b"\xEF\xBB\xBF\x1B[0mID,Name"), 7);
+        assert_eq!(junk_length(b"\xEF\xBBID,Name"), 2);
+        assert_eq!(junk_length("ქართული,Name".as_bytes()), 0);
+        assert_eq!(junk_length(b"\tID,Name"), 0);
+        assert_eq!(junk_length(b"ID,Name"), 0);
+    }
+}
@@ -0,0 +1,100 @@
+// src/sort.rs
+//! Sorting of reconstructed records by a key column
+//!
+//! An in-memory post-processing step, so downstream loaders receive records
+//! in key order.
+
+use crate::engine::{clean_and_normalize_field, ReconstructedRow};
+use crate::numeric::canonicalize_number;
+use std::cmp::Ordering;
+
+/// Direction of the sort
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SortOrder {
+    /// Smallest key first (default)
+    #[default]
+    Ascending,
+    /// Largest key first
+    Descending,
+}
+
+/// How key values are compared
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SortComparison {
+    /// Compare as strings (default)
+    #[default]
+    Lexical,
+    /// Compare as numbers in either decimal convention; values that are not
+    /// numbers sort after all numbers, in their original order
+    Numeric,
+}
+
+/// Sort records by the value of column `key`
+///
+/// Header rows stay in place and records are only sorted between them, so
+/// each section keeps its own header. The sort is stable.
+pub fn sort_rows(rows: &mut [ReconstructedRow], key: usize, order: SortOrder, comparison: SortComparison) {
+    for run in rows.split_mut(|row| row.header) {
+        match comparison {
+            SortComparison::Lexical => run.sort_by(|a, b| {
+                let ordering = key_text(a, key).cmp(&key_text(b, key));
+                directed(ordering, order)
+            }),
+            SortComparison::Numeric => run.sort_by(|a, b| match (key_number(a, key), key_number(b, key)) {
+                (Some(x), Some(y)) => directed(x.partial_cmp(&y).unwrap_or(Ordering::Equal), order),
+                (Some(_), None) => Ordering::Less,
+                (None, Some(_)) => Ordering::Greater,
+                (None, None) => Ordering::Equal,
+            }),
+        }
+    }
+}
+
+// ============================================
+// Private Helper Functions
+// ============================================
+
+fn key_text(row: &ReconstructedRow, key: usize) -> String {
+    clean_and_normalize_field(row.record.get(key).unwrap_or(""))
+}
+
+fn key_number(row: &ReconstructedRow, key: usize) -> Option<f64> {
+    canonicalize_number(&key_text(row, key)).and_then(|value| value.parse().ok())
+}
+
+fn directed(ordering: Ordering, order: SortOrder) -> Ordering {
+    match order {
+        SortOrder::Ascending => ordering,
+        SortOrder::Descending => ordering.reverse(),
+    }
+}
+
+// ============================================
+// Unit Tests
+// ============================================
+
+#[cfg(test)]
+mod tests {
+    use crate::{reconstruct_rows_from_reader, ReconstructConfig, SortComparison, SortOrder, Stats};
+
+    #[test]
+    fn test_numeric_sort_keeps_header_and_puts_outliers_last() {
+        let input = "ID,Name\n10,Gori\nn/a,Unknown\n9,Batumi\n100,Tbilisi";
+        let ids = |order: SortOrder, comparison: SortComparison| -> Vec<String> {
+            let config = ReconstructConfig {
+                sort_by: Some((0, order)),
+                sort_comparison: comparison,
+                ..ReconstructConfig::default()
+            };
+            reconstruct_rows_from_reader(input.as_bytes(), &config, &mut Stats::default())
+                .unwrap()
+                .iter()
+                .map(|row| row.record.get(0).unwrap().to_string())
+                .collect()
+        };
+
+        assert_eq!(ids(SortOrder::Ascending, SortComparison::Numeric), ["ID", "9", "10", "100", "n/a"]);
+        assert_eq!(ids(SortOrder::Descending, SortComparison::Numeric), ["ID", "100", "10", "9", "n/a"]);
+        assert_eq!(ids(SortOrder::Ascending, SortComparison::Lexical), ["ID", "10", "100", "9", "n/a"]);
+    }
+}
@@ -0,0 +1,543 @@
+// src/stitch.rs
+//! Record-at-a-time reconstruction core
+//!
+//! [`RowSource`] reads the header and then one physical row at a time, and
+//! [`Stitcher`] turns those rows into logical records, keeping the partial
+//! record between calls. The in-memory engine and the streaming
+//! [`Reconstructor`](crate::Reconstructor) both drive these two, so a file is
+//! repaired the same way whichever API reads it.
+
+use crate::config::{ReconstructConfig, RunawayFieldPolicy};
+use crate::detect::{rank_delimiters, vote_in_sample, QUOTE_SAMPLE_BYTES};
+use crate::engine::{
+    build_quoted_reader, check_expected_headers, combine_header_rows, detect_column_count, looks_like_section_header,
+    matches_known_header_names, merge_decimal_commas, merge_enum_split, merge_surplus_into, physical_start_line,
+    quoted_fields, reference_width, restitch_without_boundary_empties, runaway_field, skip_blank_record,
+    ColumnCountStrategy, Delimiter, HeaderMode, OverflowStrategy, QuoteTracker, ReconstructedRow, Stats,
+};
+use crate::error::FixerrError;
+use crate::progress::{ProgressEvent, ProgressHook, PROGRESS_INTERVAL_ROWS};
+use crate::warning::Warning;
+use csv::{Reader, StringRecord};
+use std::io::Read;
+use std::time::Instant;
+
+/// Delimiter and quote byte for `config`, detected from `sample` where set to auto
+pub(crate) fn resolve_dialect(sample: &[u8], config: &ReconstructConfig, stats: &mut Stats) -> (Delimiter, u8) {
+    let delimiter = match config.delimiter {
+        Delimiter::Auto => {
+            let (delimiter, tied) = rank_delimiters(sample);
+            if tied.len() > 1 {
+                stats.warnings.push(Warning::AmbiguousDelimiter { chosen: delimiter, tied });
+            }
+            delimiter
+        }
+        delimiter => delimiter,
+    };
+    (delimiter, config.quote.resolve(sample, delimiter))
+}
+
+/// Record width chosen by `column_count_strategy`, if it picks one up front
+///
+/// `Quantile` needs every row and is left to the caller.
+pub(crate) fn strategy_width(
+    config: &ReconstructConfig,
+    quote: u8,
+    sample: &[u8],
+) -> Result<Option<usize>, FixerrError> {
+    match &config.column_count_strategy {
+        ColumnCountStrategy::Detect | ColumnCountStrategy::Quantile(_) => Ok(None),
+        ColumnCountStrategy::FromReferenceFile(path) => reference_width(path, config, quote).map(Some),
+        ColumnCountStrategy::MajorityVote => {
+            let truncated = sample.len() == QUOTE_SAMPLE_BYTES;
+            let vote = vote_in_sample(sample, config.header_mode, config.delimiter, quote, config.comment, truncated)?;
+            Ok(vote.map(|vote| vote.columns))
+        }
+    }
+}
+
+/// One physical row as read from the input
+pub(crate) struct PhysicalRow {
+    pub(crate) record: StringRecord,
+    /// Line the row starts on
+    pub(crate) line: u64,
+    /// True if blank lines were skipped right before the row
+    pub(crate) after_blank_line: bool,
+    /// Raw input bytes of the row; empty unless captured
+    pub(crate) raw: Vec<u8>,
+}
+
+/// The header and the physical rows of an input
+pub(crate) struct RowSource<R: Read> {
+    reader: Reader<QuoteTracker<R>>,
+    /// Width of a complete record, from the header or the configuration
+    pub(crate) expected_columns: usize,
+    header: Option<ReconstructedRow>,
+    /// First line of the header, if there is one
+    pub(crate) header_line: Option<u64>,
+    size: Option<u64>,
+    progress: Option<ProgressHook>,
+    started: Instant,
+}
+
+impl<R: Read> RowSource<R> {
+    /// Read the header of `source` and work out the record width
+    ///
+    /// `width_override` is a width chosen by the column count strategy; it
+    /// replaces the header width. `size` is the input size for progress events.
+    pub(crate) fn open(
+        source: R,
+        config: &ReconstructConfig,
+        quote: u8,
+        width_override: Option<usize>,
+        size: Option<u64>,
+    ) -> Result<Self, FixerrError> {
+        let header_mode = config.header_mode;
+        let mut source = QuoteTracker::new(source, quote, config.delimiter.as_byte(), config.comment);
+        if (config.rejects_path.is_some() || config.preserve_quoting) && !config.reverse {
+            source.capture_raw();
+        }
+        let mut reader = build_quoted_reader(source, header_mode, config.delimiter, quote, config.comment);
+
+        // Detect expected column count
+        let (mut expected_columns, maybe_headers) = match (width_override, config.expected_columns, header_mode) {
+            (Some(columns), _, HeaderMode::HasHeaders) => (columns, Some(reader.headers()?.clone())),
+            (Some(columns), _, HeaderMode::NoHeaders) | (None, Some(columns), HeaderMode::NoHeaders) => (columns, None),
+            _ => detect_column_count(&mut reader, header_mode)?,
+        };
+
+        // A header spread over several rows is read as one combined header
+        let mut header_lines = Vec::new();
+        let maybe_headers = match maybe_headers {
+            Some(first) if config.header_rows > 1 => {
+                header_lines.push(first.position().map_or(1, |p| p.line()));
+                let mut parts = vec![first];
+                let mut row = StringRecord::new();
+                while parts.len() < config.header_rows && reader.read_record(&mut row)? {
+                    header_lines.push(row.position().map_or(0, |p| p.line()));
+                    parts.push(std::mem::take(&mut row));
+                }
+                let combined = combine_header_rows(&parts, &config.header_row_separator);
+                if width_override.is_none() {
+                    expected_columns = combined.len();
+                }
+                Some(combined)
+            }
+            other => other,
+        };
+
+        // The header is read during detection, so it is not part of `stats.total_rows`
+        let mut header = None;
+        if let Some(h) = maybe_headers {
+            if let Some(expected) = &config.expect_headers {
+                check_expected_headers(&h, expected, config.allow_reorder)?;
+            }
+            let line = h.position().map_or(1, |p| p.line());
+            if header_lines.is_empty() {
+                header_lines.push(line);
+            }
+            let mut row = ReconstructedRow::header(h, header_lines);
+            if config.preserve_quoting && row.source_lines.len() == 1 {
+                let start = row.record.position().map_or(0, |p| p.byte());
+                let raw = reader.get_ref().raw_line(start, reader.position().byte());
+                row.quoted = quoted_fields(&raw, row.record.len(), config.delimiter.as_byte(), quote);
+            }
+            header = Some(row);
+        }
+
+        Ok(Self {
+            reader,
+            expected_columns,
+            header_line: header.as_ref().map(|row| row.source_lines[0]),
+            header,
+            size,
+            progress: config.progress.clone(),
+            started: Instant::now(),
+        })
+    }
+
+    /// The header row, the first time this is called
+    pub(crate) fn take_header(&mut self) -> Option<ReconstructedRow> {
+        self.header.take()
+    }
+
+    /// The underlying reader, for reverse mode which reads everything at once
+    pub(crate) fn reader_mut(&mut self) -> &mut Reader<QuoteTracker<R>> {
+        &mut self.reader
+    }
+
+    /// Next non-blank physical row, counting it and reporting progress
+    ///
+    /// With `release`, captured raw bytes before the row are dropped; pass it
+    /// whenever no partial record that may still be rejected is buffered.
+    pub(crate) fn next_row(&mut self, release: bool, stats: &mut Stats) -> Result<Option<PhysicalRow>, FixerrError> {
+        let mut record = StringRecord::new();
+        loop {
+            if release {
+                let consumed = self.reader.position().byte();
+                self.reader.get_mut().release_raw(consumed);
+            }
+            if !self.reader.read_record(&mut record)? {
+                return Ok(None);
+            }
+            if !skip_blank_record(&record, self.reader.position().line(), stats) {
+                break;
+            }
+        }
+
+        stats.total_rows += 1;
+        if let Some(progress) = &self.progress {
+            if stats.total_rows % PROGRESS_INTERVAL_ROWS == 0 {
+                progress.report(&ProgressEvent {
+                    rows: stats.total_rows,
+                    bytes_processed: self.reader.position().byte(),
+                    total_bytes: self.size,
+                    elapsed: self.started.elapsed(),
+                });
+            }
+        }
+        let raw = self
+            .reader
+            .get_ref()
+            .raw_line(record.position().map_or(0, |p| p.byte()), self.reader.position().byte());
+
+        // The reader reports where parsing began, which includes any blank
+        // lines it skipped; recover the line the row itself starts on.
+        let parse_line = record.position().map_or(0, |p| p.line());
+        let line = physical_start_line(&record, parse_line, self.reader.position().line());
+        stats.blank_lines += (line - parse_line) as usize;
+
+        Ok(Some(PhysicalRow { record, line, after_blank_line: line > parse_line, raw }))
+    }
+
+    /// Record the line endings seen; true if the input ended inside a quoted field
+    pub(crate) fn finish(&self, stats: &mut Stats) -> bool {
+        self.reader.get_ref().record_line_endings(stats);
+        self.reader.get_ref().unbalanced()
+    }
+}
+
+/// Stitching state carried from one physical row to the next
+pub(crate) struct Stitcher {
+    quote: u8,
+    expected_columns: usize,
+    /// Sections started so far (always 0 unless `multi_section` is enabled)
+    section: usize,
+    /// Fields of the partial record being stitched
+    buffer: Vec<String>,
+    buffer_lines: Vec<u64>,
+    /// Raw input bytes of the buffered rows, kept for the rejects file
+    buffer_raw: Vec<u8>,
+    /// Physical records of the buffered rows, kept when `collect_discarded` is set
+    buffer_records: Vec<StringRecord>,
+    /// Physical records of the buffered rows, kept for `stitch_overshoot_tolerance`
+    buffer_fragments: Vec<StringRecord>,
+    /// Line of the last physical row read, which holds any quote left open at EOF
+    last_line: u64,
+}
+
+impl Stitcher {
+    pub(crate) fn new(quote: u8, expected_columns: usize, header_line: Option<u64>) -> Self {
+        Self {
+            quote,
+            expected_columns,
+            section: 0,
+            buffer: Vec::new(),
+            buffer_lines: Vec::new(),
+            buffer_raw: Vec::new(),
+            buffer_records: Vec::new(),
+            buffer_fragments: Vec::new(),
+            last_line: header_line.unwrap_or(0),
+        }
+    }
+
+    /// True while a partial record is buffered
+    pub(crate) fn is_buffering(&self) -> bool {
+        !self.buffer.is_empty()
+    }
+
+    /// Current record width; a new section may change it
+    pub(crate) fn expected_columns(&self) -> usize {
+        self.expected_columns
+    }
+
+    /// Feed one physical row, appending any records it completes to `out`
+    pub(crate) fn push(
+        &mut self,
+        row: PhysicalRow,
+        config: &ReconstructConfig,
+        stats: &mut Stats,
+        out: &mut Vec<ReconstructedRow>,
+    ) {
+        let PhysicalRow { record, line, after_blank_line, raw } = row;
+        let rec_len = record.len();
+        let expected_columns = self.expected_columns;
+        self.last_line = line;
+
+        // A headerless file whose first row consists of known column names was
+        // probably misconfigured; processing it would shift every record.
+        if stats.total_rows == 1
+            && matches!(config.header_mode, HeaderMode::NoHeaders)
+            && matches_known_header_names(&record, &config.known_header_names)
+        {
+            stats.warnings.push(Warning::PossibleHeaderRow { line });
+        }
+
+        // Section boundary: a header-like row following one or more blank lines
+        // resets the expected width for the records that follow.
+        if config.multi_section && after_blank_line && looks_like_section_header(&record) {
+            if self.is_buffering() {
+                stats.warnings.push(Warning::TrailingIncompleteRow { line: self.buffer_lines[0] });
+                self.discard_buffer(stats);
+            }
+            self.section += 1;
+            self.expected_columns = rec_len;
+            self.emit(out, ReconstructedRow::header(record, vec![line]));
+            return;
+        }
+
+        // Check: Immediate Over-Length Check
+        //
+        // If the physical row itself has more columns than expected, it is
+        // statistically impossible for it to be a valid part of a split record
+        // (which should be shorter) or a valid full record. Discard immediately.
+        if rec_len > expected_columns {
+            // Recovery: a stray delimiter split a value of a known enumerated
+            // column; merging the pieces back yields one of its valid values.
+            if !self.is_buffering() && !config.enum_columns.is_empty() {
+                if let Some(merged) = merge_enum_split(&record, expected_columns, &config.enum_columns, config.delimiter) {
+                    self.emit(out, ReconstructedRow::fixed(merged, vec![line]));
+                    stats.fixed_rows += 1;
+                    return;
+                }
+            }
+
+            // Recovery: unquoted decimal commas split numbers into two fields
+            if !self.is_buffering() && config.repair_decimal_comma && config.delimiter == Delimiter::Comma {
+                if let Some(merged) = merge_decimal_commas(&record, expected_columns, config.decimal_comma_to_point) {
+                    self.emit(out, ReconstructedRow::fixed(merged, vec![line]));
+                    stats.fixed_rows += 1;
+                    return;
+                }
+            }
+
+            // Recovery: a clean multiple of the expected width is most likely
+            // several complete records whose separating newline was lost.
+            // Like the recoveries above, only outside a buffered row, so the
+            // output stays in source order.
+            if !self.is_buffering()
+                && config.overflow_strategy == OverflowStrategy::SplitAtExpected
+                && expected_columns > 0
+                && rec_len % expected_columns == 0
+            {
+                let fields: Vec<&str> = record.iter().collect();
+                for chunk in fields.chunks(expected_columns) {
+                    let split = StringRecord::from(chunk.to_vec());
+                    self.emit(out, ReconstructedRow::fixed(split, vec![line]));
+                    stats.fixed_rows += 1;
+                }
+                return;
+            }
+
+            if !self.is_buffering() && config.overflow_strategy == OverflowStrategy::SmartMerge && expected_columns > 0 {
+                let target = config.free_text_columns.first().copied().unwrap_or(expected_columns - 1);
+                if target < expected_columns {
+                    let merged = merge_surplus_into(&record, expected_columns, target, config.delimiter);
+                    self.emit(out, ReconstructedRow::fixed(merged, vec![line]));
+                    stats.fixed_rows += 1;
+                    return;
+                }
+            }
+
+            stats.warnings.push(Warning::OverLengthRow { line, got: rec_len, expected: expected_columns });
+            stats.removed_rows += 1;
+            stats.discard_lines(&[line]);
+            stats.rejected_raw.extend_from_slice(&raw);
+            if config.collect_discarded {
+                stats.discarded_records.push(record);
+            }
+            return;
+        }
+
+        // Recovery: a row one field short whose first value looks like the
+        // second column lost its leading (empty) field, shifting it left.
+        if let Some(pattern) = &config.leading_column_pattern {
+            if !self.is_buffering()
+                && rec_len + 1 == expected_columns
+                && record.get(0).map_or(false, |first| pattern.is_match(first))
+            {
+                let realigned: StringRecord = std::iter::once("").chain(record.iter()).collect();
+                self.emit(out, ReconstructedRow::fixed(realigned, vec![line]));
+                stats.fixed_rows += 1;
+                return;
+            }
+        }
+
+        // Case 1: Starting a new logical row
+        if !self.is_buffering() {
+            if rec_len == expected_columns {
+                // Complete row - add directly
+                let mut clean = ReconstructedRow::clean(record, vec![line]);
+                if config.preserve_quoting {
+                    clean.quoted = quoted_fields(&raw, rec_len, config.delimiter.as_byte(), self.quote);
+                }
+                self.emit(out, clean);
+            } else {
+                // Incomplete row - start buffering
+                self.buffer.extend(record.iter().map(|s| s.to_string()));
+                self.buffer_lines.push(line);
+                self.buffer_raw = raw;
+                if config.stitch_overshoot_tolerance > 0 {
+                    self.buffer_fragments = vec![record.clone()];
+                }
+                if config.collect_discarded {
+                    self.buffer_records = vec![record];
+                }
+            }
+            return;
+        }
+
+        // Case 2: Continuing a buffered row
+        // Append first field to last buffered field (handles embedded newlines)
+        if let Some(first_part) = record.get(0) {
+            if let Some(last_col) = self.buffer.last_mut() {
+                if !last_col.is_empty() {
+                    // DESIGN DECISION: Preserve the newline in the in-memory representation.
+                    // We maintain the data fidelity here (stitching exactly as it was broken).
+                    // Sanitization is deferred to the writing phase to separate concerns.
+                    last_col.push_str(&config.stitch_separator);
+                }
+                last_col.push_str(first_part);
+            }
+        }
+
+        // Append remaining fields
+        for i in 1..rec_len {
+            self.buffer.push(record.get(i).unwrap_or("").to_string());
+        }
+        self.buffer_lines.push(line);
+        self.buffer_raw.extend_from_slice(&raw);
+        if config.stitch_overshoot_tolerance > 0 {
+            self.buffer_fragments.push(record.clone());
+        }
+        if config.collect_discarded {
+            self.buffer_records.push(record);
+        }
+
+        // Case 3: Check if row is now complete
+        if self.buffer.len() == expected_columns {
+            // Guard: a field with implausibly many newlines is a runaway stitch
+            if let Some((column, newlines)) = config.max_newlines_per_field.and_then(|max| runaway_field(&self.buffer, max)) {
+                stats.runaway_fields += 1;
+                stats.warnings.push(Warning::RunawayField { line: self.buffer_lines[0], column, newlines });
+                if config.runaway_field_policy == RunawayFieldPolicy::Reject {
+                    self.discard_buffer(stats);
+                    return;
+                }
+            }
+
+            let fields = std::mem::take(&mut self.buffer);
+            let row = ReconstructedRow::fixed(StringRecord::from(fields), std::mem::take(&mut self.buffer_lines));
+            self.emit(out, row);
+            stats.fixed_rows += 1;
+            self.buffer_raw.clear();
+            self.buffer_records.clear();
+        } else if let Some(fields) = self
+            .buffer
+            .len()
+            .checked_sub(expected_columns)
+            .filter(|&overshoot| overshoot <= config.stitch_overshoot_tolerance)
+            .and_then(|_| restitch_without_boundary_empties(&self.buffer_fragments, expected_columns, &config.stitch_separator))
+        {
+            // Recovery: the row only overshot by spurious empty fields at its line breaks
+            let row = ReconstructedRow::fixed(StringRecord::from(fields), std::mem::take(&mut self.buffer_lines));
+            self.emit(out, row);
+            stats.fixed_rows += 1;
+            self.buffer.clear();
+            self.buffer_raw.clear();
+            self.buffer_records.clear();
+        } else if self.buffer.len() > expected_columns {
+            // Row has too many columns - discard and log
+            stats.warnings.push(Warning::OverLengthRow {
+                line: self.buffer_lines[0],
+                got: self.buffer.len(),
+                expected: expected_columns,
+            });
+            self.discard_buffer(stats);
+        }
+    }
+
+    /// Handle the end of the input
+    ///
+    /// `unbalanced` tells whether the input ended inside a quoted field. The
+    /// record holding such a field may be salvaged, which replaces the last
+    /// row of `out` if that row holds it, so callers that pass rows on early
+    /// must keep the last one back when `repair_unterminated_quote` is set.
+    pub(crate) fn finish(
+        &mut self,
+        unbalanced: bool,
+        config: &ReconstructConfig,
+        stats: &mut Stats,
+        out: &mut Vec<ReconstructedRow>,
+    ) {
+        // A quoted field left open at EOF swallows everything after its opening
+        // quote, so it ends the last physical row read.
+        if unbalanced {
+            let last_line = self.last_line;
+            stats.warnings.push(Warning::UnterminatedQuote { line: last_line });
+
+            if config.repair_unterminated_quote {
+                // Close the field implicitly at EOF and salvage the record holding
+                // it, unless that record was already discarded
+                let salvaged = if self.buffer_lines.last() == Some(&last_line) {
+                    stats.fixed_rows += 1;
+                    Some((std::mem::take(&mut self.buffer), std::mem::take(&mut self.buffer_lines), self.section))
+                } else if out.last().map_or(false, |row| !row.header && row.source_lines.last() == Some(&last_line)) {
+                    out.pop().map(|row| {
+                        if !row.fixed {
+                            stats.fixed_rows += 1;
+                        }
+                        (row.record.iter().map(str::to_string).collect(), row.source_lines, row.section)
+                    })
+                } else {
+                    None
+                };
+                if let Some((mut fields, lines, section)) = salvaged {
+                    if let Some(last) = fields.last_mut() {
+                        let trimmed = last.trim_end_matches(['\r', '\n']).len();
+                        last.truncate(trimmed);
+                    }
+                    fields.resize(fields.len().max(self.expected_columns), String::new());
+                    out.push(ReconstructedRow { section, ..ReconstructedRow::fixed(StringRecord::from(fields), lines) });
+                }
+            }
+        }
+
+        // Handle any remaining incomplete row
+        if self.is_buffering() && config.pad_trailing_row && self.buffer.len() < self.expected_columns {
+            let mut fields = std::mem::take(&mut self.buffer);
+            fields.resize(self.expected_columns, String::new());
+            let lines = std::mem::take(&mut self.buffer_lines);
+            self.emit(out, ReconstructedRow::fixed(StringRecord::from(fields), lines));
+            stats.fixed_rows += 1;
+        } else if self.is_buffering() {
+            stats.warnings.push(Warning::TrailingIncompleteRow { line: self.buffer_lines[0] });
+            self.discard_buffer(stats);
+        }
+    }
+
+    /// Append `row` to `out`, tagged with the current section
+    fn emit(&self, out: &mut Vec<ReconstructedRow>, row: ReconstructedRow) {
+        out.push(ReconstructedRow { section: self.section, ..row });
+    }
+
+    /// Count the buffered partial record as removed and clear it
+    fn discard_buffer(&mut self, stats: &mut Stats) {
+        stats.removed_rows += 1;
+        stats.discard_lines(&self.buffer_lines);
+        stats.rejected_raw.append(&mut self.buffer_raw);
+        stats.discarded_records.append(&mut self.buffer_records);
+        self.buffer.clear();
+        self.buffer_lines.clear();
+    }
+}
@@ -0,0 +1,397 @@
+// src/stream.rs
+//! Lazy, pull-based reconstruction
+//!
+//! [`Reconstructor`] yields records one at a time instead of returning the
+//! whole file, and shares its [`Stats`] with the caller so a UI driving the
+//! iterator can show running counts between records. [`RecordReconstructor`]
+//! yields the bare records, for writing multi-gigabyte inputs out
+//! incrementally.
+
+use crate::config::ReconstructConfig;
+use crate::detect::QUOTE_SAMPLE_BYTES;
+use crate::encoding::DecodingReader;
+use crate::engine::{apply_row_transforms, ReconstructedRow, Stats};
+use crate::error::FixerrError;
+use crate::scrub::{scrub_leading_junk, Scrubbed};
+use crate::stitch::{resolve_dialect, strategy_width, RowSource, Stitcher};
+use csv::StringRecord;
+use std::cell::{Ref, RefCell};
+use std::io::{Chain, Cursor, Read};
+use std::rc::Rc;
+
+/// Input after junk removal, with the dialect sample put back in front
+type Sampled<R> = Chain<Cursor<Vec<u8>>, Scrubbed<DecodingReader<R>>>;
+
+/// Iterator over reconstructed rows with live statistics
+///
+/// Stitches records with the same state machine as
+/// [`reconstruct_rows_from_reader`](crate::reconstruct_rows_from_reader), so
+/// every repair, warning and discard happens the same way, and applies the
+/// per-row options (`fill_empty_header_names`, `internal_newline`,
+/// `numeric_columns`, `column_normalizers` and `validate_fn`) as rows are
+/// yielded. `progress` events carry no total size.
+///
+/// Options that need the whole input are ignored: `reverse`,
+/// `collapse_consecutive_delimiters`, `per_row_delimiter_detection`, the
+/// `Quantile` column count strategy (the width is detected instead),
+/// `generate_typed_headers`, `header_type_hints`, `sequence_column`,
+/// `group_by`, `sort_by` and `history_file`. With
+/// `repair_unterminated_quote` every row is held back until the next one is
+/// complete, since the last row may still be salvaged.
+///
+/// # Borrowing rules
+///
+/// [`stats`](Self::stats) returns a shared handle to the counters, which the
+/// iterator borrows mutably inside every call to `next`. Borrow the handle
+/// only between calls and drop the borrow before calling `next` again; a
+/// `Ref` held across `next` makes it panic.
+pub struct Reconstructor<R: Read> {
+    rows: RowSource<Sampled<R>>,
+    stitcher: Stitcher,
+    /// The configuration, with the delimiter resolved
+    config: ReconstructConfig,
+    /// Completed rows not yet yielded, in source order
+    ready: Vec<ReconstructedRow>,
+    /// Highest source line counted in `rows_in_output`
+    counted_line: Option<u64>,
+    stats: Rc<RefCell<Stats>>,
+    /// The end of the input was reached
+    ended: bool,
+    finished: bool,
+}
+
+impl<R: Read> Reconstructor<R> {
+    /// Start reconstructing `source`, reading the header if there is one
+    ///
+    /// In `NoHeaders` mode `expected_columns` must be set.
+    pub fn new(source: R, config: &ReconstructConfig) -> Result<Self, FixerrError> {
+        let (stripped, mut source) = scrub_leading_junk(DecodingReader::new(source, config.input_encoding))?;
+        let mut stats = Stats { stripped_leading_bytes: stripped, ..Stats::default() };
+
+        // Sample the start of the input for dialect detection, then put it back
+        let mut sample = Vec::new();
+        (&mut source).take(QUOTE_SAMPLE_BYTES as u64).read_to_end(&mut sample)?;
+        let (delimiter, quote) = resolve_dialect(&sample, config, &mut stats);
+        let config = ReconstructConfig { delimiter, ..config.clone() };
+        // Fail on an unknown normalizer before reading any rows
+        apply_row_transforms(&mut [], &config)?;
+
+        let width = strategy_width(&config, quote, &sample)?;
+        let mut rows = RowSource::open(Cursor::new(sample).chain(source), &config, quote, width, None)?;
+        let stitcher = Stitcher::new(quote, rows.expected_columns, rows.header_line);
+        // The header is read during detection, so like in the batch engine
+        // it is not counted among the rows read or written
+        let ready = rows.take_header().into_iter().collect();
+
+        Ok(Self {
+            rows,
+            stitcher,
+            config,
+            ready,
+            counted_line: None,
+            stats: Rc::new(RefCell::new(stats)),
+            ended: false,
+            finished: false,
+        })
+    }
+
+    /// Shared handle to the statistics accumulated so far
+    pub fn stats(&self) -> Rc<RefCell<Stats>> {
+        Rc::clone(&self.stats)
+    }
+
+    /// Next logical row, after the per-row options
+    fn next_row(&mut self) -> Result<Option<ReconstructedRow>, FixerrError> {
+        loop {
+            let held_back = usize::from(self.config.repair_unterminated_quote && !self.ended);
+            if self.ready.len() > held_back {
+                let row = self.ready.remove(0);
+                match self.finish_row(row)? {
+                    Some(row) => return Ok(Some(row)),
+                    None => continue,
+                }
+            }
+            if self.ended {
+                return Ok(None);
+            }
+
+            let mut stats = self.stats.borrow_mut();
+            match self.rows.next_row(!self.stitcher.is_buffering(), &mut stats)? {
+                Some(row) => self.stitcher.push(row, &self.config, &mut stats, &mut self.ready),
+                None => {
+                    let unbalanced = self.rows.finish(&mut stats);
+                    self.stitcher.finish(unbalanced, &self.config, &mut stats, &mut self.ready);
+                    self.ended = true;
+                }
+            }
+        }
+    }
+
+    /// Apply the per-row options to a completed row; `None` if it was dropped
+    fn finish_row(&mut self, row: ReconstructedRow) -> Result<Option<ReconstructedRow>, FixerrError> {
+        let mut stats = self.stats.borrow_mut();
+        if row.fixed {
+            stats.fixed_lines.extend(row.source_lines.first());
+        }
+        let mut rows = vec![row];
+        apply_row_transforms(&mut rows, &self.config)?;
+        if let Some(validator) = &self.config.validate_fn {
+            rows = validator.apply(rows, &mut stats);
+        }
+        let row = match rows.pop() {
+            Some(row) => row,
+            None => return Ok(None),
+        };
+
+        // Count the distinct physical rows behind the output, for reconciliation
+        for &line in &row.source_lines {
+            if self.counted_line.map_or(true, |counted| line > counted) && Some(line) != self.rows.header_line {
+                stats.rows_in_output += 1;
+                self.counted_line = Some(line);
+            }
+        }
+        Ok(Some(row))
+    }
+}
+
+impl<R: Read> Iterator for Reconstructor<R> {
+    type Item = Result<ReconstructedRow, FixerrError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.finished {
+            return None;
+        }
+        let result = self.next_row().transpose();
+        // Stop after the end of the input or the first error
+        self.finished = !matches!(result, Some(Ok(_)));
+        result
+    }
+}
+
+/// Iterator over reconstructed records, without per-row metadata
+///
+/// Same algorithm as [`Reconstructor`]; the header, if any, is the first
+/// record. The statistics stay with the iterator: look at them with
+/// [`stats`](Self::stats) or take them with [`into_stats`](Self::into_stats)
+/// once it is exhausted.
+pub struct RecordReconstructor<R: Read> {
+    rows: Reconstructor<R>,
+}
+
+impl<R: Read> RecordReconstructor<R> {
+    /// Start reconstructing `source`, reading the header if there is one
+    ///
+    /// In `NoHeaders` mode `expected_columns` must be set.
+    pub fn new(source: R, config: &ReconstructConfig) -> Result<Self, FixerrError> {
+        Ok(Self { rows: Reconstructor::new(source, config)? })
+    }
+
+    /// The statistics accumulated so far
+    pub fn stats(&self) -> Ref<'_, Stats> {
+        self.rows.stats.borrow()
+    }
+
+    /// Consume the iterator, returning its statistics
+    pub fn into_stats(self) -> Stats {
+        std::mem::take(&mut *self.rows.stats.borrow_mut())
+    }
+}
+
+impl<R: Read> Iterator for RecordReconstructor<R> {
+    type Item = Result<StringRecord, FixerrError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.rows.next().map(|row| row.map(|row| row.record))
+    }
+}
+
+// ============================================
+// Unit Tests
+// ============================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::Delimiter;
+    use crate::progress::{ProgressEvent, PROGRESS_INTERVAL_ROWS};
+
+    #[test]
+    fn test_stats_are_visible_during_iteration() {
+        let input = "ID,Name,Amount\n1,Tbilisi Waters,10\n2,Gori\nBeverages,20\n3,x,y,z\n4,Mestia,40\n5,Sarme\n";
+        let mut rows = Reconstructor::new(input.as_bytes(), &ReconstructConfig::default()).unwrap();
+        let stats = rows.stats();
+
+        assert!(rows.next().unwrap().unwrap().header);
+        assert_eq!(&rows.next().unwrap().unwrap().record[1], "Tbilisi Waters");
+        {
+            let partial = stats.borrow();
+            assert_eq!((partial.total_rows, partial.fixed_rows, partial.removed_rows), (1, 0, 0));
+        }
+
+        let stitched = rows.next().unwrap().unwrap();
+        assert!(stitched.fixed);
+        assert_eq!(stitched.source_lines, vec![3, 4]);
+        assert_eq!(stats.borrow().fixed_rows, 1);
+
+        let rest: Vec<ReconstructedRow> = rows.map(Result::unwrap).collect();
+        assert_eq!(rest.len(), 1);
+        assert_eq!(&rest[0].record[1], "Mestia");
+
+        let total = stats.borrow();
+        assert_eq!(total.total_rows, 6);
+        assert_eq!(total.fixed_rows, 1);
+        assert_eq!(total.removed_rows, 2);
+        assert_eq!(total.rows_in_output, 4);
+        assert!(total.reconcile().is_balanced());
+    }
+
+    #[test]
+    fn test_record_iterator_yields_incrementally() {
+        let input = "\u{feff}ID,Name,Amount\n1,Tbilisi\nWaters,10\n2,Gori,20\n3,a,b,c\n";
+        let mut records = RecordReconstructor::new(input.as_bytes(), &ReconstructConfig::default()).unwrap();
+
+        assert_eq!(records.next().unwrap().unwrap(), StringRecord::from(vec!["ID", "Name", "Amount"]));
+        assert_eq!(&records.next().unwrap().unwrap()[1], "Tbilisi\nWaters");
+        assert_eq!(records.stats().total_rows, 2);
+
+        assert_eq!(records.by_ref().count(), 1);
+        let stats = records.into_stats();
+        assert_eq!((stats.total_rows, stats.fixed_rows, stats.removed_rows), (4, 1, 1));
+        assert_eq!(stats.stripped_leading_bytes, 3);
+        assert!(stats.reconcile().is_balanced());
+    }
+
+    #[test]
+    fn test_progress_is_reported_while_streaming() {
+        let input: String = std::iter::once("ID,Name\n".to_string())
+            .chain((0..PROGRESS_INTERVAL_ROWS * 2 + 1).map(|i| format!("{i},x\n")))
+            .collect();
+        let reported = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+        let sink = std::sync::Arc::clone(&reported);
+        let config = crate::ReconstructOptions::new()
+            .progress(move |event: &ProgressEvent| sink.lock().unwrap().push(event.rows))
+            .build();
+
+        let records = RecordReconstructor::new(input.as_bytes(), &config).unwrap();
+        assert_eq!(records.count(), PROGRESS_INTERVAL_ROWS * 2 + 2);
+
+        assert_eq!(*reported.lock().unwrap(), [PROGRESS_INTERVAL_ROWS, PROGRESS_INTERVAL_ROWS * 2]);
+    }
+
+    #[test]
+    fn test_auto_delimiter_is_resolved_from_a_sample() {
+        let config = ReconstructConfig { delimiter: Delimiter::Auto, ..ReconstructConfig::default() };
+        let input = "ID;Name;Amount\n1;Tbilisi\nWaters;10\n2;Gori;20\n";
+
+        let records: Vec<StringRecord> =
+            RecordReconstructor::new(input.as_bytes(), &config).unwrap().map(Result::unwrap).collect();
+
+        assert_eq!(records.len(), 3);
+        assert_eq!(&records[1], &StringRecord::from(vec!["1", "Tbilisi\nWaters", "10"]));
+    }
+
+    #[test]
+    fn test_stream_matches_engine_on_engine_fixtures() {
+        use crate::engine::{reconstruct_rows_from_reader, OverflowStrategy};
+        use crate::validate::{RecordAction, RecordValidator};
+        use std::collections::HashMap;
+
+        let default = ReconstructConfig::default();
+        let mut enum_columns = HashMap::new();
+        enum_columns.insert(1, vec!["ACTIVE".to_string(), "PENDING REVIEW".to_string()]);
+        let cases: Vec<(&str, ReconstructConfig)> = vec![
+            ("ID,Organization,Details,Amount\n9413154,Tbilisi Waters,Georgian Product\n,1722.63", default.clone()),
+            (
+                "ID,Organization,Details,Amount\n9413155,Bodorna Waters,Mineral water from\nBodorna, 2909.20",
+                default.clone(),
+            ),
+            ("ID,Organization,Details,Amount\n9413156,Gori\nBeverages,Product from\nGori, 3427.50", default.clone()),
+            (
+                "ID,Organization,Details,Amount\n9413157,Sairme Waters,This\nProduct\nIs from\nSarime,1736.10",
+                default.clone(),
+            ),
+            ("ID,Organization,Details,Amount\n9413158,Svaneti Waters,\"Mestia,\nGeorgia\",2505.25", default.clone()),
+            ("ID,Name,Amount\n1,Tbilisi\n\n , ,\nWaters,10\n,,\n\n2,Gori,20\n  \n", default.clone()),
+            ("ID,Name,Amount\n1,Tbilisi\nWaters,10\n2,Gori,20,extra\n3,x\n", default.clone()),
+            (
+                "ID,Status,Amount\n1,ACTIVE,10\n2,PENDING,REVIEW,20\n3,UNKNOWN,X,30",
+                ReconstructConfig { enum_columns, ..default.clone() },
+            ),
+            (
+                "ID,Name,Price,Qty\n1,Tbilisi Waters,12,50,3\n2,Gori,1.234,56,7\n",
+                ReconstructConfig { repair_decimal_comma: true, ..default.clone() },
+            ),
+            (
+                "A,B,C\n1,x\n2,y,z,3,w,v\ny,9\n4,ok,5\n",
+                ReconstructConfig { overflow_strategy: OverflowStrategy::SplitAtExpected, ..default.clone() },
+            ),
+            (
+                "A,B,C\n1,x,y|z,w\n6,a\nb,7\n",
+                ReconstructConfig { overflow_strategy: OverflowStrategy::SmartMerge, ..default.clone() },
+            ),
+            (
+                "ID,Name,Amount\n1,Tbilisi\na\nb\nc\nd\ne\nf,10\n2,Gori,20",
+                ReconstructConfig { max_newlines_per_field: Some(5), ..default.clone() },
+            ),
+            (
+                "ID,Name,Amount\n1,Tbilisi\nWaters,x,10\n2,x,y,z\n3,Gori,20\n4,Mestia\n",
+                ReconstructConfig { stitch_overshoot_tolerance: 1, collect_discarded: true, ..default.clone() },
+            ),
+            (
+                "ID,Name,Amount,Notes\n1,Tbilisi,10,ok\n2,Gori\nBeverages",
+                ReconstructConfig { pad_trailing_row: true, ..default.clone() },
+            ),
+            (
+                "ID,Name,Amount\n1,Tbilisi Waters,10\n2,\"Gori\nBeverages,20\n",
+                ReconstructConfig { repair_unterminated_quote: true, ..default.clone() },
+            ),
+            ("ID,Desc\n1,12\" pipe\n2,ok\n", ReconstructConfig { repair_unterminated_quote: true, ..default.clone() }),
+            (
+                "ID,Name,Amount\n1,Tbilisi\nWaters,10\n2,Gori,20\n\nCode,Region\nGE-TB,Tbilisi\nGE-IM\n,Imereti",
+                ReconstructConfig { multi_section: true, ..default.clone() },
+            ),
+            (
+                "Group,,\nID,Name,Amount\n1,Tbilisi\nWaters,10\n",
+                ReconstructConfig { header_rows: 2, fill_empty_header_names: true, ..default.clone() },
+            ),
+            (
+                "ID,Status,Amount\n1,PAID,10\n2,VOID,0\n3,PAID\n,-30\n",
+                ReconstructConfig {
+                    validate_fn: Some(RecordValidator::new(Box::new(|record: &StringRecord| match &record[1] {
+                        "VOID" => RecordAction::Drop,
+                        _ => RecordAction::Keep,
+                    }))),
+                    ..default.clone()
+                },
+            ),
+        ];
+
+        for (input, config) in &cases {
+            let mut expected_stats = Stats::default();
+            let expected = reconstruct_rows_from_reader(input.as_bytes(), config, &mut expected_stats).unwrap();
+
+            let mut rows = Reconstructor::new(input.as_bytes(), config).unwrap();
+            let stats = rows.stats();
+            let streamed: Vec<ReconstructedRow> = rows.by_ref().map(Result::unwrap).collect();
+            let stats = stats.borrow();
+
+            assert_eq!(streamed, expected, "rows of {input:?}");
+            assert_eq!(stats.warnings, expected_stats.warnings, "warnings of {input:?}");
+            assert_eq!(
+                (stats.total_rows, stats.fixed_rows, stats.removed_rows, stats.rows_in_output, stats.blank_lines),
+                (
+                    expected_stats.total_rows,
+                    expected_stats.fixed_rows,
+                    expected_stats.removed_rows,
+                    expected_stats.rows_in_output,
+                    expected_stats.blank_lines
+                ),
+                "counts of {input:?}"
+            );
+            assert_eq!(stats.fixed_lines, expected_stats.fixed_lines, "fixed lines of {input:?}");
+            assert_eq!(stats.discarded_lines, expected_stats.discarded_lines, "discarded lines of {input:?}");
+            assert_eq!(stats.discarded_records, expected_stats.discarded_records, "discarded records of {input:?}");
+            assert_eq!(stats.records_dropped, expected_stats.records_dropped, "validation of {input:?}");
+        }
+    }
+}
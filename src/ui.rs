@@ -5,9 +5,16 @@
 //! Separates presentation logic from business logic.
 
 use crate::{Config, Stats};
-use std::io::{self, Write};
+use fixerr::{CardinalityReport, DirectoryReport, ProgressEvent, RunComparison, ValidationError};
+use std::io::{self, BufRead, Write};
 use std::time::Instant;
 
+/// Maximum number of warnings listed individually in the summary
+const MAX_LISTED_WARNINGS: usize = 10;
+
+/// Largest column count accepted for headerless files
+const MAX_COLUMNS: u32 = 10_000;
+
 // ============================================
 // Display Functions
 // ============================================
@@ -25,7 +32,9 @@ pub fn display_welcome() {
     println!("────────────────────────────────────────────────────");
     println!("  1. Fix CSV Records");
     println!("  2. Settings");
-    println!("  3. Exit");
+    println!("  3. Compare Configurations");
+    println!("  4. Process Directory");
+    println!("  5. Exit");
     println!("────────────────────────────────────────────────────");
 }
 
@@ -41,14 +50,16 @@ pub fn display_settings_menu(config: &Config) {
     println!("  Header Mode:  {:?}", config.header_mode);
     println!("  Input File:   {}", config.input_file);
     println!("  Output File:  {}", config.output_file);
+    println!("  Known Headers: {}", format_known_headers(&config.known_header_names));
     
     println!("\n────────────────────────────────────────────────────");
     println!("  1. Change Delimiter");
     println!("  2. Change Header Mode");
     println!("  3. Change Input File Path");
     println!("  4. Change Output File Path");
-    println!("  5. Reset to Defaults");
-    println!("  6. Back to Main Menu");
+    println!("  5. Change Known Header Names");
+    println!("  6. Reset to Defaults");
+    println!("  7. Back to Main Menu");
     println!("────────────────────────────────────────────────────");
 }
 
@@ -65,6 +76,8 @@ pub fn display_delimiter_menu(current: &str) {
     println!("  2. Semicolon (;)");
     println!("  3. Tab (\\t)");
     println!("  4. Pipe (|)");
+    println!("  5. Auto-detect");
+    println!("  6. Custom character or byte");
     println!();
 }
 
@@ -82,6 +95,34 @@ pub fn display_header_mode_menu(current: &str) {
     println!();
 }
 
+/// Display alternative configuration menu for a comparison
+pub fn display_compare_menu() {
+    clear_screen();
+    println!("╔══════════════════════════════════════════════════╗");
+    println!("║            COMPARE CONFIGURATIONS                ║");
+    println!("╚══════════════════════════════════════════════════╝\n");
+    
+    println!("📌 Compare the current settings (A) against:");
+    println!("  1. Split over-long rows at the expected width");
+    println!("  2. Merge surplus fields into the free-text column");
+    println!("  3. Majority-vote column count");
+    println!("  4. Reverse-order stitching");
+    println!();
+}
+
+/// Display directory batch processing screen
+pub fn display_directory_screen() {
+    clear_screen();
+    println!("╔══════════════════════════════════════════════════╗");
+    println!("║             PROCESS DIRECTORY                    ║");
+    println!("╚══════════════════════════════════════════════════╝\n");
+    
+    println!("📌 Every *.csv file in the input directory is repaired with");
+    println!("   the current delimiter and header mode and written under");
+    println!("   the same name to the output directory.");
+    println!();
+}
+
 /// Display file path change screen
 pub fn display_file_path_screen(setting_name: &str, current_path: &str) {
     clear_screen();
@@ -95,6 +136,19 @@ pub fn display_file_path_screen(setting_name: &str, current_path: &str) {
     println!();
 }
 
+/// Display known header names change screen
+pub fn display_known_headers_screen(current: &[String]) {
+    clear_screen();
+    println!("╔══════════════════════════════════════════════════╗");
+    println!("║           CHANGE KNOWN HEADER NAMES              ║");
+    println!("╚══════════════════════════════════════════════════╝\n");
+    
+    println!("Current Names: {}", format_known_headers(current));
+    println!("\n📌 Used in No Headers mode to warn when the first row");
+    println!("   looks like a header row.");
+    println!();
+}
+
 /// Display processing header with configuration
 pub fn display_processing_header(config: &Config) {
     clear_screen();
@@ -114,18 +168,130 @@ pub fn display_summary(stats: &Stats, total_records: usize, output_file: &str) {
     println!("\n╔══════════════════════════════════════════════════╗");
     println!("║                  SUMMARY                         ║");
     println!("╚══════════════════════════════════════════════════╝");
-    println!("📊 Total lines read       : {}", stats.total_rows);
-    println!("✅ Fixed/Merged rows      : {}", stats.fixed_rows);
-    println!("❌ Discarded rows         : {}", stats.removed_rows);
-    println!("📝 Total valid records    : {total_records}");
-    
-    let success_rate = calculate_success_rate(stats);
-    println!("📈 Success Rate           : {success_rate:.1}%");
+    print!("{}", stats.render_table(total_records, output_file));
+    println!("────────────────────────────────────────────────────");
+    print!("{}", stats.reconcile());
+
+    if !stats.fixed_lines.is_empty() || !stats.discarded_lines.is_empty() {
+        println!("────────────────────────────────────────────────────");
+        println!("🔧 Fixed at lines    : {}", format_line_list(&stats.fixed_lines));
+        println!("🗑️  Discarded lines   : {}", format_line_list(&stats.discarded_lines));
+    }
+
+    if !stats.warnings.is_empty() {
+        println!("────────────────────────────────────────────────────");
+        for warning in stats.warnings.iter().take(MAX_LISTED_WARNINGS) {
+            println!("     - {warning}");
+        }
+        if stats.warnings.len() > MAX_LISTED_WARNINGS {
+            println!("     ... and {} more", stats.warnings.len() - MAX_LISTED_WARNINGS);
+        }
+    }
     
     println!("────────────────────────────────────────────────────");
     println!("✨ Success! Output written to: {output_file}\n");
 }
 
+/// Display the distinct value count of every column
+pub fn display_cardinality(reports: &[CardinalityReport]) {
+    println!("\n📊 Column Profile");
+    println!("────────────────────────────────────────────────────");
+    for report in reports {
+        println!("  {report}");
+    }
+    println!();
+}
+
+/// Display the outcome of re-reading the written file
+pub fn display_validation(result: &Result<(), Vec<ValidationError>>) {
+    match result {
+        Ok(()) => println!("   ✅ Output parses back to the same records"),
+        Err(errors) => {
+            println!("   ⚠️  Output validation found {} problem(s):", errors.len());
+            for error in errors.iter().take(MAX_LISTED_WARNINGS) {
+                println!("      - {error}");
+            }
+            if errors.len() > MAX_LISTED_WARNINGS {
+                println!("      ... and {} more", errors.len() - MAX_LISTED_WARNINGS);
+            }
+        }
+    }
+}
+
+/// Display the outcome of a directory batch run
+pub fn display_directory_summary(report: &DirectoryReport, output_dir: &str) {
+    println!("\n╔══════════════════════════════════════════════════╗");
+    println!("║                BATCH SUMMARY                     ║");
+    println!("╚══════════════════════════════════════════════════╝");
+    for file in &report.files {
+        println!(
+            "  ✅ {}: {} records, {} fixed, {} discarded",
+            file.input.display(),
+            file.records,
+            file.stats.fixed_rows,
+            file.stats.removed_rows
+        );
+    }
+    for (input, error) in &report.failures {
+        println!("  ❌ {}: {error}", input.display());
+    }
+    println!("────────────────────────────────────────────────────");
+    print!("{}", report.totals.render_table(report.total_records(), output_dir));
+    println!("────────────────────────────────────────────────────");
+    println!("✨ {} of {} files repaired\n", report.files.len(), report.files.len() + report.failures.len());
+}
+
+/// Display the result of comparing two configurations
+pub fn display_comparison(comparison: &RunComparison) {
+    println!("\n╔══════════════════════════════════════════════════╗");
+    println!("║                 COMPARISON                       ║");
+    println!("╚══════════════════════════════════════════════════╝");
+    print!("{comparison}");
+    println!("────────────────────────────────────────────────────");
+    
+    if comparison.is_identical() {
+        println!("✅ Both configurations produce the same records.\n");
+        return;
+    }
+    for (label, records) in [("A", &comparison.only_in_a), ("B", &comparison.only_in_b)] {
+        for record in records.iter().take(MAX_LISTED_WARNINGS) {
+            let fields: Vec<&str> = record.iter().collect();
+            println!("     only in {label}: {fields:?}");
+        }
+        if records.len() > MAX_LISTED_WARNINGS {
+            println!("     ... and {} more only in {label}", records.len() - MAX_LISTED_WARNINGS);
+        }
+    }
+    println!();
+}
+
+/// Sorted, comma-separated `lines`, truncated after `MAX_LISTED_WARNINGS`
+fn format_line_list(lines: &[u64]) -> String {
+    if lines.is_empty() {
+        return "none".to_string();
+    }
+    let mut sorted = lines.to_vec();
+    sorted.sort_unstable();
+    let listed: Vec<String> = sorted.iter().take(MAX_LISTED_WARNINGS).map(u64::to_string).collect();
+    if sorted.len() > MAX_LISTED_WARNINGS {
+        format!("{}, ... ({} total)", listed.join(", "), sorted.len())
+    } else {
+        listed.join(", ")
+    }
+}
+
+/// Overwrite the current line with a progress update
+pub fn display_progress(event: &ProgressEvent) {
+    print!("\r\x1B[2K   ⏳ {event}");
+    let _ = io::stdout().flush();
+}
+
+/// Remove the progress line before printing further output
+pub fn clear_progress() {
+    print!("\r\x1B[2K");
+    let _ = io::stdout().flush();
+}
+
 // ============================================
 // Input Functions
 // ============================================
@@ -140,16 +306,59 @@ pub fn display_summary(stats: &Stats, total_records: usize, output_file: &str) {
 /// * `prompt` - Prompt to display to user
 ///
 /// # Returns
-/// Valid choice within [min, max]
+/// Valid choice within [min, max], or an `UnexpectedEof` error if stdin is closed
 pub fn get_menu_choice(min: u32, max: u32, prompt: &str) -> io::Result<u32> {
+    read_menu_choice(&mut io::stdin().lock(), min, max, prompt)
+}
+
+/// Ask for the column count of a headerless file
+///
+/// # Returns
+/// A count of at least 1, or an `UnexpectedEof` error if stdin is closed
+pub fn get_column_count() -> io::Result<usize> {
+    get_menu_choice(1, MAX_COLUMNS, "Enter expected number of columns: ").map(|n| n as usize)
+}
+
+/// Get string input from user
+///
+/// # Arguments
+/// * `prompt` - Prompt to display
+///
+/// # Returns
+/// Trimmed string input, or an `UnexpectedEof` error if stdin is closed
+pub fn get_string_input(prompt: &str) -> io::Result<String> {
+    read_string_input(&mut io::stdin().lock(), prompt)
+}
+
+/// Get yes/no confirmation from user
+///
+/// # Arguments
+/// * `prompt` - Question to ask user
+///
+/// # Returns
+/// true if user confirms (y/yes), false otherwise, or an `UnexpectedEof`
+/// error if stdin is closed
+pub fn get_confirmation(prompt: &str) -> io::Result<bool> {
+    read_confirmation(&mut io::stdin().lock(), prompt)
+}
+
+/// Check whether an error means the input stream was closed
+pub fn is_input_closed(error: &(dyn std::error::Error + 'static)) -> bool {
+    error
+        .downcast_ref::<io::Error>()
+        .map_or(false, |e| e.kind() == io::ErrorKind::UnexpectedEof)
+}
+
+// Reader-generic implementations, so EOF handling can be tested without a TTY
+
+fn read_menu_choice<R: BufRead>(input: &mut R, min: u32, max: u32, prompt: &str) -> io::Result<u32> {
     loop {
         print!("{prompt}");
         io::stdout().flush()?;
         
-        let mut input = String::new();
-        io::stdin().read_line(&mut input)?;
+        let line = read_input_line(input)?;
         
-        match input.trim().parse::<u32>() {
+        match line.trim().parse::<u32>() {
             Ok(choice) if choice >= min && choice <= max => {
                 return Ok(choice);
             }
@@ -163,39 +372,33 @@ pub fn get_menu_choice(min: u32, max: u32, prompt: &str) -> io::Result<u32> {
     }
 }
 
-/// Get string input from user
-///
-/// # Arguments
-/// * `prompt` - Prompt to display
-///
-/// # Returns
-/// Trimmed string input
-pub fn get_string_input(prompt: &str) -> io::Result<String> {
+fn read_string_input<R: BufRead>(input: &mut R, prompt: &str) -> io::Result<String> {
     print!("{prompt}");
     io::stdout().flush()?;
     
-    let mut input = String::new();
-    io::stdin().read_line(&mut input)?;
-    
-    Ok(input.trim().to_string())
+    Ok(read_input_line(input)?.trim().to_string())
 }
 
-/// Get yes/no confirmation from user
-///
-/// # Arguments
-/// * `prompt` - Question to ask user
-///
-/// # Returns
-/// true if user confirms (y/yes), false otherwise
-pub fn get_confirmation(prompt: &str) -> io::Result<bool> {
+fn read_confirmation<R: BufRead>(input: &mut R, prompt: &str) -> io::Result<bool> {
     print!("{prompt} (y/n): ");
     io::stdout().flush()?;
     
-    let mut input = String::new();
-    io::stdin().read_line(&mut input)?;
+    let line = read_input_line(input)?;
     
-    Ok(input.trim().eq_ignore_ascii_case("y") || 
-       input.trim().eq_ignore_ascii_case("yes"))
+    Ok(line.trim().eq_ignore_ascii_case("y") || 
+       line.trim().eq_ignore_ascii_case("yes"))
+}
+
+/// Read one line, treating a zero-byte read (closed stream) as an error
+///
+/// Without this, a closed stdin makes `read_line` return `Ok(0)` forever and
+/// the menu loop spins at 100% CPU.
+fn read_input_line<R: BufRead>(input: &mut R) -> io::Result<String> {
+    let mut line = String::new();
+    if input.read_line(&mut line)? == 0 {
+        return Err(io::Error::new(io::ErrorKind::UnexpectedEof, "input stream closed"));
+    }
+    Ok(line)
 }
 
 // ============================================
@@ -235,6 +438,15 @@ pub fn print_elapsed(label: &str, start: Instant) {
     }
 }
 
+/// Format the known header names for display
+fn format_known_headers(names: &[String]) -> String {
+    if names.is_empty() {
+        "(none)".to_string()
+    } else {
+        names.join(", ")
+    }
+}
+
 /// Display success message
 pub fn show_success_message(msg: &str) {
     println!("\n✅ {msg}\n");
@@ -250,18 +462,37 @@ pub fn show_warning_message(msg: &str) {
     println!("\n⚠️  {msg}\n");
 }
 
-/// Calculate and display processing efficiency percentage
-///
-/// # Arguments
-/// * `stats` - The processing statistics
-///
-/// # Returns
-/// Success rate as a percentage (0-100)
-pub fn calculate_success_rate(stats: &Stats) -> f64 {
-    if stats.total_rows == 0 {
-        return 0.0;
+// ============================================
+// Unit Tests
+// ============================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_closed_input_returns_eof_instead_of_looping() {
+        let mut empty: &[u8] = &[];
+        let err = read_menu_choice(&mut empty, 1, 3, "").unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::UnexpectedEof);
+
+        let mut empty: &[u8] = &[];
+        assert!(read_string_input(&mut empty, "").is_err());
+
+        let mut empty: &[u8] = &[];
+        assert!(read_confirmation(&mut empty, "").is_err());
+
+        let boxed: Box<dyn std::error::Error> = Box::new(err);
+        assert!(is_input_closed(boxed.as_ref()));
+    }
+
+    #[test]
+    fn test_invalid_choices_then_eof_terminates() {
+        let mut input: &[u8] = b"abc\n9\n";
+        let err = read_menu_choice(&mut input, 1, 3, "").unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::UnexpectedEof);
+
+        let mut input: &[u8] = b"2\n";
+        assert_eq!(read_menu_choice(&mut input, 1, 3, "").unwrap(), 2);
     }
-    
-    let successful = stats.total_rows - stats.removed_rows;
-    (successful as f64 / stats.total_rows as f64) * 100.0
 }
@@ -0,0 +1,306 @@
+// src/validate.rs
+//! Post-reconstruction validation
+//!
+//! Checks that use domain knowledge about the data to catch reconstruction
+//! errors that the structural column-count checks cannot see.
+
+use crate::engine::{build_csv_reader, clean_and_normalize_field, Delimiter, HeaderMode, ReconstructedRow, Stats};
+use crate::infer::ColumnType;
+use crate::warning::Warning;
+use csv::StringRecord;
+use std::fmt;
+use std::fs::File;
+use std::sync::{Arc, Mutex};
+
+/// A structural problem in reconstructed or written output
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ValidationError {
+    /// A record does not have the expected number of columns
+    ColumnCount {
+        /// 1-based record (header = 1)
+        row: usize,
+        /// Number of fields the record has
+        got: usize,
+        /// Expected column count
+        expected: usize,
+    },
+    /// The written file parses back to a different number of records
+    RecordCount {
+        /// Records that were written
+        written: usize,
+        /// Records read back
+        read: usize,
+    },
+    /// The written file could not be read back as CSV
+    Unreadable(String),
+}
+
+impl fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ValidationError::ColumnCount { row, got, expected } => {
+                write!(f, "record {row}: {got} columns instead of {expected}")
+            }
+            ValidationError::RecordCount { written, read } => {
+                write!(f, "{written} records written but {read} read back")
+            }
+            ValidationError::Unreadable(message) => write!(f, "output cannot be read back: {message}"),
+        }
+    }
+}
+
+/// Verdict of a record validator on one record
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RecordAction {
+    /// Write the record unchanged
+    Keep,
+    /// Leave the record out of the output
+    Drop,
+    /// Write this record in its place
+    Replace(StringRecord),
+}
+
+/// Signature of a record validator
+pub type RecordValidatorFn = dyn FnMut(&StringRecord) -> RecordAction + Send;
+
+/// A user hook that keeps, drops or rewrites each reconstructed data record
+///
+/// Clones share the same closure, so state it keeps carries across them.
+#[derive(Clone)]
+pub struct RecordValidator(Arc<Mutex<Box<RecordValidatorFn>>>);
+
+impl RecordValidator {
+    /// Wrap `f` as a validator
+    pub fn new(f: Box<RecordValidatorFn>) -> Self {
+        Self(Arc::new(Mutex::new(f)))
+    }
+
+    /// Run the validator over every data record; header rows are skipped
+    ///
+    /// Dropped records are counted as removed, like any other discard.
+    pub(crate) fn apply(&self, rows: Vec<ReconstructedRow>, stats: &mut Stats) -> Vec<ReconstructedRow> {
+        let mut f = self.0.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        let mut kept = Vec::with_capacity(rows.len());
+
+        for mut row in rows {
+            if row.header {
+                kept.push(row);
+                continue;
+            }
+            match f(&row.record) {
+                RecordAction::Keep => {
+                    stats.records_kept += 1;
+                    kept.push(row);
+                }
+                RecordAction::Drop => {
+                    stats.records_dropped += 1;
+                    stats.removed_rows += 1;
+                    stats.discard_lines(&row.source_lines);
+                }
+                RecordAction::Replace(record) => {
+                    stats.records_replaced += 1;
+                    row.record = record;
+                    kept.push(row);
+                }
+            }
+        }
+        kept
+    }
+}
+
+impl fmt::Debug for RecordValidator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("RecordValidator")
+    }
+}
+
+/// Verify that a column holds a consistent integer sequence
+///
+/// Each value must equal the previous one plus `step`. Gaps often mean a
+/// record was wrongly discarded, duplicates that one was wrongly split.
+/// Header rows are skipped and the sequence restarts in every section.
+pub fn check_sequence(rows: &[ReconstructedRow], column: usize, step: i64) -> Vec<Warning> {
+    let mut warnings = Vec::new();
+    let mut previous: Option<(usize, i64)> = None;
+
+    for (index, row) in rows.iter().enumerate() {
+        if row.header {
+            continue;
+        }
+        let out_row = index + 1;
+        let raw = clean_and_normalize_field(row.record.get(column).unwrap_or(""));
+
+        let value = match raw.parse::<i64>() {
+            Ok(value) => value,
+            Err(_) => {
+                warnings.push(Warning::SequenceNotNumeric { out_row, value: raw });
+                continue;
+            }
+        };
+
+        if let Some((section, last)) = previous {
+            if section == row.section {
+                if value == last {
+                    warnings.push(Warning::SequenceDuplicate { out_row, value });
+                } else if value != last + step {
+                    warnings.push(Warning::SequenceGap {
+                        out_row,
+                        expected: last + step,
+                        found: value,
+                    });
+                }
+            }
+        }
+        previous = Some((row.section, value));
+    }
+
+    warnings
+}
+
+/// Default header suffixes recognized as type hints
+pub fn default_type_hint_suffixes() -> Vec<(String, ColumnType)> {
+    vec![
+        ("_int".to_string(), ColumnType::Integer),
+        ("_decimal".to_string(), ColumnType::Decimal),
+        ("_date".to_string(), ColumnType::Date),
+        ("_iso".to_string(), ColumnType::Date),
+    ]
+}
+
+/// Check values against the types implied by header name suffixes
+///
+/// A header such as `amount_decimal` declares its column as `Decimal` when
+/// `_decimal` is in `suffixes` (matched case-insensitively). Empty values are
+/// accepted everywhere and integers are accepted in decimal columns. Each
+/// section is checked against its own header.
+pub fn check_type_hints(rows: &[ReconstructedRow], suffixes: &[(String, ColumnType)]) -> Vec<Warning> {
+    let mut warnings = Vec::new();
+    let mut hinted: Vec<Option<ColumnType>> = Vec::new();
+
+    for (index, row) in rows.iter().enumerate() {
+        if row.header {
+            hinted = row.record.iter().map(|name| hinted_type(name, suffixes)).collect();
+            continue;
+        }
+
+        for (column, expected) in hinted.iter().enumerate() {
+            let expected = match expected {
+                Some(expected) => *expected,
+                None => continue,
+            };
+            let value = clean_and_normalize_field(row.record.get(column).unwrap_or(""));
+            if !value.is_empty() && !accepts(expected, ColumnType::of_value(&value)) {
+                warnings.push(Warning::TypeHintViolation {
+                    out_row: index + 1,
+                    column,
+                    expected,
+                    value,
+                });
+            }
+        }
+    }
+
+    warnings
+}
+
+/// Check that every record has exactly `expected_columns` fields
+pub fn validate_output(rows: &[StringRecord], expected_columns: usize) -> Result<(), Vec<ValidationError>> {
+    let errors: Vec<ValidationError> = rows
+        .iter()
+        .enumerate()
+        .filter(|(_, record)| record.len() != expected_columns)
+        .map(|(index, record)| ValidationError::ColumnCount { row: index + 1, got: record.len(), expected: expected_columns })
+        .collect();
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors)
+    }
+}
+
+/// Re-read the file `rows` were written to and validate what comes back
+///
+/// The file must parse with `delimiter` into as many records as `rows`, each
+/// `expected_columns` wide. A field whose quoting was lost or damaged on the
+/// way out shows up as a wrong column or record count.
+pub fn validate_written_output(
+    path: &str,
+    delimiter: Delimiter,
+    rows: &[StringRecord],
+    expected_columns: usize,
+) -> Result<(), Vec<ValidationError>> {
+    let unreadable = |e: &dyn fmt::Display| vec![ValidationError::Unreadable(e.to_string())];
+    let file = File::open(path).map_err(|e| unreadable(&e))?;
+    let read_back = build_csv_reader(file, HeaderMode::NoHeaders, delimiter)
+        .records()
+        .collect::<Result<Vec<StringRecord>, _>>()
+        .map_err(|e| unreadable(&e))?;
+
+    let mut errors = validate_output(&read_back, expected_columns).err().unwrap_or_default();
+    if read_back.len() != rows.len() {
+        errors.push(ValidationError::RecordCount { written: rows.len(), read: read_back.len() });
+    }
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors)
+    }
+}
+
+// ============================================
+// Private Helper Functions
+// ============================================
+
+fn hinted_type(name: &str, suffixes: &[(String, ColumnType)]) -> Option<ColumnType> {
+    let name = clean_and_normalize_field(name).to_lowercase();
+    suffixes
+        .iter()
+        .find(|(suffix, _)| name.ends_with(&suffix.to_lowercase()))
+        .map(|(_, column_type)| *column_type)
+}
+
+fn accepts(expected: ColumnType, actual: ColumnType) -> bool {
+    expected == actual
+        || expected == ColumnType::Text
+        || (expected == ColumnType::Decimal && actual == ColumnType::Integer)
+}
+
+// ============================================
+// Unit Tests
+// ============================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn test_validate_written_output_catches_lost_quoting() {
+        let rows = vec![
+            StringRecord::from(vec!["ID", "Name"]),
+            StringRecord::from(vec!["1", "Gori, Ltd"]),
+            StringRecord::from(vec!["2", "Mestia"]),
+        ];
+        assert_eq!(validate_output(&rows, 2), Ok(()));
+        assert_eq!(
+            validate_output(&rows[..2], 3).unwrap_err()[1],
+            ValidationError::ColumnCount { row: 2, got: 2, expected: 3 }
+        );
+
+        let path = "test_validate_written.csv";
+        fs::write(path, "ID,Name\n1,\"Gori, Ltd\"\n2,Mestia\n").unwrap();
+        let intact = validate_written_output(path, Delimiter::Comma, &rows, 2);
+        fs::write(path, "ID,Name\n1,Gori, Ltd\n").unwrap();
+        let damaged = validate_written_output(path, Delimiter::Comma, &rows, 2);
+        fs::remove_file(path).unwrap();
+
+        assert_eq!(intact, Ok(()));
+        assert_eq!(
+            damaged.unwrap_err(),
+            vec![
+                ValidationError::ColumnCount { row: 2, got: 3, expected: 2 },
+                ValidationError::RecordCount { written: 3, read: 2 },
+            ]
+        );
+    }
+}
@@ -0,0 +1,151 @@
+// src/warning.rs
+//! Non-fatal diagnostics
+//!
+//! Warnings describe suspicious conditions found during processing that do
+//! not stop the run. They are collected in [`Stats::warnings`](crate::Stats).
+
+use crate::engine::Delimiter;
+use crate::infer::ColumnType;
+use std::fmt;
+
+/// A non-fatal problem detected during processing
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Warning {
+    /// A sequence value did not follow the previous one by the expected step
+    SequenceGap {
+        /// 1-based output row (header = 1)
+        out_row: usize,
+        /// Value that should have appeared
+        expected: i64,
+        /// Value that actually appeared
+        found: i64,
+    },
+    /// A sequence value repeated the previous one
+    SequenceDuplicate {
+        /// 1-based output row (header = 1)
+        out_row: usize,
+        /// The repeated value
+        value: i64,
+    },
+    /// A sequence column value is not an integer
+    SequenceNotNumeric {
+        /// 1-based output row (header = 1)
+        out_row: usize,
+        /// The offending value
+        value: String,
+    },
+    /// In `NoHeaders` mode, the first row consists entirely of known header
+    /// names, suggesting the file actually has a header
+    PossibleHeaderRow {
+        /// Source line of the suspicious row
+        line: u64,
+    },
+    /// The input ended inside a quoted field
+    UnterminatedQuote {
+        /// Source line on which the affected record starts
+        line: u64,
+    },
+    /// A record wider than the expected column count was discarded
+    OverLengthRow {
+        /// Source line on which the record starts
+        line: u64,
+        /// Number of fields the record had
+        got: usize,
+        /// Expected column count
+        expected: usize,
+    },
+    /// The fragments of a split record ran out before it was complete (at a
+    /// section boundary, or at the start of the input in reverse mode) and
+    /// were discarded
+    TrailingIncompleteRow {
+        /// Source line on which the record starts
+        line: u64,
+    },
+    /// A stitched field holds more embedded newlines than `max_newlines_per_field`
+    RunawayField {
+        /// Source line on which the affected record starts
+        line: u64,
+        /// 0-based column index
+        column: usize,
+        /// Number of newlines in the field
+        newlines: usize,
+    },
+    /// A value does not match the type implied by its column's header suffix
+    TypeHintViolation {
+        /// 1-based output row (header = 1)
+        out_row: usize,
+        /// 0-based column index
+        column: usize,
+        /// Type declared by the header
+        expected: ColumnType,
+        /// The offending value
+        value: String,
+    },
+    /// Delimiter detection found several equally consistent candidates
+    AmbiguousDelimiter {
+        /// The delimiter used: a comma if it was among the tied candidates
+        chosen: Delimiter,
+        /// All tied candidates
+        tied: Vec<Delimiter>,
+    },
+    /// The column count differs from the one recorded in `history_file`
+    ColumnCountChanged {
+        /// Column count of the latest recorded run
+        previous: usize,
+        /// Column count of this run
+        current: usize,
+    },
+    /// The physical row widths differ markedly from those recorded in `history_file`
+    WidthDistributionDiverged {
+        /// Number of recorded runs compared against
+        runs: usize,
+        /// Share of rows whose width would have to change to match the
+        /// recorded distribution, in percent
+        divergence_percent: u32,
+    },
+}
+
+impl fmt::Display for Warning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Warning::SequenceGap { out_row, expected, found } => {
+                write!(f, "row {out_row}: sequence gap, expected {expected} but found {found}")
+            }
+            Warning::SequenceDuplicate { out_row, value } => {
+                write!(f, "row {out_row}: duplicate sequence value {value}")
+            }
+            Warning::SequenceNotNumeric { out_row, value } => {
+                write!(f, "row {out_row}: sequence value '{value}' is not an integer")
+            }
+            Warning::PossibleHeaderRow { line } => {
+                write!(f, "line {line}: row matches known header names; the file may have headers")
+            }
+            Warning::UnterminatedQuote { line } => {
+                write!(f, "line {line}: quoted field is never closed before the end of the file")
+            }
+            Warning::OverLengthRow { line, got, expected } => {
+                write!(f, "line {line}: row has {got} fields instead of {expected} and was discarded")
+            }
+            Warning::TrailingIncompleteRow { line } => {
+                write!(f, "line {line}: record is incomplete at the end of the input and was discarded")
+            }
+            Warning::RunawayField { line, column, newlines } => {
+                write!(f, "line {line}, column {column}: stitched field spans {newlines} newlines")
+            }
+            Warning::TypeHintViolation { out_row, column, expected, value } => {
+                write!(f, "row {out_row}, column {column}: '{value}' is not a valid {} value", expected.prefix())
+            }
+            Warning::AmbiguousDelimiter { chosen, tied } => {
+                let tied: Vec<String> = tied.iter().map(|d| format!("{:?}", char::from(d.as_byte()))).collect();
+                write!(f, "delimiter detection tied between {}; using {:?}", tied.join(", "), char::from(chosen.as_byte()))
+            }
+            Warning::ColumnCountChanged { previous, current } => {
+                write!(f, "column count changed from {previous} in earlier runs to {current}; the upstream format may have changed")
+            }
+            Warning::WidthDistributionDiverged { runs, divergence_percent } => {
+                write!(f, "row widths differ by {divergence_percent}% from the {runs} recorded runs; the upstream format may have changed")
+            }
+        }
+    }
+}